@@ -0,0 +1,145 @@
+use iced::{
+    advanced::{self, graphics::core::event::Status, layout, overlay, widget, Widget},
+    Element,
+};
+
+/// Draws `content` in a layer of its own, above any [`VideoPlayer`](crate::VideoPlayer) drawn before it.
+///
+/// The video is a `wgpu` primitive, which is drawn after the quads (backgrounds, borders) of its layer;
+/// so widgets drawn after a player in the same layer go beneath the frame where they overlap it.
+/// Wrap such widgets (e.g., a floating panel or a dialog) in this, as [`iced::widget::Stack`] does
+/// for its later children. Overlays such as menus and tooltips are always drawn above.
+pub struct AboveVideo<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+}
+
+impl<'a, Message, Theme, Renderer> AboveVideo<'a, Message, Theme, Renderer> {
+    /// Creates a new layer drawing `content` above the videos drawn before it.
+    pub fn new(content: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        AboveVideo {
+            content: content.into(),
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for AboveVideo<'a, Message, Theme, Renderer>
+where
+    Renderer: advanced::Renderer,
+{
+    fn size(&self) -> iced::Size<iced::Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> iced::Size<iced::Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn tag(&self) -> widget::tree::Tag {
+        self.content.as_widget().tag()
+    }
+
+    fn state(&self) -> widget::tree::State {
+        self.content.as_widget().state()
+    }
+
+    fn children(&self) -> Vec<widget::Tree> {
+        self.content.as_widget().children()
+    }
+
+    fn diff(&self, tree: &mut widget::Tree) {
+        self.content.as_widget().diff(tree);
+    }
+
+    fn layout(
+        &self,
+        tree: &mut widget::Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content.as_widget().layout(tree, renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &widget::Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &advanced::renderer::Style,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        viewport: &iced::Rectangle,
+    ) {
+        // layers are drawn after the one they were started in, so this goes above the video
+        renderer.with_layer(layout.bounds(), |renderer| {
+            self.content
+                .as_widget()
+                .draw(tree, renderer, theme, style, layout, cursor, viewport);
+        });
+    }
+
+    fn operate(
+        &self,
+        tree: &mut widget::Tree,
+        layout: advanced::Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(tree, layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut widget::Tree,
+        event: iced::Event,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn advanced::Clipboard,
+        shell: &mut advanced::Shell<'_, Message>,
+        viewport: &iced::Rectangle,
+    ) -> Status {
+        self.content.as_widget_mut().on_event(
+            tree, event, layout, cursor, renderer, clipboard, shell, viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &widget::Tree,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        viewport: &iced::Rectangle,
+        renderer: &Renderer,
+    ) -> advanced::mouse::Interaction {
+        self.content
+            .as_widget()
+            .mouse_interaction(tree, layout, cursor, viewport, renderer)
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut widget::Tree,
+        layout: advanced::Layout<'_>,
+        renderer: &Renderer,
+        translation: iced::Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(tree, layout, renderer, translation)
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<AboveVideo<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: 'a + advanced::Renderer,
+{
+    fn from(above_video: AboveVideo<'a, Message, Theme, Renderer>) -> Self {
+        Self::new(above_video)
+    }
+}
@@ -0,0 +1,138 @@
+use gstreamer as gst;
+use std::time::{Duration, Instant};
+
+/// Receives playback lifecycle events of a [`Video`](crate::Video) for quality-of-experience telemetry,
+/// see [`Video::set_analytics_sink`](crate::Video::set_analytics_sink).
+///
+/// Every method does nothing by default, so sinks only implement what they report.
+/// Events are gathered while a [`VideoPlayer`](crate::VideoPlayer) shows the video.
+pub trait AnalyticsSink: Send {
+    /// The media loaded (i.e., the first frame was ready) `load_time` after the video was created.
+    fn loaded(&mut self, load_time: Duration) {
+        let _ = load_time;
+    }
+
+    /// Playback stalled to refill the network buffer, for the `count`th time.
+    fn rebuffer_started(&mut self, count: u32) {
+        let _ = count;
+    }
+
+    /// Playback resumed after stalling for `duration`.
+    fn rebuffer_ended(&mut self, duration: Duration) {
+        let _ = duration;
+    }
+
+    /// The stream switched to another bitrate (in bits per second), e.g. an adaptive stream changing variants,
+    /// as reported by the demuxer's tags.
+    fn bitrate_changed(&mut self, bitrate: u32) {
+        let _ = bitrate;
+    }
+
+    /// Playback failed with `error`.
+    fn error(&mut self, error: &glib::Error) {
+        let _ = error;
+    }
+
+    /// Playback stopped (paused, stalled, ended or the video went away), having played for `total` so far.
+    fn watch_time(&mut self, total: Duration) {
+        let _ = total;
+    }
+}
+
+/// An [`AnalyticsSink`] along with the state needed to derive its events.
+pub(crate) struct Analytics {
+    sink: Box<dyn AnalyticsSink>,
+    rebuffers: u32,
+    rebuffering_since: Option<Instant>,
+    bitrate: Option<u32>,
+    playing: bool,
+    playing_since: Option<Instant>,
+    watch_time: Duration,
+}
+
+impl std::fmt::Debug for Analytics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Analytics")
+            .field("rebuffers", &self.rebuffers)
+            .field("bitrate", &self.bitrate)
+            .field("watch_time", &self.watch_time)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Analytics {
+    pub(crate) fn new(mut sink: Box<dyn AnalyticsSink>, load_time: Duration) -> Self {
+        sink.loaded(load_time);
+        Analytics {
+            sink,
+            rebuffers: 0,
+            rebuffering_since: None,
+            bitrate: None,
+            playing: false,
+            playing_since: None,
+            watch_time: Duration::ZERO,
+        }
+    }
+
+    /// Follows whether the video is `playing`, counting the time it is towards the watch time.
+    pub(crate) fn update(&mut self, playing: bool) {
+        self.playing = playing;
+        self.watch();
+    }
+
+    /// Follows a buffering message filled to `percent`.
+    pub(crate) fn buffering(&mut self, percent: i32) {
+        match (self.rebuffering_since, percent < 100) {
+            (None, true) => {
+                self.rebuffers += 1;
+                self.rebuffering_since = Some(Instant::now());
+                self.sink.rebuffer_started(self.rebuffers);
+            }
+            (Some(since), false) => {
+                self.rebuffering_since = None;
+                self.sink.rebuffer_ended(since.elapsed());
+            }
+            _ => return,
+        }
+        self.watch();
+    }
+
+    /// Follows the tags posted by a demuxer, reporting a change of the stream's bitrate.
+    pub(crate) fn tags(&mut self, tags: &gst::TagListRef) {
+        let bitrate = tags
+            .get::<gst::tags::Bitrate>()
+            .or_else(|| tags.get::<gst::tags::NominalBitrate>())
+            .map(|bitrate| bitrate.get());
+        if let Some(bitrate) = bitrate.filter(|&bitrate| Some(bitrate) != self.bitrate) {
+            self.bitrate = Some(bitrate);
+            self.sink.bitrate_changed(bitrate);
+        }
+    }
+
+    pub(crate) fn error(&mut self, error: &glib::Error) {
+        self.sink.error(error);
+    }
+
+    /// Starts or stops counting watch time, as playback (without stalls) starts or stops.
+    fn watch(&mut self) {
+        let watching = self.playing && self.rebuffering_since.is_none();
+        match (self.playing_since, watching) {
+            (None, true) => self.playing_since = Some(Instant::now()),
+            (Some(_), false) => self.stop_watching(),
+            _ => {}
+        }
+    }
+
+    fn stop_watching(&mut self) {
+        if let Some(since) = self.playing_since.take() {
+            self.watch_time += since.elapsed();
+            self.sink.watch_time(self.watch_time);
+        }
+    }
+}
+
+impl Drop for Analytics {
+    fn drop(&mut self) {
+        self.stop_watching();
+    }
+}
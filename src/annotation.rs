@@ -0,0 +1,176 @@
+use iced::{Color, Point, Rectangle, Size, Vector};
+
+/// A shape drawn over the video, see [`VideoPlayer::annotations`](crate::VideoPlayer::annotations).
+///
+/// Coordinates are normalized to the video frame, from `(0.0, 0.0)` at its top-left corner
+/// to `(1.0, 1.0)` at its bottom-right corner, so annotations stay aligned however the video is fit.
+/// Widths and sizes are in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    /// A straight line.
+    Line {
+        from: Point,
+        to: Point,
+        width: f32,
+        color: Color,
+    },
+    /// A rectangle, outlined `width` thick or filled if `width` is `None`.
+    Rect {
+        bounds: Rectangle,
+        width: Option<f32>,
+        color: Color,
+    },
+    /// A closed polygon, outlined `width` thick or filled if `width` is `None`.
+    /// Only convex polygons can be filled.
+    Polygon {
+        points: Vec<Point>,
+        width: Option<f32>,
+        color: Color,
+    },
+    /// A label with its top-left corner at `position`.
+    Text {
+        position: Point,
+        content: String,
+        size: f32,
+        color: Color,
+    },
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Vertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+impl Vertex {
+    /// Moves the vertex from the clip space of the video into that of the render target,
+    /// where the video spans `rect` (left, top, right, bottom).
+    pub(crate) fn placed(self, rect: [f32; 4]) -> Vertex {
+        let [x, y] = self.position;
+        Vertex {
+            position: [
+                rect[0] + (x + 1.0) / 2.0 * (rect[2] - rect[0]),
+                rect[3] + (y + 1.0) / 2.0 * (rect[1] - rect[3]),
+            ],
+            ..self
+        }
+    }
+}
+
+/// Turns the shapes of `annotations` into triangles in clip space, for a video drawn `size` big.
+/// Text is left to the text renderer.
+pub(crate) fn tessellate(annotations: &[Annotation], size: Size) -> Vec<Vertex> {
+    let mut vertices = Vec::new();
+    if size.width <= 0.0 || size.height <= 0.0 {
+        return vertices;
+    }
+
+    // shapes are built in logical pixels so line widths don't stretch with the aspect ratio
+    let scale = |point: &Point| Point::new(point.x * size.width, point.y * size.height);
+
+    for annotation in annotations {
+        match annotation {
+            Annotation::Line {
+                from,
+                to,
+                width,
+                color,
+            } => line(&mut vertices, scale(from), scale(to), *width, *color, size),
+            Annotation::Rect {
+                bounds,
+                width,
+                color,
+            } => {
+                let corners = [
+                    bounds.position(),
+                    Point::new(bounds.x + bounds.width, bounds.y),
+                    Point::new(bounds.x + bounds.width, bounds.y + bounds.height),
+                    Point::new(bounds.x, bounds.y + bounds.height),
+                ];
+                polygon(
+                    &mut vertices,
+                    corners.iter().map(scale).collect(),
+                    *width,
+                    *color,
+                    size,
+                );
+            }
+            Annotation::Polygon {
+                points,
+                width,
+                color,
+            } => polygon(
+                &mut vertices,
+                points.iter().map(scale).collect(),
+                *width,
+                *color,
+                size,
+            ),
+            Annotation::Text { .. } => {}
+        }
+    }
+
+    vertices
+}
+
+fn polygon(
+    vertices: &mut Vec<Vertex>,
+    points: Vec<Point>,
+    width: Option<f32>,
+    color: Color,
+    size: Size,
+) {
+    if points.len() < 2 {
+        return;
+    }
+
+    match width {
+        Some(width) => {
+            for (i, &from) in points.iter().enumerate() {
+                let to = points[(i + 1) % points.len()];
+                line(vertices, from, to, width, color, size);
+            }
+        }
+        None => {
+            // a fan covers convex polygons exactly
+            for pair in points[1..].windows(2) {
+                for &point in [points[0], pair[0], pair[1]].iter() {
+                    vertices.push(vertex(point, color, size));
+                }
+            }
+        }
+    }
+}
+
+fn line(vertices: &mut Vec<Vertex>, from: Point, to: Point, width: f32, color: Color, size: Size) {
+    let direction = to - from;
+    let length = direction.x.hypot(direction.y);
+    if length == 0.0 || width <= 0.0 {
+        return;
+    }
+
+    // extend by half the width on both ends so outlines have closed corners
+    let along = direction * (width / 2.0 / length);
+    let normal = Vector::new(-along.y, along.x);
+    let corners = [
+        from - along + normal,
+        to + along + normal,
+        to + along - normal,
+        from - along - normal,
+    ];
+    for i in [0, 1, 2, 0, 2, 3] {
+        vertices.push(vertex(corners[i], color, size));
+    }
+}
+
+fn vertex(point: Point, color: Color, size: Size) -> Vertex {
+    Vertex {
+        position: [
+            point.x / size.width * 2.0 - 1.0,
+            1.0 - point.y / size.height * 2.0,
+        ],
+        // linear, so blending on sRGB targets is correct; the shader encodes them for other targets
+        color: color.into_linear(),
+    }
+}
@@ -0,0 +1,28 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // colors were made linear when tessellated
+    if LINEAR_OUTPUT {
+        return in.color;
+    }
+    return vec4<f32>(srgb_encode(in.color.rgb), in.color.a);
+}
+
+// sRGB encoding, for targets that don't encode on write
+fn srgb_encode(linear: vec3<f32>) -> vec3<f32> {
+    let lo = linear * 12.92;
+    let hi = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(hi, lo, linear <= vec3<f32>(0.0031308));
+}
@@ -0,0 +1,114 @@
+use crate::AudioDeviceEvent;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Events not yet handled by one subscriber.
+type Queue = Mutex<VecDeque<AudioDeviceEvent>>;
+type Subscribers = Mutex<Vec<Weak<Queue>>>;
+
+/// Changes kept for a subscriber whose player isn't handling events (e.g., while hidden); older ones are dropped.
+const MAX_QUEUED: usize = 32;
+
+/// Watches audio outputs being plugged/unplugged, for every video with a
+/// [`VideoPlayer::on_audio_device_changed`](crate::VideoPlayer::on_audio_device_changed) handler.
+///
+/// Only one runs at a time, started by the first subscriber and stopped with the last.
+#[derive(Debug)]
+struct Monitor {
+    monitor: gst::DeviceMonitor,
+    started: bool,
+    subscribers: Arc<Subscribers>,
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        if self.started {
+            self.monitor.stop();
+        }
+    }
+}
+
+/// Audio device changes seen by one video, see [`AudioDevices::subscribe`].
+#[derive(Debug)]
+pub(crate) struct AudioDevices {
+    events: Arc<Queue>,
+    _monitor: Arc<Monitor>,
+}
+
+impl AudioDevices {
+    /// Starts getting the audio device changes from now on, starting the shared monitor if it isn't running.
+    pub(crate) fn subscribe() -> Self {
+        static MONITOR: Mutex<Option<Weak<Monitor>>> = Mutex::new(None);
+
+        let events = Arc::new(Queue::default());
+        let mut shared = MONITOR
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(monitor) = shared.as_ref().and_then(Weak::upgrade) {
+            if let Ok(mut subscribers) = monitor.subscribers.lock() {
+                subscribers.push(Arc::downgrade(&events));
+            }
+            return AudioDevices {
+                events,
+                _monitor: monitor,
+            };
+        }
+
+        let subscribers = Arc::new(Mutex::new(vec![Arc::downgrade(&events)]));
+        let subscribers_ref = Arc::clone(&subscribers);
+        let monitor = gst::DeviceMonitor::new();
+        monitor.add_filter(Some("Audio/Sink"), None);
+        // every subscriber gets every change, so they're handed out as they're posted
+        monitor.bus().set_sync_handler(move |_, msg| {
+            let event = match msg.view() {
+                gst::MessageView::DeviceAdded(added) => {
+                    AudioDeviceEvent::Added(added.device().display_name().to_string())
+                }
+                gst::MessageView::DeviceRemoved(removed) => {
+                    AudioDeviceEvent::Removed(removed.device().display_name().to_string())
+                }
+                _ => return gst::BusSyncReply::Drop,
+            };
+            if let Ok(mut subscribers) = subscribers_ref.lock() {
+                subscribers.retain(|events| match events.upgrade() {
+                    Some(events) => {
+                        if let Ok(mut events) = events.lock() {
+                            if events.len() == MAX_QUEUED {
+                                events.pop_front();
+                            }
+                            events.push_back(event.clone());
+                        }
+                        true
+                    }
+                    None => false,
+                });
+            }
+            gst::BusSyncReply::Drop
+        });
+        let started = match monitor.start() {
+            Ok(()) => true,
+            Err(err) => {
+                log::warn!("cannot monitor audio devices: {err}");
+                false
+            }
+        };
+
+        let monitor = Arc::new(Monitor {
+            monitor,
+            started,
+            subscribers,
+        });
+        *shared = Some(Arc::downgrade(&monitor));
+        AudioDevices {
+            events,
+            _monitor: monitor,
+        }
+    }
+
+    /// Takes the oldest change not handled yet.
+    pub(crate) fn pop(&self) -> Option<AudioDeviceEvent> {
+        self.events.lock().ok()?.pop_front()
+    }
+}
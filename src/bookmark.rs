@@ -0,0 +1,40 @@
+use crate::Cue;
+use iced::widget::image as img;
+use std::time::Duration;
+
+/// A frame of the media marked by the user, see [`Video::add_bookmark`](crate::Video::add_bookmark).
+///
+/// With the `serde` feature, bookmarks (de)serialize to persist them between sessions,
+/// and are restored with [`Video::set_bookmarks`](crate::Video::set_bookmarks).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bookmark {
+    /// Identifies the bookmark among the video's bookmarks.
+    pub id: u64,
+    /// Label given to the bookmark.
+    pub label: String,
+    /// Presentation timestamp of the bookmarked frame.
+    pub position: Duration,
+    /// Number of the bookmarked frame, at the video's framerate.
+    pub frame: u64,
+    /// The bookmarked frame downscaled to about 160 pixels wide, PNG-encoded;
+    /// empty if it couldn't be captured (e.g., the video is capture protected).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thumbnail_png: Vec<u8>,
+}
+
+impl Bookmark {
+    /// Width thumbnails are downscaled towards.
+    pub(crate) const THUMBNAIL_WIDTH: u32 = 160;
+
+    /// Get the thumbnail of the bookmarked frame as an image, if it was captured.
+    pub fn thumbnail(&self) -> Option<img::Handle> {
+        (!self.thumbnail_png.is_empty())
+            .then(|| img::Handle::from_bytes(self.thumbnail_png.clone()))
+    }
+
+    /// Get the bookmark as a [`Cue`] to mark it on a [`SeekBar`](crate::SeekBar).
+    pub fn cue(&self) -> Cue {
+        Cue::new(self.position, self.label.clone())
+    }
+}
@@ -0,0 +1,69 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A chapter of the media (e.g., from MKV or MP4 chapter markers), see [`Video::chapters`](crate::Video::chapters).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chapter {
+    /// Title of the chapter, if the media names it.
+    pub title: Option<String>,
+    /// Where the chapter starts.
+    pub start: Duration,
+    /// Where the chapter ends, zero if unknown.
+    pub end: Duration,
+}
+
+/// Watches the table of contents reaching `pad` and keeps its chapters in `chapters`.
+pub(crate) fn watch_chapters(pad: &gst::Pad, chapters: Arc<Mutex<Vec<Chapter>>>) {
+    // the demuxer may have sent it before the watch was set up
+    if let Some(toc) = pad.sticky_event::<gst::event::Toc>(0) {
+        if let Ok(mut chapters) = chapters.lock() {
+            *chapters = from_toc(toc.toc().0);
+        }
+    }
+
+    pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_pad, info| {
+        if let Some(gst::EventView::Toc(toc)) = info.event().map(|event| event.view()) {
+            if let Ok(mut chapters) = chapters.lock() {
+                *chapters = from_toc(toc.toc().0);
+            }
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+/// The top level chapters of `toc`, sorted by start; editions are looked into, sub-chapters aren't.
+fn from_toc(toc: &gst::TocRef) -> Vec<Chapter> {
+    fn collect(entries: Vec<gst::TocEntry>, chapters: &mut Vec<Chapter>) {
+        for entry in entries {
+            if entry.entry_type() != gst::TocEntryType::Chapter {
+                collect(entry.sub_entries(), chapters);
+                continue;
+            }
+            let (start, stop) = entry.start_stop_times().unwrap_or((0, -1));
+            let title = entry.tags().and_then(|tags| {
+                tags.get::<gst::tags::Title>()
+                    .map(|title| title.get().to_owned())
+            });
+            chapters.push(Chapter {
+                title,
+                start: Duration::from_nanos(start.max(0) as u64),
+                end: Duration::from_nanos(stop.max(0) as u64),
+            });
+        }
+    }
+
+    let mut chapters = Vec::new();
+    collect(toc.entries(), &mut chapters);
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// Index of the chapter playing at `position`.
+pub(crate) fn chapter_at(chapters: &[Chapter], position: Duration) -> Option<usize> {
+    let next = chapters.partition_point(|chapter| chapter.start <= position);
+    let index = next.checked_sub(1)?;
+    let chapter = &chapters[index];
+    (chapter.end.is_zero() || position < chapter.end).then_some(index)
+}
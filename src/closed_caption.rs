@@ -0,0 +1,106 @@
+use crate::Error;
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use gstreamer_app::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// CEA-608 closed caption channel to decode, see [`VideoBuilder::closed_captions`](crate::VideoBuilder::closed_captions).
+///
+/// Each field of the video carries two channels; only the primary one of each can be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CaptionChannel {
+    /// The primary captions of the first field, usually the main language.
+    #[default]
+    Cc1,
+    /// The primary captions of the second field, usually a second language.
+    Cc3,
+}
+
+impl CaptionChannel {
+    fn field(self) -> i32 {
+        match self {
+            CaptionChannel::Cc1 => 0,
+            CaptionChannel::Cc3 => 1,
+        }
+    }
+}
+
+/// The closed caption on screen, and whether it changed since it was last reported.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Captions {
+    pub(crate) text: Arc<Mutex<Option<String>>>,
+    pub(crate) updated: Arc<AtomicBool>,
+}
+
+/// Decodes the captions `extractor` (a `ccextractor` in `bin`) finds in frames into `captions`,
+/// once its caption pad shows up.
+pub(crate) fn decode_captions(
+    bin: &gst::Bin,
+    extractor: &gst::Element,
+    channel: CaptionChannel,
+    captions: Captions,
+) {
+    // the extractor lives in the bin, so holding on to the bin would keep both alive forever
+    let bin = bin.downgrade();
+    extractor.connect_pad_added(move |_, pad| {
+        let Some(bin) = bin.upgrade() else {
+            return;
+        };
+        if pad.name() != "caption" {
+            return;
+        }
+        if let Err(err) = link_decoder(&bin, pad, channel, captions.clone()) {
+            log::warn!("cannot decode closed captions: {err}");
+        }
+    });
+}
+
+fn link_decoder(
+    bin: &gst::Bin,
+    pad: &gst::Pad,
+    channel: CaptionChannel,
+    captions: Captions,
+) -> Result<(), Error> {
+    let converter = gst::ElementFactory::make("ccconverter").build()?;
+    let capsfilter = gst::ElementFactory::make("capsfilter")
+        .property(
+            "caps",
+            gst::Caps::builder("closedcaption/x-cea-608")
+                .field("format", "raw")
+                .field("field", channel.field())
+                .build(),
+        )
+        .build()?;
+    let decoder = gst::ElementFactory::make("cea608tott").build()?;
+    let sink = gst_app::AppSink::builder()
+        .caps(&gst::Caps::builder("text/x-raw").build())
+        .sync(true)
+        .callbacks(
+            gst_app::AppSinkCallbacks::builder()
+                .new_sample(move |sink| {
+                    let sample = sink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                    let buffer = sample.buffer().ok_or(gst::FlowError::Error)?;
+                    let map = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+                    let text = String::from_utf8_lossy(&map).trim().to_owned();
+                    if let Ok(mut current) = captions.text.lock() {
+                        *current = (!text.is_empty()).then_some(text);
+                    }
+                    captions.updated.store(true, Ordering::SeqCst);
+                    Ok(gst::FlowSuccess::Ok)
+                })
+                .build(),
+        )
+        .build();
+
+    let elements = [&converter, &capsfilter, &decoder, sink.upcast_ref()];
+    bin.add_many(elements)?;
+    gst::Element::link_many(elements)?;
+    for element in elements {
+        element.sync_state_with_parent()?;
+    }
+    let sink_pad = converter.static_pad("sink").ok_or(Error::Caps)?;
+    pad.link(&sink_pad)
+        .map_err(|_| Error::Element("ccconverter".into()))?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+/// Keeps rated content from being watched until unlocked (e.g., with a parental PIN),
+/// see [`VideoBuilder::content_gate`](crate::VideoBuilder::content_gate) and [`Video::unlock_content`](crate::Video::unlock_content).
+///
+/// While locked, the lock is enforced by the video itself: nothing started through [`Video`](crate::Video)
+/// or [`VideoPlayer`](crate::VideoPlayer) (autoplay, media keys, clicks) can get around it.
+#[derive(Clone)]
+pub struct ContentGate {
+    pub(crate) rating: String,
+    pub(crate) obscure: bool,
+    pub(crate) unlock: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+impl ContentGate {
+    /// Creates a gate for content with the given `rating` (e.g., `"PG-13"` or `"18"`).
+    ///
+    /// `unlock` is called with the rating when unlocking is attempted, and approves it by returning `true`.
+    pub fn new<F>(rating: impl Into<String>, unlock: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        ContentGate {
+            rating: rating.into(),
+            obscure: false,
+            unlock: Arc::new(unlock),
+        }
+    }
+
+    /// Sets if locked content may play pixelated and muted, rather than not at all.
+    pub fn obscure(self, obscure: bool) -> Self {
+        ContentGate { obscure, ..self }
+    }
+
+    /// Get the rating of the content.
+    pub fn rating(&self) -> &str {
+        &self.rating
+    }
+}
+
+impl std::fmt::Debug for ContentGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContentGate")
+            .field("rating", &self.rating)
+            .field("obscure", &self.obscure)
+            .finish_non_exhaustive()
+    }
+}
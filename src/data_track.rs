@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+/// A sample of time-synced data (e.g., GPS coordinates, speed or other sensor readings),
+/// see [`Video::set_data_track`](crate::Video::set_data_track).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DataSample {
+    /// Presentation timestamp of the frame the sample belongs to.
+    pub position: Duration,
+    /// The values, in the same order for every sample of a track.
+    pub values: Vec<f64>,
+}
+
+/// How samples of a data track are matched to frames between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DataInterpolation {
+    /// Keep the latest sample until the next one.
+    #[default]
+    Hold,
+    /// Take the sample closest in time.
+    Nearest,
+    /// Linearly interpolate the values of the samples around the frame.
+    Linear,
+}
+
+/// The sample for a frame at `position`, or `None` before the first sample.
+/// `samples` must be sorted by position.
+pub(crate) fn sample_at(
+    samples: &[DataSample],
+    position: Duration,
+    interpolation: DataInterpolation,
+) -> Option<DataSample> {
+    let next = samples.partition_point(|sample| sample.position <= position);
+    let before = samples.get(next.checked_sub(1)?)?;
+    let Some(after) = samples.get(next) else {
+        // past the last sample, the track has nothing newer to offer
+        return Some(DataSample {
+            position,
+            values: before.values.clone(),
+        });
+    };
+
+    let span = (after.position - before.position).as_secs_f64();
+    let t = (position - before.position).as_secs_f64() / span;
+    let values = match interpolation {
+        DataInterpolation::Hold => before.values.clone(),
+        DataInterpolation::Nearest if t < 0.5 => before.values.clone(),
+        DataInterpolation::Nearest => after.values.clone(),
+        DataInterpolation::Linear => before
+            .values
+            .iter()
+            .zip(&after.values)
+            .map(|(a, b)| a + (b - a) * t)
+            .collect(),
+    };
+
+    Some(DataSample { position, values })
+}
@@ -0,0 +1,333 @@
+use ash::vk;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_allocators as gst_allocators;
+use gstreamer_app as gst_app;
+use gstreamer_video as gst_video;
+use iced_wgpu::wgpu;
+use std::os::fd::RawFd;
+use std::sync::OnceLock;
+
+/// Caps preferred over system memory when DMA-BUF import is enabled, see
+/// [`VideoBuilder::dmabuf`](crate::VideoBuilder::dmabuf).
+///
+/// Only linear NV12 is asked for, as it's the layout [`import`] handles
+/// (GStreamer before 1.24 describes it with `format`, newer versions with `drm-format`).
+pub(crate) const CAPS: [&str; 2] = [
+    "video/x-raw(memory:DMABuf),format=NV12",
+    "video/x-raw(memory:DMABuf),format=DMA_DRM,drm-format=NV12",
+];
+
+/// Frames the renderer keeps imported at once (the current and the previous one), each holding on to
+/// its decoder buffer.
+pub(crate) const IMPORTED_FRAMES: u32 = 2;
+
+/// Whether `caps` are for frames in DMA-BUFs.
+pub(crate) fn is_dmabuf(caps: &gst::CapsRef) -> bool {
+    caps.features(0)
+        .is_some_and(|features| features.contains(gst_allocators::CAPS_FEATURE_MEMORY_DMABUF))
+}
+
+/// Tells decoders upstream of `sink` it reads the layout of DMA-BUF frames from their video meta, which
+/// they need, as their planes are padded to what the hardware wants. System memory frames are left
+/// tightly packed, as that is how they are read.
+pub(crate) fn request_video_meta(sink: &gst_app::AppSink) {
+    let Some(pad) = sink.static_pad("sink") else {
+        return;
+    };
+    pad.add_probe(gst::PadProbeType::QUERY_DOWNSTREAM, |_, info| {
+        let Some(gst::PadProbeData::Query(ref mut query)) = info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let gst::QueryViewMut::Allocation(allocation) = query.view_mut() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        if allocation.get().0.is_some_and(is_dmabuf) {
+            allocation.add_allocation_meta::<gst_video::VideoMeta>(None);
+        }
+        // left for the other probes, see `frame::reserve_buffers`
+        gst::PadProbeReturn::Ok
+    });
+}
+
+/// An NV12 frame left in the decoder's DMA-BUF, for the renderer to import without copying it.
+pub(crate) struct DmaBufFrame {
+    buffer: gst::Buffer,
+    size: (u32, u32),
+    /// File descriptor, byte offset and row stride of the luma and chroma planes.
+    planes: [(RawFd, u64, u32); 2],
+    /// Offsets of the planes in the buffer, as a whole.
+    offsets: [usize; 2],
+    // copied out the first time something reads the frame on the CPU
+    bytes: OnceLock<Vec<u8>>,
+}
+
+impl std::fmt::Debug for DmaBufFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DmaBufFrame")
+            .field("size", &self.size)
+            .field("planes", &self.planes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DmaBufFrame {
+    /// Reads where the planes of `buffer`, a frame `size` pixels big, are if its memory is DMA-BUF.
+    pub(crate) fn new(buffer: &gst::Buffer, size: (u32, u32)) -> Option<Self> {
+        let meta = buffer.meta::<gst_video::VideoMeta>()?;
+        if meta.n_planes() != 2 {
+            return None;
+        }
+        let mut planes = [(0, 0, 0); 2];
+        let offsets = [meta.offset()[0], meta.offset()[1]];
+        for (plane, (layout, offset)) in planes.iter_mut().zip(offsets).enumerate() {
+            let (memories, skip) = buffer.find_memory(offset..offset + 1)?;
+            let memory = buffer
+                .peek_memory(memories.start)
+                .downcast_memory_ref::<gst_allocators::DmaBufMemory>()?;
+            *layout = (
+                memory.fd(),
+                (memory.offset() + skip) as u64,
+                u32::try_from(meta.stride()[plane]).ok()?,
+            );
+        }
+        Some(DmaBufFrame {
+            buffer: buffer.clone(),
+            size,
+            planes,
+            offsets,
+            bytes: OnceLock::new(),
+        })
+    }
+
+    /// Size of the frame in pixels, as `(width, height)`.
+    pub(crate) fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// The frame's bytes, laid out without padding like mapped frames, read from the DMA-BUF the first time
+    /// (which is slow).
+    pub(crate) fn bytes(&self) -> &[u8] {
+        self.bytes.get_or_init(|| {
+            let Ok(map) = self.buffer.map_readable() else {
+                return Vec::new();
+            };
+            let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+            let rows = [height, height.div_ceil(2)];
+            let mut bytes = Vec::with_capacity(width * (rows[0] + rows[1]));
+            for (plane, rows) in rows.into_iter().enumerate() {
+                let stride = self.planes[plane].2 as usize;
+                for row in 0..rows {
+                    let start = self.offsets[plane] + row * stride;
+                    let row = map
+                        .get(start..start + width.min(stride))
+                        .unwrap_or_default();
+                    bytes.extend_from_slice(row);
+                    bytes.resize(bytes.len() + width - row.len(), 0);
+                }
+            }
+            bytes
+        })
+    }
+}
+
+/// Imports the luma and chroma planes of `frame` as textures on `device`.
+///
+/// Returns `None` unless `device` is a Vulkan device with DMA-BUF import enabled, i.e. the
+/// `VK_KHR_external_memory_fd`, `VK_EXT_external_memory_dma_buf` and `VK_EXT_image_drm_format_modifier`
+/// extensions, or if the driver refuses the buffer.
+pub(crate) fn import(
+    device: &wgpu::Device,
+    frame: &DmaBufFrame,
+) -> Option<(wgpu::Texture, wgpu::Texture)> {
+    let (width, height) = frame.size;
+    let planes = [
+        (wgpu::TextureFormat::R8Unorm, (width, height)),
+        (
+            wgpu::TextureFormat::Rg8Unorm,
+            (width.div_ceil(2), height.div_ceil(2)),
+        ),
+    ];
+    let [luma, chroma] = [0, 1].map(|plane| {
+        let (format, size) = planes[plane];
+        // SAFETY: the image is created for the descriptor, on the device it's handed to
+        unsafe {
+            let hal_texture = device
+                .as_hal::<wgpu::hal::api::Vulkan, _, _>(|hal| {
+                    import_plane(hal?, frame, plane, format, size)
+                })
+                .flatten()?;
+            Some(device.create_texture_from_hal::<wgpu::hal::api::Vulkan>(
+                hal_texture,
+                &wgpu::TextureDescriptor {
+                    label: Some("iced_video_player imported texture"),
+                    size: wgpu::Extent3d {
+                        width: size.0,
+                        height: size.1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            ))
+        }
+    });
+    Some((luma?, chroma?))
+}
+
+/// Creates an image over a plane of `frame`, bound to memory imported from its DMA-BUF.
+///
+/// # Safety
+///
+/// `device` has to be the device the image will be used on.
+unsafe fn import_plane(
+    device: &wgpu::hal::vulkan::Device,
+    frame: &DmaBufFrame,
+    plane: usize,
+    format: wgpu::TextureFormat,
+    (width, height): (u32, u32),
+) -> Option<wgpu::hal::vulkan::Texture> {
+    let extensions = device.enabled_device_extensions();
+    let required = [
+        vk::KhrExternalMemoryFdFn::name(),
+        vk::ExtExternalMemoryDmaBufFn::name(),
+        vk::ExtImageDrmFormatModifierFn::name(),
+    ];
+    if !required.iter().all(|name| extensions.contains(name)) {
+        return None;
+    }
+    let raw = device.raw_device();
+    let instance = device.shared_instance().raw_instance();
+    let (fd, offset, stride) = frame.planes[plane];
+
+    // linear, at the plane's offset in the buffer, rows `stride` bytes apart
+    let layouts = [vk::SubresourceLayout {
+        offset,
+        size: 0,
+        row_pitch: stride as u64,
+        array_pitch: 0,
+        depth_pitch: 0,
+    }];
+    let mut modifier = vk::ImageDrmFormatModifierExplicitCreateInfoEXT::builder()
+        .drm_format_modifier(0)
+        .plane_layouts(&layouts);
+    let mut external = vk::ExternalMemoryImageCreateInfo::builder()
+        .handle_types(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT);
+    let info = vk::ImageCreateInfo::builder()
+        .image_type(vk::ImageType::TYPE_2D)
+        .format(match format {
+            wgpu::TextureFormat::R8Unorm => vk::Format::R8_UNORM,
+            _ => vk::Format::R8G8_UNORM,
+        })
+        .extent(vk::Extent3D {
+            width,
+            height,
+            depth: 1,
+        })
+        .mip_levels(1)
+        .array_layers(1)
+        .samples(vk::SampleCountFlags::TYPE_1)
+        .tiling(vk::ImageTiling::DRM_FORMAT_MODIFIER_EXT)
+        .usage(vk::ImageUsageFlags::SAMPLED)
+        .sharing_mode(vk::SharingMode::EXCLUSIVE)
+        .initial_layout(vk::ImageLayout::UNDEFINED)
+        .push_next(&mut external)
+        .push_next(&mut modifier);
+    let image = raw.create_image(&info, None).ok()?;
+    let Some(memory) = import_memory(instance, device, image, fd) else {
+        raw.destroy_image(image, None);
+        return None;
+    };
+    if raw.bind_image_memory(image, memory, 0).is_err() {
+        raw.destroy_image(image, None);
+        raw.free_memory(memory, None);
+        return None;
+    }
+
+    let guard = Imported {
+        device: raw.clone(),
+        image,
+        memory,
+        _buffer: frame.buffer.clone(),
+    };
+    Some(wgpu::hal::vulkan::Device::texture_from_raw(
+        image,
+        &wgpu::hal::TextureDescriptor {
+            label: Some("iced_video_player imported texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::hal::TextureUses::RESOURCE,
+            memory_flags: wgpu::hal::MemoryFlags::empty(),
+            view_formats: Vec::new(),
+        },
+        Some(Box::new(guard)),
+    ))
+}
+
+/// Imports the whole DMA-BUF `fd` as memory `image` can be bound to.
+unsafe fn import_memory(
+    instance: &ash::Instance,
+    device: &wgpu::hal::vulkan::Device,
+    image: vk::Image,
+    fd: RawFd,
+) -> Option<vk::DeviceMemory> {
+    let raw = device.raw_device();
+    // Vulkan takes ownership of the descriptor it imports, while the buffer keeps its own
+    let fd = libc::dup(fd);
+    if fd < 0 {
+        return None;
+    }
+    let imported = (|| {
+        let size = libc::lseek(fd, 0, libc::SEEK_END);
+        let properties = ash::extensions::khr::ExternalMemoryFd::new(instance, raw)
+            .get_memory_fd_properties(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT, fd)
+            .ok()?;
+        let requirements = raw.get_image_memory_requirements(image);
+        let types = requirements.memory_type_bits & properties.memory_type_bits;
+        let memory_type = (0..u32::BITS).find(|i| types & (1 << i) != 0)?;
+        let mut import = vk::ImportMemoryFdInfoKHR::builder()
+            .handle_type(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT)
+            .fd(fd);
+        let mut dedicated = vk::MemoryDedicatedAllocateInfo::builder().image(image);
+        let info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(u64::try_from(size).ok()?.max(requirements.size))
+            .memory_type_index(memory_type)
+            .push_next(&mut import)
+            .push_next(&mut dedicated);
+        raw.allocate_memory(&info, None).ok()
+    })();
+    if imported.is_none() {
+        libc::close(fd);
+    }
+    imported
+}
+
+/// Keeps an imported image, its memory and the decoder's buffer until the texture is dropped.
+struct Imported {
+    device: ash::Device,
+    image: vk::Image,
+    memory: vk::DeviceMemory,
+    // the decoder doesn't reuse the buffer while it's held
+    _buffer: gst::Buffer,
+}
+
+impl Drop for Imported {
+    fn drop(&mut self) {
+        // SAFETY: `wgpu` drops the guard once the GPU is done with the texture
+        unsafe {
+            self.device.destroy_image(self.image, None);
+            self.device.free_memory(self.memory, None);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! DVB/ATSC TV reception through GStreamer's `dvb://` source (`dvbbasebin`).
+//!
+//! Channels come from a `channels.conf` produced by a scanner such as `dvbv5-scan` or `w_scan`,
+//! in either the legacy zap format or the DVBv5 format. Play a channel by loading its URI:
+//!
+//! ```no_run
+//! # fn main() -> Result<(), iced_video_player::Error> {
+//! let channels = iced_video_player::dvb::channels()?;
+//! let video = iced_video_player::Video::new(&channels[0].uri())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Switch channels on the same video with [`Video::set_channel`](crate::Video::set_channel).
+//! Without a `channels.conf`, [`scan`] creates one from the initial tuning data of a transmitter.
+
+use crate::Error;
+use gstreamer as gst;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A TV channel listed in a `channels.conf`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Channel {
+    /// Name of the channel.
+    pub name: String,
+    /// Frequency the channel is broadcast on (Hz, or kHz for satellite).
+    pub frequency: u64,
+    /// Service (program) ID of the channel within its multiplex.
+    pub service_id: u16,
+}
+
+impl Channel {
+    /// URI of the channel, for [`Video::new`](crate::Video::new).
+    pub fn uri(&self) -> url::Url {
+        // `dvb://Name`, the name being the host; the source unescapes it when looking it up
+        let mut uri = String::from("dvb://");
+        for c in self.name.chars() {
+            if c.is_ascii_control() || " %/:?#@[]\\<>^|".contains(c) {
+                uri.push_str(&format!("%{:02X}", c as u8));
+            } else {
+                uri.push(c);
+            }
+        }
+        url::Url::parse(&uri).expect("escaped channel URI")
+    }
+}
+
+/// Path of the `channels.conf` read by GStreamer's `dvb://` source.
+pub fn channels_conf_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("GST_DVB_CHANNELS_CONF") {
+        return Some(path.into());
+    }
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("gstreamer-1.0").join("dvb-channels.conf"))
+}
+
+/// Lists the channels of the `channels.conf` read by GStreamer's `dvb://` source.
+///
+/// Fails if the GStreamer DVB plugin isn't installed or the file can't be read.
+pub fn channels() -> Result<Vec<Channel>, Error> {
+    gst::init()?;
+    if gst::ElementFactory::find("dvbbasebin").is_none() {
+        return Err(Error::Element("dvbbasebin".into()));
+    }
+    let path = channels_conf_path().ok_or(Error::Uri)?;
+    Ok(parse_channels(&std::fs::read_to_string(path)?))
+}
+
+/// Scans for channels with `dvbv5-scan`, starting from a transmitter's initial tuning file
+/// (as shipped in `dtv-scan-tables`), and writes them to the [`channels_conf_path`].
+///
+/// Scanning takes a while, so call this off the UI thread. Returns the channels found.
+pub fn scan(initial_tuning: &Path) -> Result<Vec<Channel>, Error> {
+    let path = channels_conf_path().ok_or(Error::Uri)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let status = Command::new("dvbv5-scan")
+        .arg("-o")
+        .arg(&path)
+        .arg(initial_tuning)
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("dvbv5-scan failed: {status}")).into());
+    }
+    channels()
+}
+
+/// Parses channels from the contents of a `channels.conf`, in zap or DVBv5 format.
+pub fn parse_channels(conf: &str) -> Vec<Channel> {
+    if conf.lines().any(|line| line.trim_start().starts_with('[')) {
+        parse_dvbv5(conf)
+    } else {
+        parse_zap(conf)
+    }
+}
+
+/// `name:frequency:...:service_id`, one channel per line.
+fn parse_zap(conf: &str) -> Vec<Channel> {
+    conf.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<_> = line.split(':').collect();
+            if fields.len() < 3 {
+                return None;
+            }
+            Some(Channel {
+                name: fields[0].to_string(),
+                frequency: fields[1].parse().ok()?,
+                service_id: fields[fields.len() - 1].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// `[name]` sections of `KEY = value` pairs.
+fn parse_dvbv5(conf: &str) -> Vec<Channel> {
+    let mut channels = Vec::new();
+    let mut current: Option<(String, Option<u64>, Option<u16>)> = None;
+
+    let mut finish = |current: Option<(String, Option<u64>, Option<u16>)>| {
+        if let Some((name, Some(frequency), Some(service_id))) = current {
+            channels.push(Channel {
+                name,
+                frequency,
+                service_id,
+            });
+        }
+    };
+
+    for line in conf.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            finish(current.replace((name.to_string(), None, None)));
+        } else if let (Some((key, value)), Some(channel)) = (line.split_once('='), &mut current) {
+            match key.trim() {
+                "FREQUENCY" => channel.1 = value.trim().parse().ok(),
+                "SERVICE_ID" => channel.2 = value.trim().parse().ok(),
+                _ => {}
+            }
+        }
+    }
+    finish(current);
+
+    channels
+}
@@ -0,0 +1,208 @@
+use crate::{Bookmark, Error, SkipMode};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Markup about a media file (its trim, skip segments and bookmarks), for review tools to exchange,
+/// see [`Video::edit_list`](crate::Video::edit_list) and [`VideoBuilder::edit_list`](crate::VideoBuilder::edit_list).
+///
+/// Written as a line-based text format with [`EditList::to_edl`], or as JSON (thumbnails included)
+/// through the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EditList {
+    /// Durations trimmed off the beginning and end, see [`Video::set_trim`](crate::Video::set_trim).
+    pub trim: (Duration, Duration),
+    /// Segments skipped during playback, see [`Video::set_skip_segments`](crate::Video::set_skip_segments).
+    pub skip_segments: Vec<(Duration, Duration, SkipMode)>,
+    /// Bookmarked frames, see [`Video::add_bookmark`](crate::Video::add_bookmark).
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl EditList {
+    const HEADER: &'static str = "# iced_video_player edit list";
+
+    /// Writes the edit list in the text format, one entry per line (thumbnails are left out):
+    ///
+    /// ```text
+    /// trim <start> <end>
+    /// skip <start> <end> auto|prompt
+    /// bookmark <id> <position> <frame> <label>
+    /// ```
+    ///
+    /// Times are in seconds, with nanosecond decimals so bookmarks stay frame-accurate.
+    pub fn to_edl(&self) -> String {
+        let mut edl = format!("{}\n", Self::HEADER);
+        if self.trim != (Duration::ZERO, Duration::ZERO) {
+            let _ = writeln!(
+                edl,
+                "trim {} {}",
+                seconds(self.trim.0),
+                seconds(self.trim.1)
+            );
+        }
+        for &(start, end, mode) in &self.skip_segments {
+            let mode = match mode {
+                SkipMode::Auto => "auto",
+                SkipMode::Prompt => "prompt",
+            };
+            let _ = writeln!(edl, "skip {} {} {mode}", seconds(start), seconds(end));
+        }
+        for bookmark in &self.bookmarks {
+            // labels are single-line, the rest of the line
+            let label = bookmark.label.replace(['\r', '\n'], " ");
+            let _ = writeln!(
+                edl,
+                "bookmark {} {} {} {label}",
+                bookmark.id,
+                seconds(bookmark.position),
+                bookmark.frame
+            );
+        }
+        edl
+    }
+
+    /// Reads an edit list written by [`EditList::to_edl`].
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn parse_edl(edl: &str) -> Result<Self, Error> {
+        let mut list = EditList::default();
+        for (number, line) in edl.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::EditList(number + 1);
+            let mut fields = line.splitn(5, ' ');
+            match fields.next() {
+                Some("trim") => {
+                    let start = parse_seconds(fields.next()).ok_or_else(invalid)?;
+                    let end = parse_seconds(fields.next()).ok_or_else(invalid)?;
+                    list.trim = (start, end);
+                }
+                Some("skip") => {
+                    let start = parse_seconds(fields.next()).ok_or_else(invalid)?;
+                    let end = parse_seconds(fields.next()).ok_or_else(invalid)?;
+                    let mode = match fields.next() {
+                        Some("auto") => SkipMode::Auto,
+                        Some("prompt") => SkipMode::Prompt,
+                        _ => return Err(invalid()),
+                    };
+                    list.skip_segments.push((start, end, mode));
+                }
+                Some("bookmark") => {
+                    let id = fields.next().and_then(|id| id.parse().ok());
+                    let position = parse_seconds(fields.next());
+                    let frame = fields.next().and_then(|frame| frame.parse().ok());
+                    let (Some(id), Some(position), Some(frame)) = (id, position, frame) else {
+                        return Err(invalid());
+                    };
+                    list.bookmarks.push(Bookmark {
+                        id,
+                        label: fields.next().unwrap_or_default().to_string(),
+                        position,
+                        frame,
+                        thumbnail_png: Vec::new(),
+                    });
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(list)
+    }
+}
+
+fn seconds(time: Duration) -> String {
+    format!("{}.{:09}", time.as_secs(), time.subsec_nanos())
+}
+
+fn parse_seconds(field: Option<&str>) -> Option<Duration> {
+    let (seconds, nanos) = field?.split_once('.').unwrap_or((field?, "0"));
+    if nanos.is_empty() || nanos.len() > 9 || !nanos.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // shorter decimals are fractions all the same, e.g. `.5` is half a second
+    let nanos: u32 = format!("{nanos:0<9}").parse().ok()?;
+    Some(Duration::new(seconds.parse().ok()?, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let list = EditList {
+            trim: (Duration::from_millis(1500), Duration::from_secs(2)),
+            skip_segments: vec![
+                (
+                    Duration::from_secs(10),
+                    Duration::from_secs(20),
+                    SkipMode::Auto,
+                ),
+                (
+                    Duration::from_nanos(30_000_000_001),
+                    Duration::from_secs(40),
+                    SkipMode::Prompt,
+                ),
+            ],
+            bookmarks: vec![Bookmark {
+                id: 7,
+                label: "opening  titles".to_string(),
+                position: Duration::from_nanos(41_708_333),
+                frame: 1,
+                thumbnail_png: Vec::new(),
+            }],
+        };
+        assert_eq!(EditList::parse_edl(&list.to_edl()).unwrap(), list);
+    }
+
+    #[test]
+    fn keeps_labels_on_one_line() {
+        let list = EditList {
+            bookmarks: vec![Bookmark {
+                id: 0,
+                label: "two\nlines".to_string(),
+                position: Duration::ZERO,
+                frame: 0,
+                thumbnail_png: Vec::new(),
+            }],
+            ..EditList::default()
+        };
+        let parsed = EditList::parse_edl(&list.to_edl()).unwrap();
+        assert_eq!(parsed.bookmarks[0].label, "two lines");
+    }
+
+    #[test]
+    fn reads_short_decimals() {
+        let list = EditList::parse_edl("trim 1.5 2\nbookmark 1 0.25 6").unwrap();
+        assert_eq!(
+            list.trim,
+            (Duration::from_millis(1500), Duration::from_secs(2))
+        );
+        assert_eq!(list.bookmarks[0].position, Duration::from_millis(250));
+        assert_eq!(list.bookmarks[0].label, "");
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "trim 1.5",
+            "trim 1. 2",
+            "trim 1.1234567890 2",
+            "trim -1 2",
+            "trim 1.5e3 2",
+            "skip 1 2",
+            "skip 1 2 sometimes",
+            "bookmark 1 2",
+            "bookmark x 2 3",
+            "cut 1 2",
+        ] {
+            // blank lines and comments are skipped, but still counted
+            let edl = format!("# edit list\n\n{line}");
+            assert!(
+                matches!(EditList::parse_edl(&edl), Err(Error::EditList(3))),
+                "{line}"
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use iced::widget::{image as img, MouseArea, Row};
+use iced::Element;
+use std::time::Duration;
+
+/// Positions of `count` thumbnails evenly spaced across `duration`, each at the start of its segment.
+pub(crate) fn positions(duration: Duration, count: usize) -> impl Iterator<Item = Duration> {
+    (0..count).map(move |i| duration.mul_f64(i as f64 / count as f64))
+}
+
+/// A strip of thumbnails evenly spaced along a [`Video`](crate::Video)'s timeline,
+/// where clicking a thumbnail seeks to it.
+///
+/// Generate the thumbnails with [`Video::filmstrip_thumbnails`](crate::Video::filmstrip_thumbnails).
+pub struct Filmstrip<'a, Message> {
+    thumbnails: &'a [img::Handle],
+    duration: Duration,
+    height: iced::Length,
+    spacing: f32,
+    on_seek: Option<Box<dyn Fn(Duration) -> Message + 'a>>,
+}
+
+impl<'a, Message> Filmstrip<'a, Message> {
+    /// Creates a new filmstrip from thumbnails spanning a media of the given `duration`.
+    pub fn new(thumbnails: &'a [img::Handle], duration: Duration) -> Self {
+        Filmstrip {
+            thumbnails,
+            duration,
+            height: iced::Length::Shrink,
+            spacing: 0.0,
+            on_seek: None,
+        }
+    }
+
+    /// Sets the height of the `Filmstrip`.
+    pub fn height(self, height: impl Into<iced::Length>) -> Self {
+        Filmstrip {
+            height: height.into(),
+            ..self
+        }
+    }
+
+    /// Sets the spacing between thumbnails.
+    pub fn spacing(self, spacing: f32) -> Self {
+        Filmstrip { spacing, ..self }
+    }
+
+    /// Message to send with the position of a thumbnail when it is clicked.
+    pub fn on_seek<F>(self, on_seek: F) -> Self
+    where
+        F: 'a + Fn(Duration) -> Message,
+    {
+        Filmstrip {
+            on_seek: Some(Box::new(on_seek)),
+            ..self
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Filmstrip<'a, Message>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a + Clone,
+    Theme: 'a,
+    Renderer: 'a + iced::advanced::image::Renderer<Handle = img::Handle>,
+{
+    fn from(filmstrip: Filmstrip<'a, Message>) -> Self {
+        let count = filmstrip.thumbnails.len();
+        let thumbnails = filmstrip
+            .thumbnails
+            .iter()
+            .zip(positions(filmstrip.duration, count))
+            .map(|(thumbnail, position)| {
+                let image = img::Image::new(thumbnail.clone())
+                    .width(iced::Length::Fill)
+                    .height(filmstrip.height)
+                    .content_fit(iced::ContentFit::Cover);
+                let mut area = MouseArea::new(image);
+                if let Some(ref on_seek) = filmstrip.on_seek {
+                    area = area.on_press(on_seek(position));
+                }
+                area.into()
+            });
+
+        Row::with_children(thumbnails)
+            .spacing(filmstrip.spacing)
+            .height(filmstrip.height)
+            .into()
+    }
+}
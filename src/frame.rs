@@ -0,0 +1,263 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::cell::UnsafeCell;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Set in `FrameBuffer::shared` when the shared buffer holds a frame readers haven't picked up yet.
+const FRESH: u8 = 0b100;
+const INDEX: u8 = 0b011;
+
+/// A frame, and its size (which changes when the sink is rescaled).
+type Frame = Option<(FrameData, (u32, u32))>;
+
+/// Where the pixels of a frame are.
+#[derive(Debug)]
+pub(crate) enum FrameData {
+    /// Mapped into system memory, laid out without padding.
+    Mapped(gst::MappedBuffer<gst::buffer::Readable>),
+    /// Left in the decoder's DMA-BUF, for the renderer to import.
+    #[cfg(feature = "dmabuf")]
+    DmaBuf(crate::dmabuf::DmaBufFrame),
+}
+
+impl From<gst::MappedBuffer<gst::buffer::Readable>> for FrameData {
+    fn from(map: gst::MappedBuffer<gst::buffer::Readable>) -> Self {
+        FrameData::Mapped(map)
+    }
+}
+
+/// Layout of the frames handed to the widget, picked by caps negotiation,
+/// see [`VideoBuilder::pixel_formats`](crate::VideoBuilder::pixel_formats).
+///
+/// These are the formats the shader samples directly; anything else is converted on the CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PixelFormat {
+    /// A plane of 8-bit luma, then a plane of interleaved 8-bit chroma at half the resolution.
+    #[default]
+    Nv12,
+    /// Laid out like NV12 with 16-bit little-endian samples, holding 10 bits in their high bits.
+    P010,
+}
+
+impl PixelFormat {
+    /// Name of the format in caps.
+    pub(crate) fn caps_name(self) -> &'static str {
+        match self {
+            PixelFormat::Nv12 => "NV12",
+            PixelFormat::P010 => "P010_10LE",
+        }
+    }
+
+    /// Reads the format of raw video `caps`.
+    pub(crate) fn from_caps(caps: &gst::StructureRef) -> Self {
+        match caps.get::<&str>("format") {
+            Ok("P010_10LE") => PixelFormat::P010,
+            _ => PixelFormat::Nv12,
+        }
+    }
+
+    /// Bytes per luma or chroma sample.
+    pub(crate) fn bytes_per_sample(self) -> u32 {
+        match self {
+            PixelFormat::Nv12 => 1,
+            PixelFormat::P010 => 2,
+        }
+    }
+
+    /// Bytes in a frame `width` by `height` pixels big (12 bits per pixel for NV12).
+    pub(crate) fn frame_len(self, width: u32, height: u32) -> usize {
+        (width as usize * height as usize * 3).div_ceil(2) * self.bytes_per_sample() as usize
+    }
+
+    /// YUV of the pixel at `(x, y)` of `frame`, on the 8-bit scale (with a fraction for deeper formats).
+    pub(crate) fn yuv_at(
+        self,
+        frame: &[u8],
+        width: u32,
+        height: u32,
+        x: u32,
+        y: u32,
+    ) -> Option<[f32; 3]> {
+        if x >= width || y >= height {
+            return None;
+        }
+        let uv = (width * height + width * (y / 2) + x / 2 * 2) as usize;
+        let samples = [(y * width + x) as usize, uv, uv + 1];
+        let mut yuv = [0.0; 3];
+        for (value, i) in yuv.iter_mut().zip(samples) {
+            *value = match self {
+                PixelFormat::Nv12 => f32::from(*frame.get(i)?),
+                PixelFormat::P010 => {
+                    let bytes = frame.get(i * 2..i * 2 + 2)?;
+                    f32::from(u16::from_le_bytes([bytes[0], bytes[1]])) / 257.0
+                }
+            };
+        }
+        Some(yuv)
+    }
+}
+
+/// Triple buffer handing decoded frames from the streaming thread to the renderer.
+///
+/// The writer and the readers each own one of the three buffers and trade it for the shared one
+/// with a single atomic swap, so neither side ever waits on the other.
+///
+/// Frames are the decoder's own (mapped) buffers, so the renderer uploads straight from them without an extra copy.
+pub(crate) struct FrameBuffer {
+    buffers: [UnsafeCell<Frame>; 3],
+    format: PixelFormat,
+    // until the first frame is written
+    size: (u32, u32),
+    shared: AtomicU8,
+    generation: AtomicU64,
+    // only contended between readers (e.g., the renderer and thumbnail generation), never by the writer
+    reader: Mutex<u8>,
+}
+
+// SAFETY: the writer, the readers and `shared` always refer to three distinct buffers,
+// so a buffer is never accessed from two threads at once.
+unsafe impl Sync for FrameBuffer {}
+
+impl std::fmt::Debug for FrameBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameBuffer")
+            .field("shared", &self.shared)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FrameBuffer {
+    /// Creates an empty buffer of frames in `format`, `size` big until told otherwise, along with its only writer.
+    pub(crate) fn new(format: PixelFormat, size: (u32, u32)) -> (Arc<Self>, FrameWriter) {
+        let buffer = Arc::new(FrameBuffer {
+            buffers: [(); 3].map(|_| UnsafeCell::new(None)),
+            format,
+            size,
+            shared: AtomicU8::new(1),
+            generation: AtomicU64::new(0),
+            reader: Mutex::new(2),
+        });
+        let writer = FrameWriter {
+            buffer: Arc::clone(&buffer),
+            index: 0,
+        };
+        (buffer, writer)
+    }
+
+    /// Number of frames written so far, to tell whether a new one arrived since last looked.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Gets the latest frame written, which is empty until the first one is.
+    pub(crate) fn latest(&self) -> FrameGuard<'_> {
+        let mut reader = self.reader.lock().unwrap_or_else(|err| err.into_inner());
+        if self.shared.load(Ordering::Acquire) & FRESH != 0 {
+            *reader = self.shared.swap(*reader, Ordering::AcqRel) & INDEX;
+        }
+        FrameGuard {
+            buffer: self,
+            reader,
+        }
+    }
+}
+
+/// The latest frame of a [`FrameBuffer`], kept from being swapped out while held.
+pub(crate) struct FrameGuard<'a> {
+    buffer: &'a FrameBuffer,
+    reader: MutexGuard<'a, u8>,
+}
+
+impl FrameGuard<'_> {
+    fn frame(&self) -> &Frame {
+        // SAFETY: the reader's buffer is only swapped while holding `reader`
+        unsafe { &*self.buffer.buffers[*self.reader as usize].get() }
+    }
+
+    /// Size of the frame in pixels, as `(width, height)`.
+    pub(crate) fn size(&self) -> (u32, u32) {
+        self.frame()
+            .as_ref()
+            .map_or(self.buffer.size, |(_, size)| *size)
+    }
+
+    /// The frame's DMA-BUF, if it was left in one.
+    #[cfg(feature = "dmabuf")]
+    pub(crate) fn dmabuf(&self) -> Option<&crate::dmabuf::DmaBufFrame> {
+        match self.frame() {
+            Some((FrameData::DmaBuf(frame), _)) => Some(frame),
+            _ => None,
+        }
+    }
+}
+
+impl Deref for FrameGuard<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // decoders may pad the end of the buffer
+        self.frame()
+            .as_ref()
+            .map(|(frame, (width, height))| {
+                let frame = match frame {
+                    FrameData::Mapped(map) => map.as_slice(),
+                    #[cfg(feature = "dmabuf")]
+                    FrameData::DmaBuf(frame) => frame.bytes(),
+                };
+                let len = self.buffer.format.frame_len(*width, *height);
+                &frame[..len.min(frame.len())]
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Write side of a [`FrameBuffer`], owned by the streaming thread.
+pub(crate) struct FrameWriter {
+    buffer: Arc<FrameBuffer>,
+    index: u8,
+}
+
+impl FrameWriter {
+    /// Publishes a new frame, `size` pixels big.
+    ///
+    /// Returns `true` if this replaced a frame that was never read (i.e., that frame was dropped).
+    pub(crate) fn write(&mut self, frame: impl Into<FrameData>, size: (u32, u32)) -> bool {
+        // SAFETY: the writer's buffer is only swapped by the writer itself
+        // (this also releases the frame it held back to the decoder)
+        unsafe { *self.buffer.buffers[self.index as usize].get() = Some((frame.into(), size)) };
+        let previous = self
+            .buffer
+            .shared
+            .swap(self.index | FRESH, Ordering::AcqRel);
+        // only counted once published, so a reader seeing the new generation also gets the frame
+        self.buffer.generation.fetch_add(1, Ordering::AcqRel);
+        self.index = previous & INDEX;
+        previous & FRESH != 0
+    }
+}
+
+/// Asks decoders upstream of `pad` (the video sink's) to allocate room for the frames held on to here,
+/// as decoders with small pools (e.g., hardware decoders) would otherwise stall until one is released.
+pub(crate) fn reserve_buffers(pad: &gst::Pad) {
+    pad.add_probe(gst::PadProbeType::QUERY_DOWNSTREAM, |_, info| {
+        let Some(gst::PadProbeData::Query(ref mut query)) = info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let gst::QueryViewMut::Allocation(allocation) = query.view_mut() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        #[cfg(feature = "dmabuf")]
+        let imported = match allocation.get().0 {
+            Some(caps) if crate::dmabuf::is_dmabuf(caps) => crate::dmabuf::IMPORTED_FRAMES,
+            _ => 0,
+        };
+        #[cfg(not(feature = "dmabuf"))]
+        let imported = 0;
+        // no pool of its own, only the number of buffers decoders allocate on top of what they need:
+        // one for each buffer of a `FrameBuffer`, and those of the frames imported into textures
+        allocation.add_allocation_pool(None::<&gst::BufferPool>, 0, 3 + imported, 0);
+        gst::PadProbeReturn::Ok
+    });
+}
@@ -0,0 +1,49 @@
+use gstreamer as gst;
+use std::time::Duration;
+
+/// Reference caps name of the timestamp metas carrying frame metadata.
+const REFERENCE: &str = "timestamp/x-iced-frame-metadata";
+
+/// Metadata an app attached to a frame with [`attach_frame_metadata`], read back as the frame is shown,
+/// see [`Video::frame_metadata`](crate::Video::frame_metadata).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameMetadata {
+    /// Timestamp attached to the frame (e.g., when it was captured, to measure latency end to end).
+    pub timestamp: Duration,
+    /// Fields attached to the frame, in a structure named `timestamp/x-iced-frame-metadata`.
+    pub fields: gst::Structure,
+}
+
+/// Attaches the fields of `metadata` and a `timestamp` to a frame `buffer`, e.g. before pushing it into an
+/// `appsrc` playing through [`Video::from_pipeline`](crate::Video::from_pipeline).
+///
+/// They travel as a reference timestamp meta, which converters and scalers pass along.
+pub fn attach_frame_metadata(
+    buffer: &mut gst::BufferRef,
+    metadata: &gst::StructureRef,
+    timestamp: Duration,
+) {
+    let mut reference = gst::Structure::new_empty(REFERENCE);
+    for (field, value) in metadata.iter() {
+        reference.set_value(field, value.clone());
+    }
+    gst::ReferenceTimestampMeta::add(
+        buffer,
+        &gst::Caps::builder_full().structure(reference).build(),
+        gst::ClockTime::from_nseconds(timestamp.as_nanos() as u64),
+        gst::ClockTime::NONE,
+    );
+}
+
+/// Reads the metadata attached to a frame `buffer`, if any.
+pub(crate) fn read(buffer: &gst::BufferRef) -> Option<FrameMetadata> {
+    buffer
+        .iter_meta::<gst::ReferenceTimestampMeta>()
+        .find_map(|meta| {
+            let fields = meta.reference().structure(0)?;
+            (fields.name() == REFERENCE).then(|| FrameMetadata {
+                timestamp: Duration::from_nanos(meta.timestamp().nseconds()),
+                fields: fields.to_owned(),
+            })
+        })
+}
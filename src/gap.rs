@@ -0,0 +1,100 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Why playback skipped over part of the stream, see [`Gap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GapReason {
+    /// The stream signalled that there is no data for a while (e.g., a sparse or interrupted live stream).
+    GapEvent,
+    /// Frame timestamps jumped further ahead than the frame duration.
+    TimestampJump,
+    /// The stream flagged a discontinuity (e.g., lost packets).
+    Discontinuity,
+}
+
+/// A stretch of the stream with no frames, as reported by
+/// [`VideoPlayer::on_discontinuity`](crate::VideoPlayer::on_discontinuity).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gap {
+    /// Stream time at which the gap starts.
+    pub at: Duration,
+    /// Length of the gap, zero if unknown.
+    pub duration: Duration,
+    /// What caused the gap.
+    pub reason: GapReason,
+}
+
+/// Watches buffers and events reaching `pad` and records gaps into `gaps`.
+///
+/// Timestamp jumps shorter than a few frames (or 100ms) are considered jitter and ignored.
+pub(crate) fn watch_gaps(pad: &gst::Pad, framerate: f64, gaps: Arc<Mutex<Vec<Gap>>>) {
+    let tolerance = gst::ClockTime::from_nseconds(
+        Duration::from_secs_f64(3.0 / framerate)
+            .max(Duration::from_millis(100))
+            .as_nanos() as _,
+    );
+    // end of the last buffer; reset on flushes and new segments so seeks aren't reported
+    let last_end = Mutex::new(None::<gst::ClockTime>);
+
+    pad.add_probe(
+        gst::PadProbeType::BUFFER | gst::PadProbeType::EVENT_DOWNSTREAM,
+        move |_pad, info| {
+            let gap = if let Some(event) = info.event() {
+                match event.view() {
+                    gst::EventView::Gap(gap) => {
+                        let (at, duration) = gap.get();
+                        Some(Gap {
+                            at: Duration::from_nanos(at.nseconds()),
+                            duration: Duration::from_nanos(duration.map_or(0, |d| d.nseconds())),
+                            reason: GapReason::GapEvent,
+                        })
+                    }
+                    gst::EventView::FlushStop(_) | gst::EventView::Segment(_) => {
+                        if let Ok(mut last_end) = last_end.lock() {
+                            *last_end = None;
+                        }
+                        None
+                    }
+                    _ => None,
+                }
+            } else if let Some(buffer) = info.buffer() {
+                let Some(pts) = buffer.pts() else {
+                    return gst::PadProbeReturn::Ok;
+                };
+                let Ok(mut last_end) = last_end.lock() else {
+                    return gst::PadProbeReturn::Ok;
+                };
+                let previous = last_end.replace(pts + buffer.duration().unwrap_or_default());
+                previous.and_then(|expected| {
+                    if pts > expected + tolerance {
+                        Some(Gap {
+                            at: Duration::from_nanos(expected.nseconds()),
+                            duration: Duration::from_nanos((pts - expected).nseconds()),
+                            reason: GapReason::TimestampJump,
+                        })
+                    } else if buffer.flags().contains(gst::BufferFlags::DISCONT) {
+                        Some(Gap {
+                            at: Duration::from_nanos(pts.nseconds()),
+                            duration: Duration::ZERO,
+                            reason: GapReason::Discontinuity,
+                        })
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            };
+
+            if let Some(gap) = gap {
+                if let Ok(mut gaps) = gaps.lock() {
+                    gaps.push(gap);
+                }
+            }
+
+            gst::PadProbeReturn::Ok
+        },
+    );
+}
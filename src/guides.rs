@@ -0,0 +1,67 @@
+use crate::Annotation;
+use iced::{Color, Point, Rectangle, Size};
+
+const COLOR: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.6);
+const WIDTH: f32 = 1.0;
+
+/// Framing guides drawn over the video, see [`VideoPlayer::guides`](crate::VideoPlayer::guides).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Guides {
+    /// Outline the action safe area (93% of the frame, SMPTE ST 2046-1).
+    pub action_safe: bool,
+    /// Outline the title safe area (90% of the frame, SMPTE ST 2046-1).
+    pub title_safe: bool,
+    /// Divide the frame into thirds, for the rule of thirds.
+    pub thirds: bool,
+    /// Mark the center of the frame with a cross.
+    pub center_cross: bool,
+}
+
+impl Guides {
+    /// Get if no guide is shown.
+    pub fn is_empty(&self) -> bool {
+        *self == Guides::default()
+    }
+
+    /// The guides as annotations, for a video drawn `size` big.
+    pub(crate) fn annotations(&self, size: Size) -> Vec<Annotation> {
+        let mut annotations = Vec::new();
+        let area = |fraction: f32| Annotation::Rect {
+            bounds: Rectangle {
+                x: (1.0 - fraction) / 2.0,
+                y: (1.0 - fraction) / 2.0,
+                width: fraction,
+                height: fraction,
+            },
+            width: Some(WIDTH),
+            color: COLOR,
+        };
+        let line = |from: Point, to: Point| Annotation::Line {
+            from,
+            to,
+            width: WIDTH,
+            color: COLOR,
+        };
+
+        if self.action_safe {
+            annotations.push(area(0.93));
+        }
+        if self.title_safe {
+            annotations.push(area(0.9));
+        }
+        if self.thirds {
+            for third in [1.0 / 3.0, 2.0 / 3.0] {
+                annotations.push(line(Point::new(third, 0.0), Point::new(third, 1.0)));
+                annotations.push(line(Point::new(0.0, third), Point::new(1.0, third)));
+            }
+        }
+        if self.center_cross && size.width > 0.0 && size.height > 0.0 {
+            // arms as long in both directions, whatever the aspect ratio
+            let arm = size.width.min(size.height) * 0.04;
+            let (x, y) = (arm / size.width, arm / size.height);
+            annotations.push(line(Point::new(0.5 - x, 0.5), Point::new(0.5 + x, 0.5)));
+            annotations.push(line(Point::new(0.5, 0.5 - y), Point::new(0.5, 0.5 + y)));
+        }
+        annotations
+    }
+}
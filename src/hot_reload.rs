@@ -0,0 +1,111 @@
+//! Shader loading, from disk with the `shader-hot-reload` feature so edits show up without restarting.
+
+use iced_wgpu::wgpu;
+use std::borrow::Cow;
+
+/// Source of the shader `file` (next to this one in `src/`), `embedded` unless hot-reloading.
+pub(crate) fn source(file: &str, embedded: &'static str) -> Cow<'static, str> {
+    #[cfg(feature = "shader-hot-reload")]
+    match std::fs::read_to_string(path(file)) {
+        Ok(source) => return Cow::Owned(source),
+        Err(err) => log::warn!("cannot read shader {file}, using the built-in one: {err}"),
+    }
+    #[cfg(not(feature = "shader-hot-reload"))]
+    let _ = file;
+    Cow::Borrowed(embedded)
+}
+
+/// Runs `create` (e.g., compiling a shader), returning `None` if it failed validation.
+///
+/// Without hot-reloading, shaders are known to be valid, and errors go to wgpu's error handler as usual.
+pub(crate) fn checked<T>(device: &wgpu::Device, create: impl FnOnce() -> T) -> Option<T> {
+    #[cfg(feature = "shader-hot-reload")]
+    {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let value = create();
+        match poll_now(device.pop_error_scope()) {
+            Some(Some(err)) => {
+                log::error!("cannot compile shader: {err}");
+                None
+            }
+            _ => Some(value),
+        }
+    }
+    #[cfg(not(feature = "shader-hot-reload"))]
+    {
+        let _ = device;
+        Some(create())
+    }
+}
+
+#[cfg(feature = "shader-hot-reload")]
+fn path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src")).join(file)
+}
+
+/// Polls `future` once; native wgpu resolves error scopes right away.
+#[cfg(feature = "shader-hot-reload")]
+fn poll_now<F: std::future::Future>(future: F) -> Option<F::Output> {
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    // SAFETY: the waker does nothing, so its (null) data is never used
+    let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+    let mut future = std::pin::pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
+
+/// Notices changes to the shader files on disk.
+#[cfg(feature = "shader-hot-reload")]
+#[derive(Debug)]
+pub(crate) struct ShaderWatch {
+    modified: Vec<Option<std::time::SystemTime>>,
+    checked: std::time::Instant,
+}
+
+#[cfg(feature = "shader-hot-reload")]
+impl ShaderWatch {
+    const FILES: [&'static str; 3] = ["shader.wgsl", "annotation.wgsl", "watermark.wgsl"];
+    // checked every frame otherwise
+    const INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+    pub(crate) fn new() -> Self {
+        ShaderWatch {
+            modified: Self::modified(),
+            checked: std::time::Instant::now(),
+        }
+    }
+
+    /// Whether any shader changed since last asked.
+    pub(crate) fn changed(&mut self) -> bool {
+        if self.checked.elapsed() < Self::INTERVAL {
+            return false;
+        }
+        self.checked = std::time::Instant::now();
+        let modified = Self::modified();
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+
+    fn modified() -> Vec<Option<std::time::SystemTime>> {
+        Self::FILES
+            .iter()
+            .map(|file| {
+                std::fs::metadata(path(file))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            })
+            .collect()
+    }
+}
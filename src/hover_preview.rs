@@ -0,0 +1,309 @@
+use crate::{Video, VideoBuilder, VideoPlayer};
+use iced::{
+    advanced::{self, graphics::core::event::Status, layout, text, widget, Widget},
+    Element,
+};
+use iced_wgpu::primitive::Renderer as PrimitiveRenderer;
+use log::error;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Idle preview videos kept around so hovering the same item again starts instantly.
+///
+/// Share one pool between many [`HoverPreview`] widgets (e.g., all items of a file grid).
+#[derive(Debug)]
+pub struct PreviewPool {
+    capacity: usize,
+    videos: Mutex<Vec<(url::Url, Video)>>,
+}
+
+impl PreviewPool {
+    /// Creates a pool keeping up to `capacity` idle videos; the least recently used ones are dropped first.
+    pub fn new(capacity: usize) -> Self {
+        PreviewPool {
+            capacity,
+            videos: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn take(&self, uri: &url::Url) -> Option<Video> {
+        let mut videos = self.videos.lock().ok()?;
+        let index = videos.iter().position(|(u, _)| u == uri)?;
+        Some(videos.remove(index).1)
+    }
+
+    fn give(&self, uri: url::Url, mut video: Video) {
+        video.set_paused(true);
+        if let Ok(mut videos) = self.videos.lock() {
+            videos.push((uri, video));
+            if videos.len() > self.capacity {
+                videos.remove(0);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    hovered_since: Option<Instant>,
+    failed: bool,
+    video: Option<Video>,
+}
+
+/// Shows `content` (e.g., a thumbnail) until it has been hovered for a while,
+/// then plays a muted, lightweight preview of a video in its place until the cursor leaves.
+pub struct HoverPreview<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer>
+where
+    Renderer: PrimitiveRenderer,
+{
+    uri: url::Url,
+    content: Element<'a, Message, Theme, Renderer>,
+    delay: Duration,
+    max_size: (u32, u32),
+    content_fit: iced::ContentFit,
+    pool: Option<&'a PreviewPool>,
+}
+
+impl<'a, Message, Theme, Renderer> HoverPreview<'a, Message, Theme, Renderer>
+where
+    Renderer: PrimitiveRenderer,
+{
+    /// Creates a new hover preview of the video at `uri`, showing `content` while not previewing.
+    pub fn new(uri: &url::Url, content: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        HoverPreview {
+            uri: uri.clone(),
+            content: content.into(),
+            delay: Duration::from_millis(500),
+            max_size: (480, 270),
+            content_fit: iced::ContentFit::Cover,
+            pool: None,
+        }
+    }
+
+    /// Sets how long the cursor has to rest on the widget before the preview starts.
+    pub fn delay(self, delay: Duration) -> Self {
+        HoverPreview { delay, ..self }
+    }
+
+    /// Sets the maximum resolution the preview is decoded at.
+    pub fn max_size(self, width: u32, height: u32) -> Self {
+        HoverPreview {
+            max_size: (width, height),
+            ..self
+        }
+    }
+
+    /// Sets the `ContentFit` of the preview.
+    pub fn content_fit(self, content_fit: iced::ContentFit) -> Self {
+        HoverPreview {
+            content_fit,
+            ..self
+        }
+    }
+
+    /// Sets the [`PreviewPool`] to reuse preview videos from.
+    pub fn pool(self, pool: &'a PreviewPool) -> Self {
+        HoverPreview {
+            pool: Some(pool),
+            ..self
+        }
+    }
+
+    fn open(&self) -> Option<Video> {
+        if let Some(video) = self.pool.and_then(|pool| pool.take(&self.uri)) {
+            return Some(video);
+        }
+
+        VideoBuilder::new(&self.uri)
+            .no_audio(true)
+            .max_size(self.max_size.0, self.max_size.1)
+            .build()
+            .map_err(|err| error!("cannot open preview of {}: {err}", self.uri))
+            .ok()
+    }
+
+    fn close(&self, state: &mut State) {
+        state.hovered_since = None;
+        state.failed = false;
+        if let Some(video) = state.video.take() {
+            match self.pool {
+                Some(pool) => pool.give(self.uri.clone(), video),
+                None => drop(video),
+            }
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for HoverPreview<'a, Message, Theme, Renderer>
+where
+    Message: Clone,
+    Renderer: PrimitiveRenderer + text::Renderer,
+{
+    fn size(&self) -> iced::Size<iced::Length> {
+        self.content.as_widget().size()
+    }
+
+    fn tag(&self) -> widget::tree::Tag {
+        widget::tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> widget::tree::State {
+        widget::tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<widget::Tree> {
+        vec![widget::Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut widget::Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(
+        &self,
+        tree: &mut widget::Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &widget::Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &advanced::renderer::Style,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        viewport: &iced::Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+
+        match state.video {
+            Some(ref video) => {
+                let player: VideoPlayer<'_, Message, Theme, Renderer> = VideoPlayer::new(video)
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fill)
+                    .content_fit(self.content_fit);
+                player.draw(
+                    &tree.children[0],
+                    renderer,
+                    theme,
+                    style,
+                    layout,
+                    cursor,
+                    viewport,
+                );
+            }
+            None => self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                layout,
+                cursor,
+                viewport,
+            ),
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut widget::Tree,
+        event: iced::Event,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn advanced::Clipboard,
+        shell: &mut advanced::Shell<'_, Message>,
+        viewport: &iced::Rectangle,
+    ) -> Status {
+        let status = self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event.clone(),
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+
+        let state = tree.state.downcast_mut::<State>();
+
+        if cursor.is_over(layout.bounds()) {
+            if state.hovered_since.is_none() {
+                state.hovered_since = Some(Instant::now());
+                shell.request_redraw(iced::window::RedrawRequest::At(Instant::now() + self.delay));
+            }
+        } else if state.hovered_since.is_some() {
+            self.close(state);
+            shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+        }
+
+        if let iced::Event::Window(iced::window::Event::RedrawRequested(_)) = event {
+            let due = state
+                .hovered_since
+                .is_some_and(|since| since.elapsed() >= self.delay);
+            if due && state.video.is_none() && !state.failed {
+                match self.open() {
+                    Some(mut video) => {
+                        video.set_paused(false);
+                        state.video = Some(video);
+                    }
+                    None => state.failed = true,
+                }
+            }
+        }
+
+        if let Some(ref video) = state.video {
+            // keeps the preview's redraw loop going
+            let mut player: VideoPlayer<'_, Message, Theme, Renderer> = VideoPlayer::new(video);
+            player.on_event(
+                &mut tree.children[0],
+                event,
+                layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+        }
+
+        status
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &widget::Tree,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        viewport: &iced::Rectangle,
+        renderer: &Renderer,
+    ) -> advanced::mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<HoverPreview<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a + Clone,
+    Theme: 'a,
+    Renderer: 'a + PrimitiveRenderer + text::Renderer,
+{
+    fn from(hover_preview: HoverPreview<'a, Message, Theme, Renderer>) -> Self {
+        Self::new(hover_preview)
+    }
+}
@@ -0,0 +1,73 @@
+use gstreamer as gst;
+use gstreamer_base as gst_base;
+use gstreamer_base::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Reference caps name of the timestamp metas live sources stamp frames with as they capture them.
+const REFERENCE: &str = "timestamp/x-iced-capture";
+/// Reference caps name of the capture times RTP jitter buffers attach from the sender's reports,
+/// in nanoseconds since the NTP epoch.
+const NTP_REFERENCE: &str = "timestamp/x-ntp";
+/// Seconds from the NTP epoch (1900) to the UNIX one (1970).
+const NTP_TO_UNIX: u64 = 2_208_988_800;
+
+/// Stamps the frames `source` pushes with the wall-clock time, while `measuring` and the source is live
+/// (e.g., `v4l2src` or `pipewiresrc` capturing a camera), see [`Video::latency`](crate::Video::latency).
+///
+/// The stamp travels as a reference timestamp meta, which decoders, converters and scalers pass along.
+pub(crate) fn stamp_captures(source: &gst::Element, measuring: Arc<AtomicBool>) {
+    let Some(pad) = source.static_pad("src") else {
+        return;
+    };
+    let reference = gst::Caps::new_empty_simple(REFERENCE);
+    pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+        if !measuring.load(Ordering::SeqCst) {
+            return gst::PadProbeReturn::Ok;
+        }
+        // files are read ahead of time, so only frames of live sources are stamped as they're captured
+        let live = pad
+            .parent()
+            .and_then(|parent| parent.downcast::<gst_base::BaseSrc>().ok())
+            .is_some_and(|source| source.is_live());
+        if let (true, Some(gst::PadProbeData::Buffer(buffer))) = (live, &mut info.data) {
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default();
+            gst::ReferenceTimestampMeta::add(
+                buffer.make_mut(),
+                &reference,
+                gst::ClockTime::from_nseconds(now.as_nanos() as u64),
+                gst::ClockTime::NONE,
+            );
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+/// Has an RTSP `source` attach the capture times the camera reports to the frames (GStreamer 1.22+).
+pub(crate) fn report_captures(source: &gst::Element) {
+    if source.has_property("add-reference-timestamp-meta", None) {
+        source.set_property("add-reference-timestamp-meta", true);
+    }
+}
+
+/// When the frame in `buffer` was captured, if its sender reported it or a live source stamped it.
+pub(crate) fn capture_time(buffer: &gst::BufferRef) -> Option<SystemTime> {
+    let mut stamped = None;
+    for meta in buffer.iter_meta::<gst::ReferenceTimestampMeta>() {
+        let timestamp = Duration::from_nanos(meta.timestamp().nseconds());
+        match meta.reference().structure(0).map(|s| s.name().as_str()) {
+            // the camera's own capture time beats the time its packets got to the source
+            Some(NTP_REFERENCE) => {
+                if let Some(since_unix) = timestamp.checked_sub(Duration::from_secs(NTP_TO_UNIX)) {
+                    return Some(SystemTime::UNIX_EPOCH + since_unix);
+                }
+            }
+            Some(REFERENCE) => stamped = Some(SystemTime::UNIX_EPOCH + timestamp),
+            _ => {}
+        }
+    }
+    stamped
+}
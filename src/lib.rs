@@ -34,16 +34,110 @@
 //!
 //! You can programmatically control the video (e.g., seek, pause, loop, grab thumbnails) by accessing various methods on [`Video`].
 
+mod above_video;
+mod analytics;
+mod annotation;
+mod audio_devices;
+mod bookmark;
+mod chapter;
+mod closed_caption;
+mod content_gate;
+mod data_track;
+#[cfg(feature = "dmabuf")]
+mod dmabuf;
+#[cfg(feature = "dvb")]
+pub mod dvb;
+mod edit_list;
+mod filmstrip;
+mod frame;
+mod frame_metadata;
+mod gap;
+mod guides;
+mod hot_reload;
+mod hover_preview;
+mod latency;
+mod lut;
+mod lyrics;
+#[cfg(feature = "media-session")]
+mod media_session;
+mod mini_player;
+mod mpegts;
+mod network_error;
 mod pipeline;
+mod pixel_probe;
+mod quality_cap;
+mod reduced_motion;
+mod registry;
+mod rtsp;
+mod seek_bar;
+mod sink_caps;
+mod snapshot;
+mod styled_subtitle;
+mod teletext;
+mod thread_priority;
+mod track_meter;
+mod transform;
+mod url_refresher;
+mod variant;
 mod video;
+mod video_builder;
+mod video_image;
 mod video_player;
+mod video_texture;
+mod visualization;
+mod watermark;
 
 use gstreamer as gst;
 use thiserror::Error;
 
+pub use above_video::AboveVideo;
+pub use analytics::AnalyticsSink;
+pub use annotation::Annotation;
+pub use bookmark::Bookmark;
+pub use chapter::Chapter;
+pub use closed_caption::CaptionChannel;
+pub use content_gate::ContentGate;
+pub use data_track::{DataInterpolation, DataSample};
+pub use edit_list::EditList;
+pub use filmstrip::Filmstrip;
+pub use frame::PixelFormat;
+pub use frame_metadata::{attach_frame_metadata, FrameMetadata};
+pub use gap::{Gap, GapReason};
+pub use guides::Guides;
+pub use hover_preview::{HoverPreview, PreviewPool};
+pub use lut::Lut;
+pub use mini_player::{MiniPlayer, MiniPlayerMessage};
+pub use mpegts::{Program, ProgramStream, StreamKind};
+pub use network_error::{NetworkError, NetworkErrorKind};
+pub use pipeline::{ColorBalance, OutputEncoding, ToneMapping};
+pub use pixel_probe::PixelProbe;
+pub use quality_cap::{
+    CapReason, DataSaver, PowerStatus, QualityCap, QualityPolicy, SystemPowerStatus,
+};
+pub use reduced_motion::{MotionPreference, SystemMotionPreference};
+pub use registry::{VideoId, VideoRegistry};
+pub use rtsp::{LowLatency, RtspTransport};
+pub use seek_bar::{Cue, SeekBar};
+pub use thread_priority::ThreadPriority;
+pub use track_meter::TrackLevel;
+pub use transform::Transform;
+pub use url_refresher::UrlRefresher;
+pub use variant::Variant;
+pub use video::AudioClockPosition;
+pub use video::AudioMix;
+pub use video::AudioTrack;
+pub use video::Features;
+pub use video::Karaoke;
 pub use video::Position;
+pub use video::SeekMode;
+pub use video::SkipMode;
 pub use video::Video;
-pub use video_player::VideoPlayer;
+pub use video::VideoTrack;
+pub use video_builder::VideoBuilder;
+pub use video_image::VideoImage;
+pub use video_player::{AudioDeviceEvent, Filter, FocusPolicy, Interpolation, VideoPlayer};
+pub use video_texture::VideoTexture;
+pub use watermark::Corner;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -57,6 +151,8 @@ pub enum Error {
     AppSink(String),
     #[error("{0}")]
     StateChange(#[from] gst::StateChangeError),
+    #[error("failed to find element '{0}' in gstreamer pipeline")]
+    Element(String),
     #[error("failed to cast gstreamer element")]
     Cast,
     #[error("{0}")]
@@ -69,8 +165,30 @@ pub enum Error {
     Duration,
     #[error("failed to sync with playback")]
     Sync,
+    #[error("failed to step frames")]
+    Step,
     #[error("failed to lock internal sync primitive")]
     Lock,
     #[error("invalid framerate: {0}")]
     Framerate(f64),
+    #[error("{0}")]
+    Network(#[from] NetworkError),
+    #[error("content is locked behind a content gate")]
+    ContentLocked,
+    #[error("frames of the video are capture protected")]
+    CaptureProtected,
+    #[error("no frame has been decoded yet")]
+    NoFrame,
+    #[error("failed to encode the frame")]
+    Encode,
+    #[error("no chapter at index {0}")]
+    Chapter(usize),
+    #[error("no bookmark with id {0}")]
+    Bookmark(u64),
+    #[error("no stream variant at index {0}")]
+    Variant(usize),
+    #[error("invalid edit list entry at line {0}")]
+    EditList(usize),
+    #[error("invalid color lookup table at line {0}")]
+    Lut(usize),
 }
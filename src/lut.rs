@@ -0,0 +1,235 @@
+use crate::Error;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A 3D color lookup table grading the frames as they are drawn, see [`Video::set_lut`](crate::Video::set_lut).
+///
+/// Read from the `.cube` files grading tools (e.g., DaVinci Resolve) export, and applied on the GPU
+/// after the conversion to RGB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut {
+    id: u64,
+    pub(crate) size: u32,
+    /// Output colors, red changing fastest, then green, then blue.
+    pub(crate) table: Arc<[[f32; 3]]>,
+    pub(crate) domain_min: [f32; 3],
+    pub(crate) domain_max: [f32; 3],
+}
+
+impl Lut {
+    // more than any grading tool exports (65 is the usual maximum)
+    const MAX_SIZE: u32 = 128;
+
+    /// Reads a `.cube` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::parse_cube(&std::fs::read_to_string(path)?)
+    }
+
+    /// Reads the contents of a `.cube` file, as specified by Adobe's Cube LUT Specification 1.0.
+    ///
+    /// Only 3D tables are supported; the `TITLE` and keywords the specification doesn't know
+    /// (e.g., those some tools add) are ignored. Resolve's `LUT_3D_INPUT_RANGE` sets the domain.
+    pub fn parse_cube(cube: &str) -> Result<Self, Error> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+        for (number, line) in cube.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::Lut(number + 1);
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            match keyword {
+                "TITLE" => {}
+                "LUT_3D_SIZE" => {
+                    let lut_size = rest
+                        .trim()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|size| (2..=Self::MAX_SIZE).contains(size))
+                        .ok_or_else(invalid)?;
+                    size = Some(lut_size);
+                    table.reserve_exact(lut_size.pow(3) as usize);
+                }
+                "DOMAIN_MIN" => domain_min = parse_triple(rest).ok_or_else(invalid)?,
+                "DOMAIN_MAX" => domain_max = parse_triple(rest).ok_or_else(invalid)?,
+                "LUT_3D_INPUT_RANGE" => {
+                    let mut range = rest.split_whitespace().map(|field| field.parse::<f32>());
+                    let (Some(Ok(min)), Some(Ok(max)), None) =
+                        (range.next(), range.next(), range.next())
+                    else {
+                        return Err(invalid());
+                    };
+                    if !(min.is_finite() && max.is_finite()) {
+                        return Err(invalid());
+                    }
+                    domain_min = [min; 3];
+                    domain_max = [max; 3];
+                }
+                "LUT_1D_SIZE" => return Err(invalid()),
+                // keywords start with a letter, unlike the data
+                _ if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {}
+                // anything else before the data
+                _ if size.is_none() => return Err(invalid()),
+                _ => {
+                    let entry = parse_triple(line).ok_or_else(invalid)?;
+                    if table.len() == size.unwrap_or_default().pow(3) as usize {
+                        return Err(invalid());
+                    }
+                    table.push(entry);
+                }
+            }
+        }
+        let lines = cube.lines().count().max(1);
+        let size = size.ok_or(Error::Lut(lines))?;
+        if table.len() != size.pow(3) as usize || (0..3).any(|i| domain_min[i] >= domain_max[i]) {
+            return Err(Error::Lut(lines));
+        }
+
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Ok(Lut {
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            size,
+            table: table.into(),
+            domain_min,
+            domain_max,
+        })
+    }
+
+    /// Number of entries along each axis of the table.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Identifies the table across clones, so it is only uploaded once.
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+fn parse_triple(fields: &str) -> Option<[f32; 3]> {
+    let mut fields = fields.split_whitespace().map(|field| field.parse::<f32>());
+    let triple = [
+        fields.next()?.ok()?,
+        fields.next()?.ok()?,
+        fields.next()?.ok()?,
+    ];
+    (fields.next().is_none() && triple.iter().all(|value| value.is_finite())).then_some(triple)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2×2×2 `.cube` table after `header`, each entry being its own coordinates.
+    fn cube(header: &str) -> String {
+        let mut cube = format!("{header}\n");
+        for b in 0..2 {
+            for g in 0..2 {
+                for r in 0..2 {
+                    cube.push_str(&format!("{r} {g} {b}\n"));
+                }
+            }
+        }
+        cube
+    }
+
+    #[test]
+    fn parses_table() {
+        let lut = Lut::parse_cube(&cube("TITLE \"grade\"\n# comment\n\nLUT_3D_SIZE 2")).unwrap();
+        assert_eq!(lut.size(), 2);
+        assert_eq!(lut.table.len(), 8);
+        // red changes fastest
+        assert_eq!(lut.table[1], [1.0, 0.0, 0.0]);
+        assert_eq!(lut.table[2], [0.0, 1.0, 0.0]);
+        assert_eq!(lut.table[4], [0.0, 0.0, 1.0]);
+        assert_eq!((lut.domain_min, lut.domain_max), ([0.0; 3], [1.0; 3]));
+    }
+
+    #[test]
+    fn skips_unknown_keywords() {
+        let lut = Lut::parse_cube(&cube(
+            "LUT_3D_SIZE 2\nLUT_IN_VIDEO_RANGE\nLUT_OUT_VIDEO_RANGE",
+        ));
+        assert_eq!(lut.map(|lut| lut.size()).ok(), Some(2));
+    }
+
+    #[test]
+    fn reads_domain() {
+        let lut = Lut::parse_cube(&cube(
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0 0.1 0.2\nDOMAIN_MAX 1 2 3",
+        ))
+        .unwrap();
+        assert_eq!(lut.domain_min, [0.0, 0.1, 0.2]);
+        assert_eq!(lut.domain_max, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn reads_input_range() {
+        let lut = Lut::parse_cube(&cube("LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE 0.0 4.0")).unwrap();
+        assert_eq!((lut.domain_min, lut.domain_max), ([0.0; 3], [4.0; 3]));
+    }
+
+    #[test]
+    fn rejects_malformed_input_range() {
+        for range in ["0.0", "0.0 1.0 2.0", "0.0 max", "0.0 inf"] {
+            let cube = cube(&format!("LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE {range}"));
+            assert!(
+                matches!(Lut::parse_cube(&cube), Err(Error::Lut(2))),
+                "{range}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_empty_domain() {
+        let cube = cube("LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE 1.0 1.0");
+        assert!(matches!(Lut::parse_cube(&cube), Err(Error::Lut(10))));
+    }
+
+    #[test]
+    fn rejects_1d_tables() {
+        let cube = cube("TITLE \"1d\"\nLUT_1D_SIZE 2");
+        assert!(matches!(Lut::parse_cube(&cube), Err(Error::Lut(2))));
+    }
+
+    #[test]
+    fn rejects_sizes_out_of_range() {
+        for size in ["1", "129", "two", ""] {
+            let cube = cube(&format!("LUT_3D_SIZE {size}"));
+            assert!(
+                matches!(Lut::parse_cube(&cube), Err(Error::Lut(1))),
+                "{size}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_data_before_size() {
+        let cube = cube("0 0 0\nLUT_3D_SIZE 2");
+        assert!(matches!(Lut::parse_cube(&cube), Err(Error::Lut(1))));
+    }
+
+    #[test]
+    fn rejects_malformed_entries() {
+        for entry in ["0 0", "0 0 0 0", "0 0 x", "0 NaN 0"] {
+            let cube = cube("LUT_3D_SIZE 2").replacen("1 0 0", entry, 1);
+            assert!(
+                matches!(Lut::parse_cube(&cube), Err(Error::Lut(3))),
+                "{entry}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_entry_count() {
+        let extra = cube("LUT_3D_SIZE 2") + "0 0 0\n";
+        assert!(matches!(Lut::parse_cube(&extra), Err(Error::Lut(10))));
+        let missing = cube("LUT_3D_SIZE 2").replacen("1 1 1\n", "", 1);
+        assert!(matches!(Lut::parse_cube(&missing), Err(Error::Lut(8))));
+        assert!(matches!(Lut::parse_cube(""), Err(Error::Lut(1))));
+    }
+}
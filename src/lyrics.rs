@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+/// Parses LRC lyrics into `(time, line)` pairs sorted by time.
+///
+/// Lines with several timestamps (e.g., a repeated chorus) appear once per timestamp,
+/// and the `[offset:ms]` tag is applied. Other metadata tags are ignored.
+pub(crate) fn parse_lrc(lrc: &str) -> Vec<(Duration, String)> {
+    let mut offset = 0i64;
+    let mut lines = Vec::new();
+
+    for line in lrc.lines() {
+        let mut rest = line.trim();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let Some((tag, after)) = tag.split_once(']') else {
+                break;
+            };
+            rest = after;
+            if let Some(value) = tag.strip_prefix("offset:") {
+                offset = value.trim().parse().unwrap_or(0);
+            } else if let Some(time) = parse_timestamp(tag) {
+                times.push(time);
+            }
+        }
+        lines.extend(
+            times
+                .into_iter()
+                .map(|time| (time, rest.trim().to_string())),
+        );
+    }
+
+    // a positive offset makes the lyrics appear sooner
+    let shift = Duration::from_millis(offset.unsigned_abs());
+    for (time, _) in &mut lines {
+        *time = if offset >= 0 {
+            time.saturating_sub(shift)
+        } else {
+            *time + shift
+        };
+    }
+
+    lines.sort_by_key(|(time, _)| *time);
+    lines
+}
+
+/// Parses an `mm:ss.xx` timestamp.
+fn parse_timestamp(tag: &str) -> Option<Duration> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    let seconds: f64 = seconds.trim().parse().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs(minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+/// Index of the line that should be showing at `position`, if any.
+pub(crate) fn line_at(lines: &[(Duration, String)], position: Duration) -> Option<usize> {
+    lines
+        .partition_point(|(time, _)| *time <= position)
+        .checked_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: f64) -> Duration {
+        Duration::from_secs_f64(seconds)
+    }
+
+    #[test]
+    fn repeats_lines_with_several_timestamps() {
+        let lines = parse_lrc("[00:01.00][00:03.50]chorus\n[00:02.00] verse ");
+        assert_eq!(
+            lines,
+            [
+                (secs(1.0), "chorus".to_string()),
+                (secs(2.0), "verse".to_string()),
+                (secs(3.5), "chorus".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_metadata_and_malformed_tags() {
+        let lines = parse_lrc("[ar:Artist]\n[ti:Title]\n[00:xx]bad\n[-1:00]bad\n[00:-1]bad\n[00:01\nplain\n[01:00.5]line");
+        assert_eq!(lines, [(secs(60.5), "line".to_string())]);
+    }
+
+    #[test]
+    fn applies_offset() {
+        assert_eq!(parse_lrc("[offset:500]\n[00:01.00]a")[0].0, secs(0.5));
+        assert_eq!(parse_lrc("[offset:-500]\n[00:01.00]a")[0].0, secs(1.5));
+        // lyrics can't be shown before the start
+        assert_eq!(parse_lrc("[offset:2000]\n[00:01.00]a")[0].0, Duration::ZERO);
+    }
+
+    #[test]
+    fn finds_line_at_position() {
+        let lines = parse_lrc("[00:01]a\n[00:02]b");
+        assert_eq!(line_at(&lines, secs(0.5)), None);
+        assert_eq!(line_at(&lines, secs(1.0)), Some(0));
+        assert_eq!(line_at(&lines, secs(1.5)), Some(0));
+        assert_eq!(line_at(&lines, secs(5.0)), Some(1));
+        assert_eq!(line_at(&[], secs(1.0)), None);
+    }
+}
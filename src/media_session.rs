@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use zbus::blocking::{connection, Connection};
+use zbus::export::futures_util::future::{abortable, AbortHandle};
+use zbus::export::futures_util::StreamExt;
+use zbus::zvariant::{ObjectPath, Value};
+
+const PATH: &str = "/org/mpris/MediaPlayer2";
+const TRACK_ID: &str = "/org/mpris/MediaPlayer2/Track/0";
+
+/// What the desktop asked the player to do, through media keys or a media applet.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Command {
+    Play,
+    Pause,
+    PlayPause,
+    Stop,
+    /// Seek relative to the current position, in microseconds.
+    Seek(i64),
+    /// Seek to an absolute position, in microseconds.
+    SetPosition(i64),
+}
+
+#[derive(Debug, Default)]
+struct Status {
+    paused: bool,
+    position: Duration,
+    duration: Duration,
+    rate: f64,
+}
+
+/// Publishes a video as an MPRIS media player on the session bus,
+/// which is how COSMIC (and other desktops) show playback and route global media keys.
+///
+/// The video is also paused when the screen locks.
+pub(crate) struct MediaSession {
+    connection: Connection,
+    status: Arc<Mutex<Status>>,
+    commands: mpsc::Receiver<Command>,
+    /// Stop watching for the screen to lock, see [`watch_lock`].
+    lock_watchers: Vec<AbortHandle>,
+}
+
+impl std::fmt::Debug for MediaSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaSession").finish_non_exhaustive()
+    }
+}
+
+impl MediaSession {
+    pub(crate) fn new(id: u64, uri: &url::Url) -> zbus::Result<Self> {
+        let (sender, commands) = mpsc::channel();
+        let status = Arc::new(Mutex::new(Status {
+            rate: 1.0,
+            ..Status::default()
+        }));
+
+        let identity = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "iced_video_player".into());
+        let title = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| uri.to_string());
+
+        // every video gets its own instance, as MPRIS expects of apps playing several at once
+        let name = format!(
+            "org.mpris.MediaPlayer2.iced_video_player.instance{}_{id}",
+            std::process::id()
+        );
+        let connection = connection::Builder::session()?
+            .name(name)?
+            .serve_at(PATH, Root { identity })?
+            .serve_at(
+                PATH,
+                Player {
+                    status: Arc::clone(&status),
+                    commands: sender.clone(),
+                    title,
+                    url: uri.to_string(),
+                },
+            )?
+            .build()?;
+
+        let lock_watchers = watch_lock(sender);
+
+        Ok(MediaSession {
+            connection,
+            status,
+            commands,
+            lock_watchers,
+        })
+    }
+
+    /// Takes the next command sent by the desktop, if any.
+    pub(crate) fn command(&self) -> Option<Command> {
+        self.commands.try_recv().ok()
+    }
+
+    /// Reports the playback state to the desktop.
+    pub(crate) fn update(&self, paused: bool, position: Duration, duration: Duration, rate: f64) {
+        let changed = match self.status.lock() {
+            Ok(mut status) => {
+                let changed =
+                    status.paused != paused || status.duration != duration || status.rate != rate;
+                *status = Status {
+                    paused,
+                    position,
+                    duration,
+                    rate,
+                };
+                changed
+            }
+            Err(_) => false,
+        };
+        if !changed {
+            return;
+        }
+
+        let player = match self.connection.object_server().interface::<_, Player>(PATH) {
+            Ok(player) => player,
+            Err(err) => {
+                log::warn!("cannot report playback to the media session: {err}");
+                return;
+            }
+        };
+        let context = player.signal_context();
+        let player = player.get();
+        let result = zbus::block_on(async {
+            player.playback_status_changed(context).await?;
+            player.rate_changed(context).await?;
+            player.minimum_rate_changed(context).await?;
+            player.maximum_rate_changed(context).await?;
+            player.metadata_changed(context).await
+        });
+        if let Err(err) = result {
+            log::warn!("cannot report playback to the media session: {err}");
+        }
+    }
+}
+
+impl Drop for MediaSession {
+    fn drop(&mut self) {
+        for watcher in &self.lock_watchers {
+            watcher.abort();
+        }
+    }
+}
+
+/// Pauses playback when the screen locks, as announced by the screen saver or logind.
+///
+/// Each watches on its own thread and connection, until aborted through the returned handles.
+fn watch_lock(sender: mpsc::Sender<Command>) -> Vec<AbortHandle> {
+    vec![
+        watch_signal(
+            "screen saver",
+            zbus::connection::Builder::session(),
+            (
+                "org.freedesktop.ScreenSaver",
+                "/org/freedesktop/ScreenSaver",
+                "org.freedesktop.ScreenSaver",
+            ),
+            "ActiveChanged",
+            |message| message.body().deserialize::<bool>().unwrap_or(false),
+            sender.clone(),
+        ),
+        watch_signal(
+            "login session",
+            zbus::connection::Builder::system(),
+            (
+                "org.freedesktop.login1",
+                "/org/freedesktop/login1/session/auto",
+                "org.freedesktop.login1.Session",
+            ),
+            "Lock",
+            |_| true,
+            sender,
+        ),
+    ]
+}
+
+/// Sends [`Command::Pause`] whenever `signal` is emitted with a message `pauses` accepts.
+fn watch_signal(
+    what: &'static str,
+    connection: zbus::Result<zbus::connection::Builder<'static>>,
+    (destination, path, interface): (&'static str, &'static str, &'static str),
+    signal: &'static str,
+    pauses: fn(&zbus::Message) -> bool,
+    sender: mpsc::Sender<Command>,
+) -> AbortHandle {
+    let (watching, watcher) = abortable(async move {
+        let connection = connection?.build().await?;
+        let proxy = zbus::Proxy::new(&connection, destination, path, interface).await?;
+        let mut signals = proxy.receive_signal(signal).await?;
+        while let Some(message) = signals.next().await {
+            if pauses(&message) && sender.send(Command::Pause).is_err() {
+                break;
+            }
+        }
+        zbus::Result::Ok(())
+    });
+    std::thread::spawn(move || {
+        if let Ok(Err(err)) = zbus::block_on(watching) {
+            log::debug!("cannot watch the {what}: {err}");
+        }
+    });
+    watcher
+}
+
+struct Root {
+    identity: String,
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        self.identity.clone()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+struct Player {
+    status: Arc<Mutex<Status>>,
+    commands: mpsc::Sender<Command>,
+    title: String,
+    url: String,
+}
+
+impl Player {
+    fn send(&self, command: Command) {
+        let _ = self.commands.send(command);
+    }
+
+    fn status<T>(&self, f: impl FnOnce(&Status) -> T) -> T {
+        // the status is plain data, so it's still good after a panic elsewhere
+        f(&self.status.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn play(&self) {
+        self.send(Command::Play);
+    }
+
+    fn pause(&self) {
+        self.send(Command::Pause);
+    }
+
+    fn play_pause(&self) {
+        self.send(Command::PlayPause);
+    }
+
+    fn stop(&self) {
+        self.send(Command::Stop);
+    }
+
+    fn next(&self) {}
+
+    fn previous(&self) {}
+
+    fn seek(&self, offset: i64) {
+        self.send(Command::Seek(offset));
+    }
+
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+        if track_id.as_str() == TRACK_ID {
+            self.send(Command::SetPosition(position));
+        }
+    }
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        let paused = self.status(|status| status.paused);
+        if paused { "Paused" } else { "Playing" }.into()
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, Value<'static>> {
+        let duration = self.status(|status| status.duration);
+        HashMap::from([
+            (
+                "mpris:trackid".into(),
+                ObjectPath::from_static_str_unchecked(TRACK_ID).into(),
+            ),
+            ("mpris:length".into(), (duration.as_micros() as i64).into()),
+            ("xesam:title".into(), self.title.clone().into()),
+            ("xesam:url".into(), self.url.clone().into()),
+        ])
+    }
+
+    #[zbus(property)]
+    fn position(&self) -> i64 {
+        self.status(|status| status.position.as_micros() as i64)
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        self.status(|status| status.rate)
+    }
+
+    // the rate is only reported, not set, so the range just has to contain it
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        self.status(|status| status.rate.min(1.0))
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        self.status(|status| status.rate.max(1.0))
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Corner;
+use iced::{window, Point, Size, Subscription, Task};
+
+/// Messages driving a [`MiniPlayer`], to be routed back to [`MiniPlayer::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiniPlayerMessage {
+    /// Hand the video off to a mini-player window.
+    Detach,
+    /// The mini-player window opened and takes over showing the video.
+    Opened(window::Id),
+    /// Take the video back, closing the mini-player window.
+    Reattach,
+    /// A window closed; if it was the mini-player, the video is reattached.
+    Closed(window::Id),
+}
+
+/// Hands a playing [`Video`](crate::Video) off to a small always-on-top window and back
+/// (in an app using iced's multi-window `daemon`).
+///
+/// The video itself never moves: the app shows it with a [`VideoPlayer`](crate::VideoPlayer)
+/// in the view of [`MiniPlayer::window`] while detached, and in its main window otherwise.
+/// Windows share the renderer, so playback goes on without restarting the pipeline or re-uploading frames.
+///
+/// Route [`MiniPlayer::subscription`] and any [`MiniPlayerMessage`] (e.g., [`MiniPlayerMessage::Reattach`]
+/// from a button in the mini-player) to [`MiniPlayer::update`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MiniPlayer {
+    size: Size,
+    corner: Corner,
+    opening: Option<window::Id>,
+    window: Option<window::Id>,
+}
+
+impl Default for MiniPlayer {
+    fn default() -> Self {
+        MiniPlayer {
+            size: Size::new(320.0, 180.0),
+            corner: Corner::BottomRight,
+            opening: None,
+            window: None,
+        }
+    }
+}
+
+impl MiniPlayer {
+    /// Creates a reattached mini-player, 320 by 180 big in the bottom right of the screen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size of the mini-player window.
+    pub fn size(self, size: Size) -> Self {
+        MiniPlayer { size, ..self }
+    }
+
+    /// Sets the corner of the screen the mini-player window opens in.
+    pub fn corner(self, corner: Corner) -> Self {
+        MiniPlayer { corner, ..self }
+    }
+
+    /// Get the mini-player window, if the video is detached into it.
+    pub fn window(&self) -> Option<window::Id> {
+        self.window
+    }
+
+    /// Get if the video is detached into the mini-player window.
+    pub fn is_detached(&self) -> bool {
+        self.window.is_some()
+    }
+
+    /// Settings of the mini-player window.
+    pub fn settings(&self) -> window::Settings {
+        const MARGIN: f32 = 24.0;
+
+        fn top_left(_: Size, _: Size) -> Point {
+            Point::new(MARGIN, MARGIN)
+        }
+        fn top_right(window: Size, monitor: Size) -> Point {
+            Point::new(monitor.width - window.width - MARGIN, MARGIN)
+        }
+        fn bottom_left(window: Size, monitor: Size) -> Point {
+            Point::new(MARGIN, monitor.height - window.height - MARGIN)
+        }
+        fn bottom_right(window: Size, monitor: Size) -> Point {
+            Point::new(
+                monitor.width - window.width - MARGIN,
+                monitor.height - window.height - MARGIN,
+            )
+        }
+
+        window::Settings {
+            size: self.size,
+            position: window::Position::SpecificWith(match self.corner {
+                Corner::TopLeft => top_left,
+                Corner::TopRight => top_right,
+                Corner::BottomLeft => bottom_left,
+                Corner::BottomRight => bottom_right,
+            }),
+            min_size: Some(Size::new(160.0, 90.0)),
+            level: window::Level::AlwaysOnTop,
+            ..window::Settings::default()
+        }
+    }
+
+    /// Follows a [`MiniPlayerMessage`], opening or closing the mini-player window as needed.
+    pub fn update(&mut self, message: MiniPlayerMessage) -> Task<MiniPlayerMessage> {
+        match message {
+            MiniPlayerMessage::Detach => {
+                if self.opening.is_some() || self.window.is_some() {
+                    return Task::none();
+                }
+                let (id, open) = window::open(self.settings());
+                // the main window keeps showing the video until the mini-player is there
+                self.opening = Some(id);
+                open.map(MiniPlayerMessage::Opened)
+            }
+            MiniPlayerMessage::Opened(id) => {
+                if self.opening == Some(id) {
+                    self.opening = None;
+                    self.window = Some(id);
+                }
+                Task::none()
+            }
+            MiniPlayerMessage::Reattach => {
+                match self.window.take().or_else(|| self.opening.take()) {
+                    Some(id) => window::close(id),
+                    None => Task::none(),
+                }
+            }
+            MiniPlayerMessage::Closed(id) => {
+                if self.window == Some(id) {
+                    self.window = None;
+                }
+                if self.opening == Some(id) {
+                    self.opening = None;
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Reattaches the video when the mini-player window is closed by the user.
+    pub fn subscription(&self) -> Subscription<MiniPlayerMessage> {
+        window::close_events().map(MiniPlayerMessage::Closed)
+    }
+}
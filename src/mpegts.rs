@@ -0,0 +1,286 @@
+//! Minimal MPEG-TS PAT/PMT parsing, for listing the programs of a transport stream file.
+
+use std::io::Read;
+
+const PACKET_SIZE: usize = 188;
+const SYNC_BYTE: u8 = 0x47;
+/// How far into the file to look for the program tables before giving up.
+const MAX_SCAN: usize = 8 * 1024 * 1024;
+
+/// Kind of an elementary stream in a [`Program`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamKind {
+    /// A video stream.
+    Video,
+    /// An audio stream.
+    Audio,
+    /// Any other stream (e.g., subtitles, teletext or data).
+    Other,
+}
+
+/// An elementary stream of a [`Program`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramStream {
+    /// Packet identifier of the stream.
+    pub pid: u16,
+    /// MPEG-TS `stream_type` of the stream.
+    pub stream_type: u8,
+    /// Kind of the stream, derived from its `stream_type`.
+    pub kind: StreamKind,
+}
+
+/// A program (e.g., a TV channel) of an MPEG-TS multi-program stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Program {
+    /// Program number, as passed to [`Video::select_program`](crate::Video::select_program).
+    pub number: u16,
+    /// Elementary streams (tracks) of the program.
+    pub streams: Vec<ProgramStream>,
+}
+
+fn stream_kind(stream_type: u8) -> StreamKind {
+    match stream_type {
+        0x01 | 0x02 | 0x10 | 0x1b | 0x24 | 0x33 => StreamKind::Video,
+        0x03 | 0x04 | 0x0f | 0x11 | 0x81 | 0x87 => StreamKind::Audio,
+        _ => StreamKind::Other,
+    }
+}
+
+/// Returns the table section starting in a packet's payload, if any.
+fn read_section(packet: &[u8]) -> Option<(u16, &[u8])> {
+    if packet[0] != SYNC_BYTE || packet[1] & 0x40 == 0 {
+        return None;
+    }
+    let pid = (u16::from(packet[1] & 0x1f) << 8) | u16::from(packet[2]);
+    let mut offset = 4;
+    match (packet[3] >> 4) & 0x3 {
+        0x1 => {}
+        0x3 => offset += 1 + usize::from(packet[4]),
+        _ => return None,
+    }
+    let pointer = usize::from(*packet.get(offset)?);
+    let section = packet.get(offset + 1 + pointer..)?;
+    let length = (usize::from(section.get(1)? & 0x0f) << 8) | usize::from(*section.get(2)?);
+    // sections spanning several packets aren't supported; PAT/PMTs rarely do
+    Some((pid, section.get(..3 + length)?))
+}
+
+/// Parses `(program_number, pmt_pid)` pairs from a PAT section.
+fn parse_pat(section: &[u8]) -> Vec<(u16, u16)> {
+    if section[0] != 0x00 || section.len() < 12 {
+        return Vec::new();
+    }
+    section[8..section.len() - 4]
+        .chunks_exact(4)
+        .map(|entry| {
+            let number = u16::from_be_bytes([entry[0], entry[1]]);
+            let pid = (u16::from(entry[2] & 0x1f) << 8) | u16::from(entry[3]);
+            (number, pid)
+        })
+        // program 0 points to the network information table
+        .filter(|&(number, _)| number != 0)
+        .collect()
+}
+
+/// Parses the elementary streams of a PMT section.
+fn parse_pmt(section: &[u8]) -> Option<Vec<ProgramStream>> {
+    if section[0] != 0x02 || section.len() < 16 {
+        return None;
+    }
+    let info_length = (usize::from(section[10] & 0x0f) << 8) | usize::from(section[11]);
+    let mut entries = section.get(12 + info_length..section.len() - 4)?;
+    let mut streams = Vec::new();
+    while entries.len() >= 5 {
+        let stream_type = entries[0];
+        let pid = (u16::from(entries[1] & 0x1f) << 8) | u16::from(entries[2]);
+        let es_info_length = (usize::from(entries[3] & 0x0f) << 8) | usize::from(entries[4]);
+        streams.push(ProgramStream {
+            pid,
+            stream_type,
+            kind: stream_kind(stream_type),
+        });
+        entries = entries.get(5 + es_info_length..)?;
+    }
+    Some(streams)
+}
+
+/// Lists the programs of an MPEG-TS stream read from `reader`.
+pub(crate) fn programs(reader: impl Read) -> std::io::Result<Vec<Program>> {
+    let mut reader = reader.take(MAX_SCAN as u64);
+    let mut packet = [0u8; PACKET_SIZE];
+    let mut pat: Option<Vec<(u16, u16)>> = None;
+    let mut programs: Vec<Program> = Vec::new();
+
+    loop {
+        match reader.read_exact(&mut packet) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+        let Some((pid, section)) = read_section(&packet) else {
+            continue;
+        };
+
+        match pat {
+            None if pid == 0 => pat = Some(parse_pat(section)),
+            None => {}
+            Some(ref pat) => {
+                let Some(&(number, _)) = pat.iter().find(|&&(_, pmt_pid)| pmt_pid == pid) else {
+                    continue;
+                };
+                if programs.iter().any(|program| program.number == number) {
+                    continue;
+                }
+                if let Some(streams) = parse_pmt(section) {
+                    programs.push(Program { number, streams });
+                }
+                if programs.len() == pat.len() {
+                    break;
+                }
+            }
+        }
+    }
+
+    programs.sort_by_key(|program| program.number);
+    Ok(programs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A table section with the fields following its length, and a CRC (which isn't checked).
+    fn section(table_id: u8, fields: &[u8]) -> Vec<u8> {
+        let length = fields.len() + 4;
+        let mut section = vec![table_id, 0xb0 | (length >> 8) as u8, length as u8];
+        section.extend_from_slice(fields);
+        section.extend_from_slice(&[0; 4]);
+        section
+    }
+
+    /// A packet of `pid` starting `section`, after an adaptation field `adaptation` bytes long if any.
+    fn packet(pid: u16, adaptation: Option<u8>, section: &[u8]) -> Vec<u8> {
+        let mut packet = vec![SYNC_BYTE, 0x40 | (pid >> 8) as u8, pid as u8];
+        match adaptation {
+            None => packet.push(0x10),
+            Some(length) => {
+                packet.extend([0x30, length]);
+                packet.resize(packet.len() + usize::from(length), 0xff);
+            }
+        }
+        // pointer field
+        packet.push(0);
+        packet.extend_from_slice(section);
+        packet.resize(PACKET_SIZE, 0xff);
+        packet
+    }
+
+    fn pat(programs: &[(u16, u16)]) -> Vec<u8> {
+        let mut fields = vec![0x00, 0x01, 0xc1, 0x00, 0x00];
+        for &(number, pid) in programs {
+            fields.extend(number.to_be_bytes());
+            fields.extend([0xe0 | (pid >> 8) as u8, pid as u8]);
+        }
+        section(0x00, &fields)
+    }
+
+    /// A PMT of `number` with `(stream_type, pid, es_info_length)` streams.
+    fn pmt(number: u16, streams: &[(u8, u16, u8)]) -> Vec<u8> {
+        let mut fields = number.to_be_bytes().to_vec();
+        // version, section numbers, PCR PID, and a program descriptor two bytes long
+        fields.extend([0xc1, 0x00, 0x00, 0xe1, 0x00, 0xf0, 0x02, 0xaa, 0xbb]);
+        for &(stream_type, pid, info_length) in streams {
+            fields.extend([
+                stream_type,
+                0xe0 | (pid >> 8) as u8,
+                pid as u8,
+                0xf0,
+                info_length,
+            ]);
+            fields.resize(fields.len() + usize::from(info_length), 0xcc);
+        }
+        section(0x02, &fields)
+    }
+
+    fn stream(pid: u16, stream_type: u8, kind: StreamKind) -> ProgramStream {
+        ProgramStream {
+            pid,
+            stream_type,
+            kind,
+        }
+    }
+
+    #[test]
+    fn lists_programs() {
+        let ts = [
+            packet(0, None, &pat(&[(0, 0x10), (2, 0x200), (1, 0x100)])),
+            // payload continuing a section from an earlier packet
+            vec![SYNC_BYTE, 0x01, 0x00, 0x10]
+                .into_iter()
+                .chain(std::iter::repeat(0xff))
+                .take(PACKET_SIZE)
+                .collect(),
+            packet(
+                0x200,
+                Some(7),
+                &pmt(2, &[(0x02, 0x201, 0), (0x81, 0x202, 0)]),
+            ),
+            packet(
+                0x100,
+                None,
+                &pmt(1, &[(0x1b, 0x101, 0), (0x0f, 0x102, 3), (0x06, 0x103, 0)]),
+            ),
+        ]
+        .concat();
+        let programs = programs(ts.as_slice()).unwrap();
+        assert_eq!(
+            programs,
+            [
+                Program {
+                    number: 1,
+                    streams: vec![
+                        stream(0x101, 0x1b, StreamKind::Video),
+                        stream(0x102, 0x0f, StreamKind::Audio),
+                        stream(0x103, 0x06, StreamKind::Other),
+                    ],
+                },
+                Program {
+                    number: 2,
+                    streams: vec![
+                        stream(0x201, 0x02, StreamKind::Video),
+                        stream(0x202, 0x81, StreamKind::Audio),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn waits_for_the_pat() {
+        let ts = [
+            packet(0x100, None, &pmt(1, &[(0x1b, 0x101, 0)])),
+            packet(0, None, &pat(&[(1, 0x100)])),
+        ]
+        .concat();
+        assert!(programs(ts.as_slice()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn skips_malformed_packets() {
+        let mut unsynced = packet(0, None, &pat(&[(1, 0x100)]));
+        unsynced[0] = 0x00;
+        // its stream's descriptors claim to run past the end of the section
+        let mut truncated = pmt(1, &[(0x1b, 0x101, 0)]);
+        truncated[18] = 10;
+        let truncated = packet(0x100, None, &truncated);
+        let ts = [unsynced, packet(0, None, &pat(&[(1, 0x100)])), truncated].concat();
+        assert!(programs(ts.as_slice()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reads_up_to_a_truncated_packet() {
+        let mut ts = packet(0, None, &pat(&[(1, 0x100)]));
+        ts.extend_from_slice(&packet(0x100, None, &pmt(1, &[(0x1b, 0x101, 0)]))[..100]);
+        assert!(programs(ts.as_slice()).unwrap().is_empty());
+    }
+}
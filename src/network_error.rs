@@ -0,0 +1,95 @@
+use gstreamer as gst;
+
+/// What went wrong with a network stream, see [`NetworkError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NetworkErrorKind {
+    /// The server's name couldn't be resolved.
+    Dns,
+    /// The secure connection couldn't be set up (e.g., an invalid certificate).
+    Tls,
+    /// The server refused access (HTTP 401 or 403); credentials or a fresh token may help.
+    Unauthorized,
+    /// The media doesn't exist (HTTP 404 or 410).
+    NotFound,
+    /// The server took too long to respond.
+    Timeout,
+    /// The connection couldn't be established or was lost.
+    Connection,
+    /// The server failed (HTTP 5xx).
+    Server,
+    /// Any other network failure.
+    Other,
+}
+
+/// A network failure of a streamed media, classified from the source's error
+/// so apps can choose between re-authenticating, retrying and giving up.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("{message}")]
+pub struct NetworkError {
+    /// What went wrong.
+    pub kind: NetworkErrorKind,
+    /// HTTP status code of the response, if the server responded.
+    pub status: Option<u16>,
+    /// Error message from the source.
+    pub message: String,
+}
+
+impl NetworkError {
+    /// Classifies a pipeline error, returning `None` if it isn't a network failure.
+    pub fn from_message(err: &gst::message::Error) -> Option<Self> {
+        let error = err.error();
+        let resource = error.kind::<gst::ResourceError>()?;
+        let status = err
+            .details()
+            .and_then(|details| details.get::<u32>("http-status-code").ok())
+            .and_then(|status| u16::try_from(status).ok());
+        let message = error.message().to_string();
+        let debug = err
+            .debug()
+            .map(|debug| debug.to_string())
+            .unwrap_or_default();
+        let text = format!("{message} {debug}").to_lowercase();
+
+        let kind = match (status, resource) {
+            (Some(401 | 403), _) => NetworkErrorKind::Unauthorized,
+            (Some(404 | 410), _) => NetworkErrorKind::NotFound,
+            (Some(408 | 504), _) => NetworkErrorKind::Timeout,
+            (Some(500..=599), _) => NetworkErrorKind::Server,
+            (Some(_), _) => NetworkErrorKind::Other,
+            // souphttpsrc doesn't have dedicated error codes for these
+            _ if text.contains("resolve") => NetworkErrorKind::Dns,
+            _ if ["secure", "tls", "ssl", "certificate"]
+                .iter()
+                .any(|word| text.contains(word)) =>
+            {
+                NetworkErrorKind::Tls
+            }
+            _ if text.contains("timed out") || text.contains("timeout") => {
+                NetworkErrorKind::Timeout
+            }
+            (None, gst::ResourceError::NotAuthorized) => NetworkErrorKind::Unauthorized,
+            (None, gst::ResourceError::NotFound) => NetworkErrorKind::NotFound,
+            (None, gst::ResourceError::OpenRead | gst::ResourceError::Read) => {
+                NetworkErrorKind::Connection
+            }
+            (None, _) => return None,
+        };
+
+        Some(NetworkError {
+            kind,
+            status,
+            message,
+        })
+    }
+
+    /// Whether trying again later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            NetworkErrorKind::Dns
+                | NetworkErrorKind::Timeout
+                | NetworkErrorKind::Connection
+                | NetworkErrorKind::Server
+        )
+    }
+}
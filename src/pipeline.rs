@@ -1,38 +1,270 @@
+use crate::annotation::Vertex;
+use crate::frame::{FrameBuffer, FrameGuard, PixelFormat};
+use crate::hot_reload;
+use crate::styled_subtitle::SubtitleImage;
+use crate::transform::Transform;
+use crate::video::Internal;
+use crate::watermark::Watermark;
+use crate::{Filter, Lut};
+use gstreamer as gst;
+use iced::advanced::image as img;
 use iced_wgpu::primitive::Primitive;
 use iced_wgpu::wgpu;
 use std::{
-    collections::{btree_map::Entry, BTreeMap},
+    collections::BTreeMap,
+    hash::{DefaultHasher, Hash, Hasher},
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc,
     },
 };
 
 #[repr(C)]
 struct Uniforms {
     rect: [f32; 4],
+    crop: [f32; 4],
+    balance: [f32; 4],
+    lut_min: [f32; 4],
+    lut_max: [f32; 4],
+    blend: f32,
+    obscure: f32,
+    transfer: u32,
+    tone_mapping: u32,
+    ten_bit: u32,
+    transform: u32,
+    lut: u32,
+    _padding: [u32; 1],
 }
 
+#[repr(C)]
+struct WatermarkUniforms {
+    rect: [f32; 4],
+    opacity: f32,
+    _padding: [f32; 3],
+}
+
+/// Operator mapping HDR video down to the SDR range of the display, see
+/// [`VideoPlayer::tone_mapping`](crate::VideoPlayer::tone_mapping).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ToneMapping {
+    /// Cut off everything brighter than SDR white.
+    Clip,
+    /// Extended Reinhard, rolling highlights off up to a 1000 nit peak.
+    Reinhard,
+    /// Hable's filmic curve, keeping more contrast in the midtones.
+    #[default]
+    Hable,
+    /// Narkowicz's fit of the ACES filmic curve.
+    Aces,
+}
+
+/// Color adjustments applied when drawing frames, see [`Video::set_color_balance`](crate::Video::set_color_balance).
+///
+/// Ranges follow GStreamer's `videobalance`; the default leaves colors untouched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorBalance {
+    /// Added to the brightness, `-1.0..=1.0`.
+    pub brightness: f32,
+    /// Multiplies the contrast, `0.0..=2.0`.
+    pub contrast: f32,
+    /// Multiplies the saturation, `0.0..=2.0`; `0.0` is grayscale.
+    pub saturation: f32,
+    /// Rotates the hue, `-1.0..=1.0` for -180° to 180°.
+    pub hue: f32,
+}
+
+impl Default for ColorBalance {
+    fn default() -> Self {
+        ColorBalance {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            hue: 0.0,
+        }
+    }
+}
+
+impl ColorBalance {
+    /// The balance with every value clamped to its range, NaN taken as the default.
+    pub(crate) fn clamped(self) -> Self {
+        let clamp = |value: f32, min: f32, max: f32, default: f32| {
+            if value.is_nan() {
+                default
+            } else {
+                value.clamp(min, max)
+            }
+        };
+        ColorBalance {
+            brightness: clamp(self.brightness, -1.0, 1.0, 0.0),
+            contrast: clamp(self.contrast, 0.0, 2.0, 1.0),
+            saturation: clamp(self.saturation, 0.0, 2.0, 1.0),
+            hue: clamp(self.hue, -1.0, 1.0, 0.0),
+        }
+    }
+}
+
+/// Whether colors are written into the render target linear or sRGB-encoded,
+/// see [`VideoTexture::with_format`](crate::VideoTexture::with_format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutputEncoding {
+    /// Linear for sRGB targets (the GPU encodes on write) and floating point targets,
+    /// sRGB-encoded for other targets, which are presented as-is.
+    #[default]
+    Auto,
+    /// sRGB-encoded, e.g. for a pipeline that samples a non-sRGB texture and expects display-ready values.
+    Srgb,
+    /// Linear, e.g. for a pipeline that blends or encodes colors itself.
+    Linear,
+}
+
+impl OutputEncoding {
+    /// Whether colors are written linear to a target of the given `format`.
+    pub fn is_linear(self, format: wgpu::TextureFormat) -> bool {
+        match self {
+            OutputEncoding::Auto => {
+                format.is_srgb()
+                    || matches!(
+                        format,
+                        wgpu::TextureFormat::R16Float
+                            | wgpu::TextureFormat::Rg16Float
+                            | wgpu::TextureFormat::Rgba16Float
+                            | wgpu::TextureFormat::R32Float
+                            | wgpu::TextureFormat::Rg32Float
+                            | wgpu::TextureFormat::Rgba32Float
+                            | wgpu::TextureFormat::Rg11b10Float
+                    )
+            }
+            OutputEncoding::Srgb => false,
+            OutputEncoding::Linear => true,
+        }
+    }
+}
+
+/// Transfer function of the decoded frames, read from their colorimetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) enum Transfer {
+    /// SDR, shown as-is.
+    #[default]
+    Sdr,
+    /// HDR10 (SMPTE ST 2084).
+    Pq,
+    /// Hybrid log-gamma (ARIB STD-B67).
+    Hlg,
+}
+
+impl Transfer {
+    /// Reads the transfer function from the `colorimetry` of raw video `caps`.
+    pub(crate) fn from_caps(caps: &gst::StructureRef) -> Self {
+        let Ok(colorimetry) = caps.get::<&str>("colorimetry") else {
+            return Transfer::Sdr;
+        };
+        // either a well known name, or `range:matrix:transfer:primaries` as numbers
+        match colorimetry {
+            "bt2100-pq" => Transfer::Pq,
+            "bt2100-hlg" => Transfer::Hlg,
+            other => match other.split(':').nth(2) {
+                Some("14") => Transfer::Pq,
+                Some("15") => Transfer::Hlg,
+                _ => Transfer::Sdr,
+            },
+        }
+    }
+}
+
+/// How a video is drawn, besides its frame.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DrawSettings {
+    pub(crate) blend: f32,
+    /// Whether the previous frame is kept to blend from, see [`Interpolation::Blend`](crate::Interpolation::Blend).
+    pub(crate) interpolate: bool,
+    pub(crate) obscure: bool,
+    pub(crate) filter: Filter,
+    pub(crate) transfer: Transfer,
+    pub(crate) tone_mapping: ToneMapping,
+    pub(crate) color_balance: ColorBalance,
+    pub(crate) lut: Option<Lut>,
+    pub(crate) transform: Transform,
+    /// Normalized region of the frame to show, the whole frame if `None`.
+    pub(crate) crop: Option<iced::Rectangle>,
+    pub(crate) annotations: Vec<Vertex>,
+    pub(crate) watermark: Option<(Watermark, iced::Vector)>,
+    /// Styled subtitles drawn over the frame, see [`VideoBuilder::styled_subtitles`](crate::VideoBuilder::styled_subtitles).
+    pub(crate) subtitles: Option<SubtitleImage>,
+}
+
+/// Luma and chroma textures of the current frame, and of the previous one while interpolating.
+type FrameTextures = Vec<(wgpu::Texture, wgpu::Texture)>;
+type FrameViews = Vec<(wgpu::TextureView, wgpu::TextureView)>;
+
 struct VideoEntry {
-    texture_y: wgpu::Texture,
-    texture_uv: wgpu::Texture,
+    // with `Interpolation::Blend`, two frames are kept so the previous one can be blended into the current one
+    textures: FrameTextures,
+    views: FrameViews,
+    format: PixelFormat,
+    current: usize,
     uniforms: wgpu::Buffer,
-    bg0: wgpu::BindGroup,
+    bg0: Vec<wgpu::BindGroup>,
+    /// Set when a frame's textures were imported from a DMA-BUF, which can't be written to.
+    imported: bool,
+    /// The lookup table bound in `bg0`, by id.
+    lut: Option<(u64, wgpu::Texture, wgpu::TextureView)>,
+    filter: Filter,
     alive: Arc<AtomicBool>,
+    annotations: Option<wgpu::Buffer>,
+    annotation_vertices: u32,
+    /// The watermark image by id, as decoded and bound; `None` if it couldn't be decoded.
+    /// Only the current image is kept, it's replaced when the image changes.
+    watermark: Option<(img::Id, Option<(WatermarkTexture, wgpu::BindGroup)>)>,
+    watermark_uniforms: wgpu::Buffer,
+    show_watermark: bool,
+    /// The subtitle layer by the generation of its image, drawn with the watermark pipeline.
+    subtitles: Option<(u64, (WatermarkTexture, wgpu::BindGroup))>,
+    subtitle_uniforms: wgpu::Buffer,
+    show_subtitles: bool,
+    /// Physical size of the render target, which the viewport spans.
+    target: iced::Size<u32>,
 }
 
-struct VideoPipeline {
-    pipeline: wgpu::RenderPipeline,
+/// A decoded watermark image (or subtitle layer), and its size.
+type WatermarkTexture = (wgpu::Texture, wgpu::TextureView, (u32, u32));
+
+pub(crate) struct VideoPipeline {
+    // one variant of the shader per filter, compiled the first time the filter is used
+    pipelines: BTreeMap<Filter, wgpu::RenderPipeline>,
+    annotation_pipeline: wgpu::RenderPipeline,
+    watermark_pipeline: wgpu::RenderPipeline,
+    watermark_layout: wgpu::BindGroupLayout,
+    layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    // whether the shaders write linear colors, or encode them to sRGB themselves
+    linear: bool,
     bg0_layout: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
+    lut_sampler: wgpu::Sampler,
+    // bound in place of a lookup table when there is none
+    no_lut: wgpu::TextureView,
     videos: BTreeMap<u64, VideoEntry>,
+    #[cfg(feature = "shader-hot-reload")]
+    shader_watch: hot_reload::ShaderWatch,
+    // set when a shader failed to compile, so it isn't tried again every frame until it changes
+    #[cfg(feature = "shader-hot-reload")]
+    shader_broken: bool,
+    device: wgpu::Id<wgpu::Device>,
+    lost: Arc<AtomicBool>,
 }
 
 impl VideoPipeline {
-    fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("iced_video_player shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        encoding: OutputEncoding,
+    ) -> Self {
+        let linear = encoding.is_linear(format);
+        let lost = Arc::new(AtomicBool::new(false));
+        let lost_ref = Arc::clone(&lost);
+        device.set_device_lost_callback(move |reason, message| {
+            log::warn!("GPU device lost ({reason:?}): {message}");
+            lost_ref.store(true, Ordering::SeqCst);
         });
 
         let bg0_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -66,7 +298,7 @@ impl VideoPipeline {
                 },
                 wgpu::BindGroupLayoutEntry {
                     binding: 3,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -74,6 +306,42 @@ impl VideoPipeline {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
             ],
         });
 
@@ -83,9 +351,150 @@ impl VideoPipeline {
             push_constant_ranges: &[],
         });
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("iced_video_player sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 1.0,
+            compare: None,
+            anisotropy_clamp: 1,
+            border_color: None,
+        });
+
+        // trilinear interpolation between the entries of lookup tables
+        let lut_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("iced_video_player lut sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..wgpu::SamplerDescriptor::default()
+        });
+        let no_lut = lut_texture(device, 1).create_view(&wgpu::TextureViewDescriptor::default());
+
+        let annotation_pipeline = annotation_pipeline(device, format, linear);
+        let (watermark_pipeline, watermark_layout) = watermark_pipeline(device, format, linear);
+
+        let mut pipeline = VideoPipeline {
+            pipelines: BTreeMap::new(),
+            annotation_pipeline,
+            watermark_pipeline,
+            watermark_layout,
+            layout,
+            format,
+            linear,
+            bg0_layout,
+            sampler,
+            lut_sampler,
+            no_lut,
+            videos: BTreeMap::new(),
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watch: hot_reload::ShaderWatch::new(),
+            #[cfg(feature = "shader-hot-reload")]
+            shader_broken: false,
+            device: device.global_id(),
+            lost,
+        };
+        pipeline.compile(device, Filter::None);
+        pipeline
+    }
+
+    /// Whether the resources were created on `device` and it is still usable.
+    pub(crate) fn is_valid_for(&self, device: &wgpu::Device) -> bool {
+        self.device == device.global_id() && !self.lost.load(Ordering::SeqCst)
+    }
+
+    /// Whether a frame of the video has been uploaded.
+    pub(crate) fn has_video(&self, video_id: u64) -> bool {
+        self.videos.contains_key(&video_id)
+    }
+
+    fn compile(&mut self, device: &wgpu::Device, filter: Filter) {
+        if self.pipelines.contains_key(&filter) {
+            return;
+        }
+        #[cfg(feature = "shader-hot-reload")]
+        if self.shader_broken {
+            return;
+        }
+        match hot_reload::checked(device, || self.build(device, filter)) {
+            Some(pipeline) => {
+                self.pipelines.insert(filter, pipeline);
+            }
+            #[cfg(feature = "shader-hot-reload")]
+            None => self.shader_broken = true,
+            #[cfg(not(feature = "shader-hot-reload"))]
+            None => {}
+        }
+    }
+
+    /// Recompiles every pipeline from the shaders on disk, keeping the old ones of any that fail to compile.
+    #[cfg(feature = "shader-hot-reload")]
+    fn reload(&mut self, device: &wgpu::Device) {
+        log::info!("reloading shaders");
+        self.shader_broken = false;
+        let filters: Vec<_> = self.pipelines.keys().copied().collect();
+        for filter in filters {
+            if let Some(pipeline) = hot_reload::checked(device, || self.build(device, filter)) {
+                self.pipelines.insert(filter, pipeline);
+            }
+        }
+        if let Some(pipeline) = hot_reload::checked(device, || {
+            annotation_pipeline(device, self.format, self.linear)
+        }) {
+            self.annotation_pipeline = pipeline;
+        }
+        if let Some((pipeline, layout)) = hot_reload::checked(device, || {
+            watermark_pipeline(device, self.format, self.linear)
+        }) {
+            self.watermark_pipeline = pipeline;
+            self.watermark_layout = layout;
+            // bound to the old layout, they're loaded and bound again on the next prepare
+            for video in self.videos.values_mut() {
+                if let Some((_, Some(((texture, _, _), _)))) = video.watermark.take() {
+                    texture.destroy();
+                }
+                if let Some((_, ((texture, _, _), _))) = video.subtitles.take() {
+                    texture.destroy();
+                }
+            }
+        }
+    }
+
+    fn build(&self, device: &wgpu::Device, filter: Filter) -> wgpu::RenderPipeline {
+        let function = match filter {
+            Filter::None => "filter_none",
+            Filter::HighContrast => "filter_high_contrast",
+            Filter::EdgeEnhance => "filter_edge_enhance",
+            Filter::Protanopia => "filter_protanopia",
+            Filter::Deuteranopia => "filter_deuteranopia",
+            Filter::Tritanopia => "filter_tritanopia",
+            Filter::Grayscale => "filter_grayscale",
+            Filter::Thermal => "filter_thermal",
+            Filter::FalseColor => "filter_false_color",
+        };
+        let source = format!(
+            "{}\nfn apply_filter(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {{\n    return {function}(rgb, uv);\n}}\n",
+            with_output(
+                hot_reload::source("shader.wgsl", include_str!("shader.wgsl")),
+                self.linear
+            )
+        );
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("iced_video_player shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("iced_video_player pipeline"),
-            layout: Some(&layout),
+            layout: Some(&self.layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: "vs_main",
@@ -102,98 +511,74 @@ impl VideoPipeline {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format,
+                    format: self.format,
                     blend: None,
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
             }),
             multiview: None,
-        });
-
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("iced_video_player sampler"),
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            lod_min_clamp: 0.0,
-            lod_max_clamp: 1.0,
-            compare: None,
-            anisotropy_clamp: 1,
-            border_color: None,
-        });
-
-        VideoPipeline {
-            pipeline,
-            bg0_layout,
-            sampler,
-            videos: BTreeMap::new(),
-        }
+        })
     }
 
-    fn upload(
+    /// Uploads `frame`, or imports it if it was left in a DMA-BUF; the previous frame is kept
+    /// to blend from if `interpolate` is set.
+    ///
+    /// Returns `false` if the frame is incomplete, leaving the textures as they were.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn upload(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         video_id: u64,
         alive: &Arc<AtomicBool>,
-        (width, height): (u32, u32),
-        frame: &[u8],
-    ) {
-        if let Entry::Vacant(entry) = self.videos.entry(video_id) {
-            let texture_y = device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("iced_video_player texture"),
-                size: wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::R8Unorm,
-                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
-            });
+        format: PixelFormat,
+        frame: &FrameGuard<'_>,
+        interpolate: bool,
+    ) -> bool {
+        let (width, height) = frame.size();
+        let frames = if interpolate { 2 } else { 1 };
+        #[cfg(feature = "dmabuf")]
+        if let Some(dmabuf) = frame.dmabuf() {
+            if self.import(device, video_id, dmabuf) {
+                return true;
+            }
+        }
+        let frame: &[u8] = frame;
+        if frame.len() < format.frame_len(width, height) {
+            return false;
+        }
 
-            let texture_uv = device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("iced_video_player texture"),
-                size: wgpu::Extent3d {
-                    width: width / 2,
-                    height: height / 2,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rg8Unorm,
-                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
-            });
+        // frames change size when the sink is rescaled, see `Video::set_output_size`
+        if let Some(video) = self.videos.get(&video_id) {
+            let (texture_y, _) = &video.textures[0];
+            if (texture_y.width(), texture_y.height()) != (width, height)
+                || video.textures.len() != frames
+                || video.imported
+            {
+                let (textures, views) =
+                    frame_textures(device, (width, height), video.format, frames);
+                let lut = video.lut.as_ref().map_or(&self.no_lut, |(_, _, view)| view);
+                let bind_groups = self.bind_groups(device, &views, &video.uniforms, lut);
+                for (texture_y, texture_uv) in &textures {
+                    write_frame(queue, texture_y, texture_uv, (width, height), format, frame);
+                }
 
-            let view_y = texture_y.create_view(&wgpu::TextureViewDescriptor {
-                label: Some("iced_video_player texture view"),
-                format: None,
-                dimension: None,
-                aspect: wgpu::TextureAspect::All,
-                base_mip_level: 0,
-                mip_level_count: None,
-                base_array_layer: 0,
-                array_layer_count: None,
-            });
+                let video = self.videos.get_mut(&video_id).unwrap();
+                for (texture_y, texture_uv) in &video.textures {
+                    texture_y.destroy();
+                    texture_uv.destroy();
+                }
+                video.textures = textures;
+                video.views = views;
+                video.bg0 = bind_groups;
+                video.imported = false;
+                video.current = 0;
+                return true;
+            }
+        }
 
-            let view_uv = texture_uv.create_view(&wgpu::TextureViewDescriptor {
-                label: Some("iced_video_player texture view"),
-                format: None,
-                dimension: None,
-                aspect: wgpu::TextureAspect::All,
-                base_mip_level: 0,
-                mip_level_count: None,
-                base_array_layer: 0,
-                array_layer_count: None,
-            });
+        if !self.videos.contains_key(&video_id) {
+            let (textures, views) = frame_textures(device, (width, height), format, frames);
 
             let buffer = device.create_buffer(&wgpu::BufferDescriptor {
                 label: Some("iced_video_player uniform buffer"),
@@ -202,87 +587,192 @@ impl VideoPipeline {
                 mapped_at_creation: false,
             });
 
-            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("iced_video_player bind group"),
-                layout: &self.bg0_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&view_y),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::TextureView(&view_uv),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: wgpu::BindingResource::Sampler(&self.sampler),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 3,
-                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                            buffer: &buffer,
-                            offset: 0,
-                            size: None,
-                        }),
-                    },
-                ],
-            });
+            let bind_groups = self.bind_groups(device, &views, &buffer, &self.no_lut);
 
-            entry.insert(VideoEntry {
-                texture_y,
-                texture_uv,
-                uniforms: buffer,
-                bg0: bind_group,
-                alive: Arc::clone(alive),
-            });
+            // fill both frames so the first blend doesn't fade in from an empty texture
+            for (texture_y, texture_uv) in &textures {
+                write_frame(queue, texture_y, texture_uv, (width, height), format, frame);
+            }
+
+            self.videos.insert(
+                video_id,
+                VideoEntry {
+                    textures,
+                    views,
+                    format,
+                    current: 0,
+                    uniforms: buffer,
+                    bg0: bind_groups,
+                    imported: false,
+                    lut: None,
+                    filter: Filter::None,
+                    alive: Arc::clone(alive),
+                    annotations: None,
+                    annotation_vertices: 0,
+                    watermark: None,
+                    watermark_uniforms: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("iced_video_player watermark uniform buffer"),
+                        size: std::mem::size_of::<WatermarkUniforms>() as _,
+                        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+                        mapped_at_creation: false,
+                    }),
+                    show_watermark: false,
+                    subtitles: None,
+                    subtitle_uniforms: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("iced_video_player subtitle uniform buffer"),
+                        size: std::mem::size_of::<WatermarkUniforms>() as _,
+                        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+                        mapped_at_creation: false,
+                    }),
+                    show_subtitles: false,
+                    target: iced::Size::new(1, 1),
+                },
+            );
+
+            return true;
         }
 
-        let VideoEntry {
+        let video = self.videos.get_mut(&video_id).unwrap();
+        video.current = (video.current + 1) % video.textures.len();
+        let (texture_y, texture_uv) = &video.textures[video.current];
+        write_frame(
+            queue,
             texture_y,
             texture_uv,
-            ..
-        } = self.videos.get(&video_id).unwrap();
-
-        queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: texture_y,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &frame[..(width * height) as usize],
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(width),
-                rows_per_image: Some(height),
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
+            (width, height),
+            video.format,
+            frame,
         );
+        true
+    }
 
-        queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: texture_uv,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &frame[(width * height) as usize..],
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(width),
-                rows_per_image: Some(height / 2),
-            },
-            wgpu::Extent3d {
-                width: width / 2,
-                height: height / 2,
-                depth_or_array_layers: 1,
-            },
+    /// Swaps the textures of the next frame for ones importing `frame` from its DMA-BUF.
+    ///
+    /// Returns `false` if the device can't import it, or if the first frame (which is always uploaded,
+    /// so there are textures of the right size to fall back to) wasn't uploaded yet.
+    #[cfg(feature = "dmabuf")]
+    fn import(
+        &mut self,
+        device: &wgpu::Device,
+        video_id: u64,
+        frame: &crate::dmabuf::DmaBufFrame,
+    ) -> bool {
+        let Some(video) = self.videos.get_mut(&video_id) else {
+            return false;
+        };
+        let (texture_y, _) = &video.textures[0];
+        if video.format != PixelFormat::Nv12
+            || (texture_y.width(), texture_y.height()) != frame.size()
+        {
+            return false;
+        }
+        let Some((texture_y, texture_uv)) = crate::dmabuf::import(device, frame) else {
+            return false;
+        };
+        let next = (video.current + 1) % video.textures.len();
+        video.views[next] = (
+            texture_y.create_view(&wgpu::TextureViewDescriptor::default()),
+            texture_uv.create_view(&wgpu::TextureViewDescriptor::default()),
         );
+        video.textures[next] = (texture_y, texture_uv);
+
+        let video = &self.videos[&video_id];
+        let lut = video.lut.as_ref().map_or(&self.no_lut, |(_, _, view)| view);
+        let bind_groups = self.bind_groups(device, &video.views, &video.uniforms, lut);
+        let video = self.videos.get_mut(&video_id).unwrap();
+        video.bg0 = bind_groups;
+        video.imported = true;
+        video.current = next;
+        true
+    }
+
+    /// Bind group `i` samples frame `i` of `views` as the current frame and the other one as the previous frame;
+    /// with a single frame, it's bound as both.
+    fn bind_groups(
+        &self,
+        device: &wgpu::Device,
+        views: &FrameViews,
+        uniforms: &wgpu::Buffer,
+        lut: &wgpu::TextureView,
+    ) -> Vec<wgpu::BindGroup> {
+        (0..views.len())
+            .map(|i| {
+                let (view_y, view_uv) = &views[i];
+                let (prev_view_y, prev_view_uv) = &views[(i + 1) % views.len()];
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("iced_video_player bind group"),
+                    layout: &self.bg0_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(view_y),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(view_uv),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::Sampler(&self.sampler),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: uniforms,
+                                offset: 0,
+                                size: None,
+                            }),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 4,
+                            resource: wgpu::BindingResource::TextureView(prev_view_y),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 5,
+                            resource: wgpu::BindingResource::TextureView(prev_view_uv),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 6,
+                            resource: wgpu::BindingResource::TextureView(lut),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 7,
+                            resource: wgpu::BindingResource::Sampler(&self.lut_sampler),
+                        },
+                    ],
+                })
+            })
+            .collect()
+    }
+
+    /// Binds `lut` (or no lookup table) to the video, if it isn't already.
+    fn bind_lut(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        video_id: u64,
+        lut: Option<&Lut>,
+    ) {
+        let Some(video) = self.videos.get(&video_id) else {
+            return;
+        };
+        if video.lut.as_ref().map(|(id, _, _)| *id) == lut.map(Lut::id) {
+            return;
+        }
+        let uploaded = lut.map(|lut| {
+            let texture = lut_texture(device, lut.size);
+            write_lut(queue, &texture, lut);
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            (lut.id(), texture, view)
+        });
+        let view = uploaded.as_ref().map_or(&self.no_lut, |(_, _, view)| view);
+        let bind_groups = self.bind_groups(device, &video.views, &video.uniforms, view);
+
+        let video = self.videos.get_mut(&video_id).unwrap();
+        video.bg0 = bind_groups;
+        if let Some((_, texture, _)) = std::mem::replace(&mut video.lut, uploaded) {
+            texture.destroy();
+        }
     }
 
     fn cleanup(&mut self) {
@@ -293,22 +783,124 @@ impl VideoPipeline {
             .collect();
         for id in ids {
             if let Some(video) = self.videos.remove(&id) {
-                video.texture_y.destroy();
-                video.texture_uv.destroy();
+                for (texture_y, texture_uv) in &video.textures {
+                    texture_y.destroy();
+                    texture_uv.destroy();
+                }
+                if let Some((_, texture, _)) = video.lut {
+                    texture.destroy();
+                }
                 video.uniforms.destroy();
+                video.watermark_uniforms.destroy();
+                if let Some((_, Some(((texture, _, _), _)))) = video.watermark {
+                    texture.destroy();
+                }
+                video.subtitle_uniforms.destroy();
+                if let Some((_, ((texture, _, _), _))) = video.subtitles {
+                    texture.destroy();
+                }
+                if let Some(annotations) = video.annotations {
+                    annotations.destroy();
+                }
             }
         }
     }
 
-    fn prepare(&mut self, queue: &wgpu::Queue, video_id: u64, bounds: &iced::Rectangle) {
-        if let Some(video) = self.videos.get(&video_id) {
+    pub(crate) fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        video_id: u64,
+        bounds: &iced::Rectangle,
+        viewport: &iced_wgpu::graphics::Viewport,
+        settings: &DrawSettings,
+    ) {
+        #[cfg(feature = "shader-hot-reload")]
+        if self.shader_watch.changed() {
+            self.reload(device);
+        }
+        self.compile(device, settings.filter);
+        self.bind_lut(device, queue, video_id, settings.lut.as_ref());
+
+        if let Some(video) = self.videos.get_mut(&video_id) {
+            video.filter = settings.filter;
+
+            // the viewport spans the whole target, so whatever is scrolled out of view is cut off
+            // by the scissor rect rather than squeezed into it; see `draw`
+            let scale = viewport.scale_factor() as f32;
+            let target = viewport.physical_size();
+            video.target = target;
+            let to_clip = |x: f32, y: f32| {
+                [
+                    x * scale / target.width.max(1) as f32 * 2.0 - 1.0,
+                    1.0 - y * scale / target.height.max(1) as f32 * 2.0,
+                ]
+            };
+            let [left, top] = to_clip(bounds.x, bounds.y);
+            let [right, bottom] = to_clip(bounds.x + bounds.width, bounds.y + bounds.height);
+            let rect = [left, top, right, bottom];
+
+            let annotations: Vec<Vertex> = settings
+                .annotations
+                .iter()
+                .map(|vertex| vertex.placed(rect))
+                .collect();
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    annotations.as_ptr() as *const u8,
+                    std::mem::size_of_val(annotations.as_slice()),
+                )
+            };
+            let fits = video
+                .annotations
+                .as_ref()
+                .is_some_and(|buffer| buffer.size() >= bytes.len() as u64);
+            if !bytes.is_empty() {
+                if !fits {
+                    if let Some(buffer) = video.annotations.take() {
+                        buffer.destroy();
+                    }
+                    // grow in powers of two so shapes changing every frame don't reallocate every frame
+                    video.annotations = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("iced_video_player annotation buffer"),
+                        size: bytes.len().next_power_of_two() as _,
+                        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::VERTEX,
+                        mapped_at_creation: false,
+                    }));
+                }
+                if let Some(buffer) = &video.annotations {
+                    queue.write_buffer(buffer, 0, bytes);
+                }
+            }
+            video.annotation_vertices = annotations.len() as u32;
+
             let uniforms = Uniforms {
-                rect: [
-                    bounds.x,
-                    bounds.y,
-                    bounds.x + bounds.width,
-                    bounds.y + bounds.height,
+                rect,
+                crop: settings.crop.map_or([0.0, 0.0, 1.0, 1.0], |crop| {
+                    [crop.x, crop.y, crop.width, crop.height]
+                }),
+                balance: [
+                    settings.color_balance.brightness,
+                    settings.color_balance.contrast,
+                    settings.color_balance.saturation,
+                    settings.color_balance.hue,
                 ],
+                lut_min: settings
+                    .lut
+                    .as_ref()
+                    .map_or([0.0; 4], |lut| with_alpha(lut.domain_min)),
+                lut_max: settings
+                    .lut
+                    .as_ref()
+                    .map_or([1.0; 4], |lut| with_alpha(lut.domain_max)),
+                blend: settings.blend,
+                obscure: if settings.obscure { 1.0 } else { 0.0 },
+                transfer: settings.transfer as u32,
+                tone_mapping: settings.tone_mapping as u32,
+                ten_bit: u32::from(video.format == PixelFormat::P010),
+                transform: settings.transform as u32,
+                lut: u32::from(settings.lut.is_some()),
+                _padding: [0; 1],
             };
             queue.write_buffer(&video.uniforms, 0, unsafe {
                 std::slice::from_raw_parts(
@@ -316,18 +908,116 @@ impl VideoPipeline {
                     std::mem::size_of::<Uniforms>(),
                 )
             });
+
+            video.show_watermark = false;
+            if let Some((watermark, offset)) = &settings.watermark {
+                let id = watermark.image.id();
+                if video.watermark.as_ref().map(|(loaded, _)| *loaded) != Some(id) {
+                    if let Some((_, Some(((texture, _, _), _)))) = video.watermark.take() {
+                        texture.destroy();
+                    }
+                    let loaded = load_watermark(device, queue, &watermark.image).map(|texture| {
+                        let bind_group = watermark_bind_group(
+                            device,
+                            &self.watermark_layout,
+                            &self.sampler,
+                            &texture.1,
+                            &video.watermark_uniforms,
+                        );
+                        (texture, bind_group)
+                    });
+                    video.watermark = Some((id, loaded));
+                }
+                if let Some((_, Some(((_, _, size), _)))) = &video.watermark {
+                    let mark = watermark.bounds(bounds.size(), *size, *offset);
+                    let [left, top] = to_clip(bounds.x + mark.x, bounds.y + mark.y);
+                    let [right, bottom] = to_clip(
+                        bounds.x + mark.x + mark.width,
+                        bounds.y + mark.y + mark.height,
+                    );
+                    let uniforms = WatermarkUniforms {
+                        rect: [left, top, right, bottom],
+                        opacity: watermark.opacity,
+                        _padding: [0.0; 3],
+                    };
+                    queue.write_buffer(&video.watermark_uniforms, 0, unsafe {
+                        std::slice::from_raw_parts(
+                            &uniforms as *const _ as *const u8,
+                            std::mem::size_of::<WatermarkUniforms>(),
+                        )
+                    });
+                    video.show_watermark = true;
+                }
+            }
+
+            video.show_subtitles = false;
+            // like the frame, a locked video shouldn't give away what is said
+            if let Some(image) = settings.subtitles.as_ref().filter(|_| !settings.obscure) {
+                if video.subtitles.as_ref().map(|(generation, _)| *generation)
+                    != Some(image.generation)
+                {
+                    // karaoke changes the image every frame, so the texture is kept while it fits
+                    let loaded = match video.subtitles.take() {
+                        Some((_, loaded)) if loaded.0 .2 == image.size => loaded,
+                        old => {
+                            if let Some((_, ((texture, _, _), _))) = old {
+                                texture.destroy();
+                            }
+                            let texture = overlay_texture(
+                                device,
+                                "iced_video_player subtitle texture",
+                                image.size,
+                            );
+                            let bind_group = watermark_bind_group(
+                                device,
+                                &self.watermark_layout,
+                                &self.sampler,
+                                &texture.1,
+                                &video.subtitle_uniforms,
+                            );
+                            (texture, bind_group)
+                        }
+                    };
+                    write_overlay(queue, &loaded.0 .0, &image.pixels, image.size);
+                    video.subtitles = Some((image.generation, loaded));
+                }
+                let [left, top] = to_clip(
+                    bounds.x + image.bounds.x * bounds.width,
+                    bounds.y + image.bounds.y * bounds.height,
+                );
+                let [right, bottom] = to_clip(
+                    bounds.x + (image.bounds.x + image.bounds.width) * bounds.width,
+                    bounds.y + (image.bounds.y + image.bounds.height) * bounds.height,
+                );
+                let uniforms = WatermarkUniforms {
+                    rect: [left, top, right, bottom],
+                    opacity: 1.0,
+                    _padding: [0.0; 3],
+                };
+                queue.write_buffer(&video.subtitle_uniforms, 0, unsafe {
+                    std::slice::from_raw_parts(
+                        &uniforms as *const _ as *const u8,
+                        std::mem::size_of::<WatermarkUniforms>(),
+                    )
+                });
+                video.show_subtitles = true;
+            }
         }
 
         self.cleanup();
     }
 
-    fn draw(
+    pub(crate) fn draw(
         &self,
         target: &wgpu::TextureView,
         encoder: &mut wgpu::CommandEncoder,
-        viewport: &iced::Rectangle<u32>,
+        clip_bounds: &iced::Rectangle<u32>,
         video_id: u64,
     ) {
+        if self.lost.load(Ordering::SeqCst) {
+            return;
+        }
+
         if let Some(video) = self.videos.get(&video_id) {
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("iced_video_player render pass"),
@@ -344,44 +1034,524 @@ impl VideoPipeline {
                 occlusion_query_set: None,
             });
 
-            pass.set_pipeline(&self.pipeline);
-            pass.set_bind_group(0, &video.bg0, &[]);
-            pass.set_viewport(
-                viewport.x as _,
-                viewport.y as _,
-                viewport.width as _,
-                viewport.height as _,
-                0.0,
-                1.0,
-            );
-            pass.draw(0..4, 0..1);
+            // missing only if hot-reloading and the shader never compiled
+            let Some(pipeline) = self.pipelines.get(&video.filter) else {
+                return;
+            };
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &video.bg0[video.current], &[]);
+            let target = video.target;
+            let x = clip_bounds.x.min(target.width);
+            let y = clip_bounds.y.min(target.height);
+            let width = clip_bounds.width.min(target.width - x);
+            let height = clip_bounds.height.min(target.height - y);
+            if width == 0 || height == 0 {
+                return;
+            }
+            pass.set_viewport(0.0, 0.0, target.width as _, target.height as _, 0.0, 1.0);
+            pass.set_scissor_rect(x, y, width, height);
+            pass.draw(0..6, 0..1);
+
+            if let Some(buffer) = video
+                .annotations
+                .as_ref()
+                .filter(|_| video.annotation_vertices > 0)
+            {
+                pass.set_pipeline(&self.annotation_pipeline);
+                pass.set_vertex_buffer(0, buffer.slice(..));
+                pass.draw(0..video.annotation_vertices, 0..1);
+            }
+
+            if let Some((_, (_, bind_group))) =
+                video.subtitles.as_ref().filter(|_| video.show_subtitles)
+            {
+                pass.set_pipeline(&self.watermark_pipeline);
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.draw(0..4, 0..1);
+            }
+
+            if let Some((_, bind_group)) = video
+                .watermark
+                .as_ref()
+                .and_then(|(_, loaded)| loaded.as_ref())
+                .filter(|_| video.show_watermark)
+            {
+                pass.set_pipeline(&self.watermark_pipeline);
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.draw(0..4, 0..1);
+            }
         }
     }
 }
 
+/// Appends the `LINEAR_OUTPUT` constant the shaders branch on to their `source`.
+fn with_output(source: std::borrow::Cow<'static, str>, linear: bool) -> String {
+    format!("{source}\nconst LINEAR_OUTPUT: bool = {linear};\n")
+}
+
+fn annotation_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    linear: bool,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("iced_video_player annotation shader"),
+        source: wgpu::ShaderSource::Wgsl(
+            with_output(
+                hot_reload::source("annotation.wgsl", include_str!("annotation.wgsl")),
+                linear,
+            )
+            .into(),
+        ),
+    });
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("iced_video_player annotation pipeline layout"),
+        bind_group_layouts: &[],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("iced_video_player annotation pipeline"),
+        layout: Some(&layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Vertex>() as _,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+            }],
+        },
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        multiview: None,
+    })
+}
+
+fn watermark_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    linear: bool,
+) -> (wgpu::RenderPipeline, wgpu::BindGroupLayout) {
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("iced_video_player watermark bind group layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("iced_video_player watermark shader"),
+        source: wgpu::ShaderSource::Wgsl(
+            with_output(
+                hot_reload::source("watermark.wgsl", include_str!("watermark.wgsl")),
+                linear,
+            )
+            .into(),
+        ),
+    });
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("iced_video_player watermark pipeline layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("iced_video_player watermark pipeline"),
+        layout: Some(&layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            ..wgpu::PrimitiveState::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        multiview: None,
+    });
+
+    (pipeline, bind_group_layout)
+}
+
+/// Binds an image drawn with the watermark pipeline, placed by `uniforms`.
+fn watermark_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    view: &wgpu::TextureView,
+    uniforms: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("iced_video_player watermark bind group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: uniforms.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+/// Decodes a watermark image into a texture.
+fn load_watermark(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    handle: &img::Handle,
+) -> Option<WatermarkTexture> {
+    let image = match iced_wgpu::graphics::image::load(handle) {
+        Ok(image) => image,
+        Err(err) => {
+            log::error!("cannot load watermark image: {err}");
+            return None;
+        }
+    };
+    let texture = overlay_texture(
+        device,
+        "iced_video_player watermark texture",
+        image.dimensions(),
+    );
+    write_overlay(queue, &texture.0, &image, texture.2);
+    Some(texture)
+}
+
+/// An sRGB RGBA texture `(width, height)` big, for images drawn over the frame.
+fn overlay_texture(
+    device: &wgpu::Device,
+    label: &str,
+    (width, height): (u32, u32),
+) -> WatermarkTexture {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view, (width, height))
+}
+
+fn write_overlay(
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    pixels: &[u8],
+    (width, height): (u32, u32),
+) {
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        pixels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width * 4),
+            rows_per_image: Some(height),
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// A 3D texture for a lookup table of `size` entries along each axis.
+fn lut_texture(device: &wgpu::Device, size: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("iced_video_player lut texture"),
+        size: wgpu::Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: size,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D3,
+        // 10 bits per channel, filterable everywhere unlike 16-bit normalized and 32-bit float formats
+        format: wgpu::TextureFormat::Rgb10a2Unorm,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
+
+fn write_lut(queue: &wgpu::Queue, texture: &wgpu::Texture, lut: &Lut) {
+    // red changes fastest in `.cube` tables as along x in textures, so entries go in as they are
+    let texels: Vec<u8> = lut
+        .table
+        .iter()
+        .flat_map(|rgb| {
+            let [r, g, b] = rgb.map(|value| (value.clamp(0.0, 1.0) * 1023.0).round() as u32);
+            (r | (g << 10) | (b << 20) | (3 << 30)).to_le_bytes()
+        })
+        .collect();
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &texels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(lut.size * 4),
+            rows_per_image: Some(lut.size),
+        },
+        wgpu::Extent3d {
+            width: lut.size,
+            height: lut.size,
+            depth_or_array_layers: lut.size,
+        },
+    );
+}
+
+fn with_alpha([r, g, b]: [f32; 3]) -> [f32; 4] {
+    [r, g, b, 1.0]
+}
+
+/// Creates the luma and chroma textures of two frames `width` by `height` big, and their views.
+fn frame_textures(
+    device: &wgpu::Device,
+    (width, height): (u32, u32),
+    format: PixelFormat,
+    frames: usize,
+) -> (FrameTextures, FrameViews) {
+    // 16-bit samples go into pairs of 8-bit channels (low byte, high byte) the shader puts back together,
+    // as 16-bit normalized textures need a feature not every adapter has
+    let (format_y, format_uv) = match format {
+        PixelFormat::Nv12 => (wgpu::TextureFormat::R8Unorm, wgpu::TextureFormat::Rg8Unorm),
+        PixelFormat::P010 => (
+            wgpu::TextureFormat::Rg8Unorm,
+            wgpu::TextureFormat::Rgba8Unorm,
+        ),
+    };
+    let textures: FrameTextures = (0..frames)
+        .map(|_| {
+            let texture_y = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("iced_video_player texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: format_y,
+                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+
+            let texture_uv = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("iced_video_player texture"),
+                size: wgpu::Extent3d {
+                    width: width / 2,
+                    height: height / 2,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: format_uv,
+                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+
+            (texture_y, texture_uv)
+        })
+        .collect();
+
+    let views = textures
+        .iter()
+        .map(|(texture_y, texture_uv)| {
+            let view_y = texture_y.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("iced_video_player texture view"),
+                format: None,
+                dimension: None,
+                aspect: wgpu::TextureAspect::All,
+                base_mip_level: 0,
+                mip_level_count: None,
+                base_array_layer: 0,
+                array_layer_count: None,
+            });
+
+            let view_uv = texture_uv.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("iced_video_player texture view"),
+                format: None,
+                dimension: None,
+                aspect: wgpu::TextureAspect::All,
+                base_mip_level: 0,
+                mip_level_count: None,
+                base_array_layer: 0,
+                array_layer_count: None,
+            });
+
+            (view_y, view_uv)
+        })
+        .collect();
+
+    (textures, views)
+}
+
+fn write_frame(
+    queue: &wgpu::Queue,
+    texture_y: &wgpu::Texture,
+    texture_uv: &wgpu::Texture,
+    (width, height): (u32, u32),
+    format: PixelFormat,
+    frame: &[u8],
+) {
+    let bytes = format.bytes_per_sample();
+    let luma = (width * height * bytes) as usize;
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: texture_y,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &frame[..luma],
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width * bytes),
+            rows_per_image: Some(height),
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: texture_uv,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &frame[luma..],
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width * bytes),
+            rows_per_image: Some(height / 2),
+        },
+        wgpu::Extent3d {
+            width: width / 2,
+            height: height / 2,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// Identifies a device across renderers, never `0`.
+fn device_key(device: &wgpu::Device) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    device.global_id().hash(&mut hasher);
+    hasher.finish().max(1)
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct VideoPrimitive {
     video_id: u64,
     alive: Arc<AtomicBool>,
-    frame: Arc<Mutex<Vec<u8>>>,
-    size: (u32, u32),
+    frame: Arc<FrameBuffer>,
+    format: PixelFormat,
     upload_frame: bool,
+    renderer: Arc<AtomicU64>,
+    renderer_reset: Arc<AtomicBool>,
+    scale_factor: Arc<AtomicU32>,
+    settings: DrawSettings,
 }
 
 impl VideoPrimitive {
-    pub fn new(
-        video_id: u64,
-        alive: Arc<AtomicBool>,
-        frame: Arc<Mutex<Vec<u8>>>,
-        size: (u32, u32),
-        upload_frame: bool,
-    ) -> Self {
+    pub fn new(video: &Internal, upload_frame: bool, settings: DrawSettings) -> Self {
         VideoPrimitive {
-            video_id,
-            alive,
-            frame,
-            size,
+            video_id: video.id,
+            alive: Arc::clone(&video.alive),
+            frame: Arc::clone(&video.frame),
+            format: video.format,
             upload_frame,
+            renderer: Arc::clone(&video.renderer),
+            renderer_reset: Arc::clone(&video.renderer_reset),
+            scale_factor: Arc::clone(&video.scale_factor),
+            settings,
         }
     }
 }
@@ -394,26 +1564,49 @@ impl Primitive for VideoPrimitive {
         format: wgpu::TextureFormat,
         storage: &mut iced_wgpu::primitive::Storage,
         bounds: &iced::Rectangle,
-        _viewport: &iced_wgpu::graphics::Viewport,
+        viewport: &iced_wgpu::graphics::Viewport,
     ) {
-        if !storage.has::<VideoPipeline>() {
-            storage.store(VideoPipeline::new(device, format));
+        match storage.get::<VideoPipeline>() {
+            Some(pipeline) if pipeline.is_valid_for(device) => {}
+            // a lost device can't be used until the renderer replaces it
+            Some(pipeline) if pipeline.device == device.global_id() => return,
+            // textures from a lost device are gone, start over on the new one
+            _ => storage.store(VideoPipeline::new(device, format, OutputEncoding::Auto)),
+        }
+
+        let key = device_key(device);
+        let previous = self.renderer.swap(key, Ordering::SeqCst);
+        if previous != 0 && previous != key {
+            self.renderer_reset.store(true, Ordering::SeqCst);
         }
 
+        self.scale_factor
+            .store((viewport.scale_factor() as f32).to_bits(), Ordering::SeqCst);
+
         let pipeline = storage.get_mut::<VideoPipeline>().unwrap();
 
-        if self.upload_frame {
+        // after a reset the latest frame has to be uploaded again, even if it didn't change
+        if self.upload_frame || !pipeline.has_video(self.video_id) {
+            let frame = self.frame.latest();
             pipeline.upload(
                 device,
                 queue,
                 self.video_id,
                 &self.alive,
-                self.size,
-                self.frame.lock().expect("lock frame mutex").as_slice(),
+                self.format,
+                &frame,
+                self.settings.interpolate,
             );
         }
 
-        pipeline.prepare(queue, self.video_id, bounds);
+        pipeline.prepare(
+            device,
+            queue,
+            self.video_id,
+            bounds,
+            viewport,
+            &self.settings,
+        );
     }
 
     fn render(
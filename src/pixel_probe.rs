@@ -0,0 +1,34 @@
+use crate::frame::PixelFormat;
+use crate::video::yuv_to_rgb;
+
+/// A pixel of the frame under the cursor, as reported by
+/// [`VideoPlayer::on_pixel_probe`](crate::VideoPlayer::on_pixel_probe).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelProbe {
+    /// Column of the pixel in the frame.
+    pub x: u32,
+    /// Row of the pixel in the frame.
+    pub y: u32,
+    /// Value of the pixel as decoded, in limited range BT.601 (rounded to 8 bits for 10-bit video).
+    pub yuv: [u8; 3],
+    /// Value of the pixel converted to RGB.
+    pub rgb: [u8; 3],
+}
+
+/// Reads the pixel at `(x, y)` of a `frame` that is `width` by `height` pixels big.
+pub(crate) fn pixel_at(
+    frame: &[u8],
+    format: PixelFormat,
+    width: u32,
+    height: u32,
+    x: u32,
+    y: u32,
+) -> Option<PixelProbe> {
+    let yuv = format.yuv_at(frame, width, height, x, y)?;
+    Some(PixelProbe {
+        x,
+        y,
+        yuv: yuv.map(|c| c.round() as u8),
+        rgb: yuv_to_rgb(yuv).map(|c| c as u8),
+    })
+}
@@ -0,0 +1,300 @@
+use crate::Variant;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of whether the device runs on battery, see [`VideoPlayer::power_status`](crate::VideoPlayer::power_status).
+///
+/// Implemented for closures returning `bool`, so an app can plug in its own provider.
+pub trait PowerStatus {
+    /// Whether the device currently runs on battery.
+    fn on_battery(&self) -> bool;
+}
+
+impl<F: Fn() -> bool> PowerStatus for F {
+    fn on_battery(&self) -> bool {
+        self()
+    }
+}
+
+/// Reads whether a battery is discharging from `/sys/class/power_supply`, re-read at most every 30 seconds.
+///
+/// Never on battery outside Linux.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemPowerStatus;
+
+impl PowerStatus for SystemPowerStatus {
+    fn on_battery(&self) -> bool {
+        static ON_BATTERY: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+        let Ok(mut cached) = ON_BATTERY.lock() else {
+            return false;
+        };
+        match *cached {
+            Some((read, on_battery)) if read.elapsed() < Duration::from_secs(30) => on_battery,
+            _ => {
+                let on_battery = battery_discharging();
+                *cached = Some((Instant::now(), on_battery));
+                on_battery
+            }
+        }
+    }
+}
+
+fn battery_discharging() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    supplies.flatten().any(|supply| {
+        let read = |file| std::fs::read_to_string(supply.path().join(file)).unwrap_or_default();
+        read("type").trim() == "Battery" && read("status").trim() == "Discharging"
+    })
+}
+
+/// The user's choice to trade quality for less data use, see [`QualityPolicy::data_saver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DataSaver {
+    /// Don't limit quality to save data.
+    #[default]
+    Off,
+    /// Limit adaptive streams to 480 lines and 1.5 Mbit/s.
+    On,
+}
+
+impl DataSaver {
+    const BITRATE: u32 = 1_500_000;
+    const HEIGHT: u32 = 480;
+}
+
+/// Limits on the variants an adaptive stream (HLS, DASH, Smooth Streaming) may switch up to,
+/// see [`VideoPlayer::quality_policy`](crate::VideoPlayer::quality_policy).
+///
+/// The default limits nothing, leaving variant selection to the demuxer.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QualityPolicy {
+    /// Cap the bitrate at this share of the measured download bandwidth (e.g., `0.8` for some headroom).
+    pub bandwidth_share: Option<f64>,
+    /// Cap the variant height at the height the video is drawn at, in physical pixels.
+    pub match_window: bool,
+    /// Cap the bitrate at this many bits per second while on battery, see [`PowerStatus`].
+    pub battery_bitrate: Option<u32>,
+    /// The user's data saver setting.
+    pub data_saver: DataSaver,
+}
+
+/// Why a [`QualityCap`] limits quality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CapReason {
+    /// The measured bandwidth, see [`QualityPolicy::bandwidth_share`].
+    Bandwidth,
+    /// The size the video is drawn at, see [`QualityPolicy::match_window`].
+    WindowSize,
+    /// Running on battery, see [`QualityPolicy::battery_bitrate`].
+    Battery,
+    /// The user's [`DataSaver`] setting.
+    DataSaver,
+}
+
+/// Limits currently put on an adaptive stream's variants, as reported by
+/// [`VideoPlayer::on_quality_capped`](crate::VideoPlayer::on_quality_capped).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct QualityCap {
+    /// Highest bitrate allowed in bits per second, if any.
+    pub max_bitrate: Option<u32>,
+    /// Highest variant height allowed, if any.
+    pub max_height: Option<u32>,
+    /// Every limit in effect; empty if uncapped.
+    pub reasons: Vec<CapReason>,
+}
+
+impl QualityCap {
+    /// Get if nothing is capped.
+    pub fn is_uncapped(&self) -> bool {
+        self.reasons.is_empty()
+    }
+
+    fn limit_bitrate(&mut self, bitrate: u32, reason: CapReason) {
+        self.max_bitrate = Some(self.max_bitrate.map_or(bitrate, |max| max.min(bitrate)));
+        self.reasons.push(reason);
+    }
+
+    fn limit_height(&mut self, height: u32, reason: CapReason) {
+        self.max_height = Some(self.max_height.map_or(height, |max| max.min(height)));
+        if !self.reasons.contains(&reason) {
+            self.reasons.push(reason);
+        }
+    }
+
+    /// Puts the cap on an adaptive `demuxer`, as far as it supports caps,
+    /// or holds it to the variant of the `pinned` bitrate instead.
+    fn apply(&self, demuxer: &gst::Element, pinned: Option<u32>) {
+        // 0 lifts the limits
+        let bitrate = pinned.or(self.max_bitrate).unwrap_or(0);
+        if demuxer.has_property("max-bitrate", None) {
+            demuxer.set_property_from_str("max-bitrate", &bitrate.to_string());
+        } else if demuxer.has_property("connection-speed", None) {
+            // older demuxers only take the bandwidth to assume, in kbit/s
+            demuxer.set_property_from_str("connection-speed", &(bitrate / 1000).to_string());
+        }
+        if demuxer.has_property("min-bitrate", None) {
+            let min = pinned.unwrap_or(0);
+            demuxer.set_property_from_str("min-bitrate", &min.to_string());
+        }
+        if demuxer.has_property("max-video-height", None) {
+            let height = self.max_height.filter(|_| pinned.is_none()).unwrap_or(0);
+            demuxer.set_property_from_str("max-video-height", &height.to_string());
+        }
+    }
+}
+
+/// Adaptive demuxers of a pipeline, with the cap they're held to.
+#[derive(Debug, Default)]
+pub(crate) struct Demuxers {
+    elements: Vec<glib::WeakRef<gst::Element>>,
+    cap: QualityCap,
+    /// Variants listed by the stream's manifest, see [`Video::variants`](crate::Video::variants).
+    pub(crate) variants: Vec<Variant>,
+    /// Bitrate of the variant chosen by hand, overriding the cap.
+    pinned: Option<u32>,
+    /// Whether the demuxers stay on the variant playing, pinned once it's known,
+    /// see [`Video::set_auto_quality`](crate::Video::set_auto_quality).
+    held: bool,
+}
+
+impl Demuxers {
+    /// Holds `demuxer` to the cap from now on.
+    pub(crate) fn add(&mut self, demuxer: &gst::Element) {
+        self.cap.apply(demuxer, self.pinned);
+        self.elements.retain(|demuxer| demuxer.upgrade().is_some());
+        self.elements.push(demuxer.downgrade());
+    }
+
+    /// Holds the demuxers to the variant of the `pinned` bitrate, or back to the cap with `None`.
+    pub(crate) fn pin(&mut self, pinned: Option<u32>) {
+        self.pinned = pinned;
+        self.apply();
+    }
+
+    pub(crate) fn pinned(&self) -> Option<u32> {
+        self.pinned
+    }
+
+    /// Holds the demuxers to the variant `playing` (an index into `variants`) if `held`, as soon as
+    /// that's known, or lets them switch variants by themselves again.
+    pub(crate) fn hold(&mut self, held: bool, playing: Option<usize>) {
+        self.held = held;
+        let pinned = playing
+            .filter(|_| held)
+            .and_then(|index| self.variants.get(index))
+            .map(|variant| variant.bitrate);
+        self.pin(pinned);
+    }
+
+    pub(crate) fn held(&self) -> bool {
+        self.held
+    }
+
+    /// Notes the stream switched to the variant at index `playing`, pinning it if the demuxers are
+    /// held but it wasn't known yet.
+    pub(crate) fn playing(&mut self, playing: usize) {
+        if self.held && self.pinned.is_none() {
+            self.hold(true, Some(playing));
+        }
+    }
+
+    fn apply(&self) {
+        for demuxer in self.elements.iter().filter_map(|demuxer| demuxer.upgrade()) {
+            self.cap.apply(&demuxer, self.pinned);
+        }
+    }
+}
+
+/// Measures the bandwidth of an adaptive stream and caps its variants following a [`QualityPolicy`].
+#[derive(Debug, Default)]
+pub(crate) struct QualityCapper {
+    demuxers: Arc<Mutex<Demuxers>>,
+    /// Moving average of the download bandwidth, in bits per second.
+    bandwidth: Option<f64>,
+    checked: Option<Instant>,
+}
+
+impl QualityCapper {
+    /// Whether the element factory `name` makes adaptive demuxers.
+    pub(crate) fn is_adaptive_demuxer(name: &str) -> bool {
+        matches!(
+            name,
+            "hlsdemux" | "hlsdemux2" | "dashdemux" | "dashdemux2" | "mssdemux" | "mssdemux2"
+        )
+    }
+
+    pub(crate) fn demuxers(&self) -> Arc<Mutex<Demuxers>> {
+        Arc::clone(&self.demuxers)
+    }
+
+    /// Measures the bandwidth from an element message, if it is a demuxer's fragment statistics.
+    pub(crate) fn fragment(&mut self, stats: &gst::StructureRef) {
+        // weight of the latest fragment in the average
+        const SMOOTHING: f64 = 0.3;
+
+        if stats.name() != "adaptive-streaming-statistics" {
+            return;
+        }
+        let (Ok(size), Ok(time)) = (
+            stats.get::<u64>("fragment-size"),
+            stats.get::<u64>("fragment-download-time"),
+        ) else {
+            return;
+        };
+        if time == 0 {
+            return;
+        }
+        let bandwidth = size as f64 * 8.0 / Duration::from_nanos(time).as_secs_f64();
+        self.bandwidth = Some(match self.bandwidth {
+            Some(average) => average + (bandwidth - average) * SMOOTHING,
+            None => bandwidth,
+        });
+    }
+
+    /// Re-evaluates the cap (at most every second) for a video drawn `height` tall,
+    /// returning it if it changed.
+    pub(crate) fn update(
+        &mut self,
+        policy: &QualityPolicy,
+        height: f32,
+        power: &dyn PowerStatus,
+    ) -> Option<QualityCap> {
+        if self
+            .checked
+            .is_some_and(|checked| checked.elapsed() < Duration::from_secs(1))
+        {
+            return None;
+        }
+        self.checked = Some(Instant::now());
+
+        let mut cap = QualityCap::default();
+        if let Some((share, bandwidth)) = policy.bandwidth_share.zip(self.bandwidth) {
+            // in steps, so the cap doesn't change with every fragment
+            const STEP: u32 = 250_000;
+            let bitrate = (bandwidth * share) as u32 / STEP * STEP;
+            cap.limit_bitrate(bitrate.max(STEP), CapReason::Bandwidth);
+        }
+        if policy.match_window && height >= 1.0 {
+            cap.limit_height(height.ceil() as u32, CapReason::WindowSize);
+        }
+        if let Some(bitrate) = policy.battery_bitrate.filter(|_| power.on_battery()) {
+            cap.limit_bitrate(bitrate, CapReason::Battery);
+        }
+        if policy.data_saver == DataSaver::On {
+            cap.limit_bitrate(DataSaver::BITRATE, CapReason::DataSaver);
+            cap.limit_height(DataSaver::HEIGHT, CapReason::DataSaver);
+        }
+
+        let mut demuxers = self.demuxers.lock().ok()?;
+        if demuxers.cap == cap {
+            return None;
+        }
+        demuxers.cap = cap.clone();
+        demuxers.apply();
+        Some(cap)
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::OnceLock;
+
+/// Source of the user's "reduce motion" preference, see
+/// [`VideoPlayer::respect_reduced_motion`](crate::VideoPlayer::respect_reduced_motion).
+///
+/// Implemented for closures returning `bool`, so an app can plug in its own setting.
+pub trait MotionPreference {
+    /// Whether the user asked for animations (including autoplaying videos) to be reduced.
+    fn reduce_motion(&self) -> bool;
+}
+
+impl<F: Fn() -> bool> MotionPreference for F {
+    fn reduce_motion(&self) -> bool {
+        self()
+    }
+}
+
+/// Reads the desktop's "reduce motion" setting, read once and then cached.
+///
+/// Currently understands GNOME's (and GTK-based desktops') `enable-animations` setting;
+/// elsewhere motion is never reduced.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemMotionPreference;
+
+impl MotionPreference for SystemMotionPreference {
+    fn reduce_motion(&self) -> bool {
+        static REDUCE_MOTION: OnceLock<bool> = OnceLock::new();
+        *REDUCE_MOTION.get_or_init(|| {
+            std::process::Command::new("gsettings")
+                .args(["get", "org.gnome.desktop.interface", "enable-animations"])
+                .output()
+                .is_ok_and(|output| {
+                    output.status.success()
+                        && String::from_utf8_lossy(&output.stdout).trim() == "false"
+                })
+        })
+    }
+}
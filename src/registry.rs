@@ -0,0 +1,73 @@
+use crate::Video;
+use std::collections::BTreeMap;
+
+/// Identifies a [`Video`] for its whole life, see [`Video::id`].
+///
+/// It's the id the video was created with; unlike the [`Video`]'s pipeline, it survives
+/// [`Video::rebuild_preserving_position`] and similar rebuilds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VideoId(pub(crate) u64);
+
+/// Keeps the [`Video`]s of an app playing many at once, looked up by [`VideoId`].
+///
+/// To route the messages of each [`VideoPlayer`](crate::VideoPlayer) without capturing the id in every callback,
+/// have the player tag them with [`VideoPlayer::with_id`](crate::VideoPlayer::with_id):
+///
+/// ```rust,ignore
+/// VideoPlayer::new(video)
+///     .on_end_of_stream(PlayerMessage::Ended)
+///     .with_id(Message::Player)
+/// ```
+#[derive(Debug, Default)]
+pub struct VideoRegistry {
+    videos: BTreeMap<VideoId, Video>,
+}
+
+impl VideoRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `video`, returning its id.
+    pub fn insert(&mut self, video: Video) -> VideoId {
+        let id = video.id();
+        self.videos.insert(id, video);
+        id
+    }
+
+    /// Removes the video with the given id, returning it.
+    pub fn remove(&mut self, id: VideoId) -> Option<Video> {
+        self.videos.remove(&id)
+    }
+
+    /// Get the video with the given id.
+    pub fn get(&self, id: VideoId) -> Option<&Video> {
+        self.videos.get(&id)
+    }
+
+    /// Get the video with the given id, for changing it.
+    pub fn get_mut(&mut self, id: VideoId) -> Option<&mut Video> {
+        self.videos.get_mut(&id)
+    }
+
+    /// Iterates over the videos, in the order they were created.
+    pub fn iter(&self) -> impl Iterator<Item = (VideoId, &Video)> {
+        self.videos.iter().map(|(id, video)| (*id, video))
+    }
+
+    /// Iterates over the videos for changing them, in the order they were created.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (VideoId, &mut Video)> {
+        self.videos.iter_mut().map(|(id, video)| (*id, video))
+    }
+
+    /// Number of videos in the registry.
+    pub fn len(&self) -> usize {
+        self.videos.len()
+    }
+
+    /// Get if the registry has no videos.
+    pub fn is_empty(&self) -> bool {
+        self.videos.is_empty()
+    }
+}
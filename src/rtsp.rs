@@ -0,0 +1,60 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::time::Duration;
+
+/// How an RTSP stream reaches the player, see [`LowLatency::transport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RtspTransport {
+    /// Try UDP, falling back to TCP (e.g., behind a firewall).
+    #[default]
+    Auto,
+    /// Interleaved in the RTSP connection; reliable, but late packets hold up the ones after them.
+    Tcp,
+    /// Unicast or multicast UDP; lowest latency, lost packets stay lost.
+    Udp,
+}
+
+/// Settings for live RTSP streams (e.g., IP cameras), trading smoothness for latency,
+/// see [`VideoBuilder::low_latency`](crate::VideoBuilder::low_latency).
+///
+/// GStreamer otherwise buffers them for seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LowLatency {
+    /// How the stream is transported.
+    pub transport: RtspTransport,
+    /// How long packets are buffered to put them back in order (`rtspsrc` buffers 2 seconds by default).
+    pub latency: Duration,
+}
+
+impl Default for LowLatency {
+    fn default() -> Self {
+        LowLatency {
+            transport: RtspTransport::Auto,
+            latency: Duration::from_millis(200),
+        }
+    }
+}
+
+impl LowLatency {
+    /// Applies the settings to a `source` playbin created, if it is an RTSP source.
+    pub(crate) fn setup(&self, source: &gst::Element) {
+        let rtsp = source
+            .factory()
+            .is_some_and(|factory| factory.name() == "rtspsrc");
+        if !rtsp {
+            return;
+        }
+        let protocols = match self.transport {
+            RtspTransport::Auto => "udp+udp-mcast+tcp",
+            RtspTransport::Tcp => "tcp",
+            RtspTransport::Udp => "udp+udp-mcast",
+        };
+        source.set_property_from_str("protocols", protocols);
+        source.set_property(
+            "latency",
+            self.latency.as_millis().min(u32::MAX as u128) as u32,
+        );
+        // packets too late to make the latency are dropped instead of stalling everything after them
+        source.set_property("drop-on-latency", true);
+    }
+}
@@ -0,0 +1,308 @@
+use iced::{
+    advanced::{self, graphics::core::event::Status, layout, text, widget, Widget},
+    Element,
+};
+use std::time::Duration;
+
+const LABEL_HEIGHT: f32 = 18.0;
+const TRACK_HEIGHT: f32 = 6.0;
+const MARKER_WIDTH: f32 = 2.0;
+
+/// A labelled point in the media, such as a chapter start or a user bookmark.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cue {
+    /// Position of the cue in the media.
+    pub position: Duration,
+    /// Label shown when hovering the cue's marker.
+    pub label: String,
+}
+
+impl Cue {
+    /// Creates a new cue at `position`.
+    pub fn new(position: Duration, label: impl Into<String>) -> Self {
+        Cue {
+            position,
+            label: label.into(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    dragging: bool,
+}
+
+/// Seek bar showing the playback position of a [`Video`](crate::Video) with markers for [`Cue`]s.
+///
+/// Hovering a marker shows its label, and seeking near a marker snaps to it.
+/// [`VideoPlayer::controls`](crate::VideoPlayer::controls) draws one over the video itself.
+pub struct SeekBar<'a, Message> {
+    position: Duration,
+    duration: Duration,
+    cues: &'a [Cue],
+    snap_distance: f32,
+    width: iced::Length,
+    on_seek: Box<dyn Fn(Duration) -> Message + 'a>,
+    on_release: Option<Message>,
+}
+
+impl<'a, Message> SeekBar<'a, Message> {
+    /// Creates a new seek bar for the current `position` in a media of the given `duration`.
+    ///
+    /// `on_seek` is called with the new position while the bar is clicked or dragged.
+    pub fn new<F>(position: Duration, duration: Duration, on_seek: F) -> Self
+    where
+        F: 'a + Fn(Duration) -> Message,
+    {
+        SeekBar {
+            position,
+            duration,
+            cues: &[],
+            snap_distance: 6.0,
+            width: iced::Length::Fill,
+            on_seek: Box::new(on_seek),
+            on_release: None,
+        }
+    }
+
+    /// Sets the cue points (e.g., chapters) to mark on the `SeekBar`.
+    pub fn cue_points(self, cues: &'a [Cue]) -> Self {
+        SeekBar { cues, ..self }
+    }
+
+    /// Sets how close (in logical pixels) a seek has to land to a cue to snap to it.
+    pub fn snap_distance(self, snap_distance: f32) -> Self {
+        SeekBar {
+            snap_distance,
+            ..self
+        }
+    }
+
+    /// Sets the width of the `SeekBar`.
+    pub fn width(self, width: impl Into<iced::Length>) -> Self {
+        SeekBar {
+            width: width.into(),
+            ..self
+        }
+    }
+
+    /// Message to send when the mouse is released after seeking.
+    pub fn on_release(self, on_release: Message) -> Self {
+        SeekBar {
+            on_release: Some(on_release),
+            ..self
+        }
+    }
+
+    fn x_of(&self, bounds: iced::Rectangle, position: Duration) -> f32 {
+        if self.duration.is_zero() {
+            return bounds.x;
+        }
+        bounds.x + bounds.width * (position.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// The cue whose marker is within snapping distance of `x`, if any.
+    fn cue_near(&self, bounds: iced::Rectangle, x: f32) -> Option<&'a Cue> {
+        self.cues
+            .iter()
+            .map(|cue| (cue, (self.x_of(bounds, cue.position) - x).abs()))
+            .filter(|&(_, distance)| distance <= self.snap_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(cue, _)| cue)
+    }
+
+    fn position_at(&self, bounds: iced::Rectangle, x: f32) -> Duration {
+        if let Some(cue) = self.cue_near(bounds, x) {
+            return cue.position;
+        }
+        let ratio = ((x - bounds.x) / bounds.width).clamp(0.0, 1.0);
+        self.duration.mul_f32(ratio)
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for SeekBar<'a, Message>
+where
+    Message: Clone,
+    Renderer: text::Renderer,
+{
+    fn size(&self) -> iced::Size<iced::Length> {
+        iced::Size {
+            width: self.width,
+            height: iced::Length::Shrink,
+        }
+    }
+
+    fn tag(&self) -> widget::tree::Tag {
+        widget::tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> widget::tree::State {
+        widget::tree::State::new(State::default())
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut widget::Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let size = limits.resolve(
+            self.width,
+            iced::Length::Shrink,
+            iced::Size::new(0.0, LABEL_HEIGHT + TRACK_HEIGHT * 2.0),
+        );
+        layout::Node::new(size)
+    }
+
+    fn draw(
+        &self,
+        _tree: &widget::Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        style: &advanced::renderer::Style,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let track = iced::Rectangle {
+            x: bounds.x,
+            y: bounds.y + LABEL_HEIGHT + TRACK_HEIGHT / 2.0,
+            width: bounds.width,
+            height: TRACK_HEIGHT,
+        };
+
+        renderer.fill_quad(
+            advanced::renderer::Quad {
+                bounds: track,
+                border: iced::border::rounded(TRACK_HEIGHT / 2.0),
+                ..Default::default()
+            },
+            style.text_color.scale_alpha(0.25),
+        );
+
+        renderer.fill_quad(
+            advanced::renderer::Quad {
+                bounds: iced::Rectangle {
+                    width: self.x_of(track, self.position) - track.x,
+                    ..track
+                },
+                border: iced::border::rounded(TRACK_HEIGHT / 2.0),
+                ..Default::default()
+            },
+            style.text_color.scale_alpha(0.75),
+        );
+
+        for cue in self.cues {
+            renderer.fill_quad(
+                advanced::renderer::Quad {
+                    bounds: iced::Rectangle {
+                        x: self.x_of(track, cue.position) - MARKER_WIDTH / 2.0,
+                        y: track.y - TRACK_HEIGHT / 2.0,
+                        width: MARKER_WIDTH,
+                        height: TRACK_HEIGHT * 2.0,
+                    },
+                    ..Default::default()
+                },
+                style.text_color,
+            );
+        }
+
+        let hovered = cursor
+            .position_over(bounds)
+            .and_then(|cursor| self.cue_near(track, cursor.x));
+        if let Some(cue) = hovered {
+            renderer.fill_text(
+                advanced::Text {
+                    content: cue.label.clone(),
+                    bounds: iced::Size::new(bounds.width, LABEL_HEIGHT),
+                    size: renderer.default_size(),
+                    line_height: text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: iced::alignment::Horizontal::Center,
+                    vertical_alignment: iced::alignment::Vertical::Center,
+                    shaping: text::Shaping::Advanced,
+                    wrapping: text::Wrapping::None,
+                },
+                iced::Point::new(
+                    self.x_of(track, cue.position),
+                    bounds.y + LABEL_HEIGHT / 2.0,
+                ),
+                style.text_color,
+                bounds,
+            );
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut widget::Tree,
+        event: iced::Event,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn advanced::Clipboard,
+        shell: &mut advanced::Shell<'_, Message>,
+        _viewport: &iced::Rectangle,
+    ) -> Status {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+
+        match event {
+            iced::Event::Mouse(advanced::mouse::Event::ButtonPressed(
+                advanced::mouse::Button::Left,
+            )) => {
+                if let Some(cursor) = cursor.position_over(bounds) {
+                    state.dragging = true;
+                    shell.publish((self.on_seek)(self.position_at(bounds, cursor.x)));
+                    return Status::Captured;
+                }
+            }
+            iced::Event::Mouse(advanced::mouse::Event::CursorMoved { position })
+                if state.dragging =>
+            {
+                shell.publish((self.on_seek)(self.position_at(bounds, position.x)));
+                return Status::Captured;
+            }
+            iced::Event::Mouse(advanced::mouse::Event::ButtonReleased(
+                advanced::mouse::Button::Left,
+            )) if state.dragging => {
+                state.dragging = false;
+                if let Some(on_release) = self.on_release.clone() {
+                    shell.publish(on_release);
+                }
+                return Status::Captured;
+            }
+            _ => {}
+        }
+
+        Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &widget::Tree,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+        _renderer: &Renderer,
+    ) -> advanced::mouse::Interaction {
+        if cursor.is_over(layout.bounds()) {
+            advanced::mouse::Interaction::Pointer
+        } else {
+            advanced::mouse::Interaction::None
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<SeekBar<'a, Message>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a + Clone,
+    Theme: 'a,
+    Renderer: 'a + text::Renderer,
+{
+    fn from(seek_bar: SeekBar<'a, Message>) -> Self {
+        Self::new(seek_bar)
+    }
+}
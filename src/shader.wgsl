@@ -4,7 +4,27 @@ struct VertexOutput {
 }
 
 struct Uniforms {
+    // left, top, right, bottom in clip space
     rect: vec4<f32>,
+    // region of the frame shown, as x, y, width and height
+    crop: vec4<f32>,
+    // brightness, contrast, saturation and hue, as in `ColorBalance`
+    balance: vec4<f32>,
+    // input range of the lookup table, see `apply_lut`
+    lut_min: vec4<f32>,
+    lut_max: vec4<f32>,
+    blend: f32,
+    obscure: f32,
+    // 0 for SDR, 1 for PQ, 2 for HLG
+    transfer: u32,
+    // 0 clip, 1 Reinhard, 2 Hable, 3 ACES
+    tone_mapping: u32,
+    // 1 if samples are 16-bit, split over two 8-bit channels
+    ten_bit: u32,
+    // how frames are rotated or mirrored, see `transform_uv`
+    transform: u32,
+    // 1 if frames are graded through `tex_lut`
+    lut: u32,
 }
 
 @group(0) @binding(0)
@@ -19,9 +39,22 @@ var s: sampler;
 @group(0) @binding(3)
 var<uniform> uniforms: Uniforms;
 
+@group(0) @binding(4)
+var tex_y_prev: texture_2d<f32>;
+
+@group(0) @binding(5)
+var tex_uv_prev: texture_2d<f32>;
+
+@group(0) @binding(6)
+var tex_lut: texture_3d<f32>;
+
+@group(0) @binding(7)
+var s_lut: sampler;
+
 @vertex
 fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
-    let quad = array<vec2<f32>, 6>(
+    // two triangles covering `rect`, which may reach past the viewport when scrolled partly out of view
+    var quad = array<vec2<f32>, 6>(
         uniforms.rect.xy,
         uniforms.rect.zy,
         uniforms.rect.xw,
@@ -29,12 +62,19 @@ fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
         uniforms.rect.zw,
         uniforms.rect.xw,
     );
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+    );
 
     var out: VertexOutput;
-    out.uv = vec2<f32>(0.0);
-    out.uv.x = select(0.0, 2.0, in_vertex_index == 1u);
-    out.uv.y = select(0.0, 2.0, in_vertex_index == 2u);
-    out.position = vec4<f32>(out.uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 1.0, 1.0);
+    out.position = vec4<f32>(quad[in_vertex_index], 1.0, 1.0);
+    // the mapping is affine, so transforming the corners transforms everything between them
+    out.uv = uniforms.crop.xy + transform_uv(corners[in_vertex_index]) * uniforms.crop.zw;
     return out;
 }
 
@@ -44,20 +84,263 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let yuv2g = vec3<f32>(1.164, -0.391, -0.813);
     let yuv2b = vec3<f32>(1.164, 2.018, 0.0);
 
-    var yuv = vec3<f32>(0.0);
-    yuv.x = textureSample(tex_y, s, in.uv).r - 0.0625;
-    yuv.y = textureSample(tex_uv, s, in.uv).r - 0.5;
-    yuv.z = textureSample(tex_uv, s, in.uv).g - 0.5;
+    // obscured content is pixelated into large blocks, each showing the color at its center
+    let blocks = vec2<f32>(24.0);
+    let uv = select(in.uv, (floor(in.uv * blocks) + vec2<f32>(0.5)) / blocks, uniforms.obscure > 0.5);
+
+    let offset = vec3<f32>(0.0625, 0.5, 0.5);
+    var yuv = vec3<f32>(sample_y(tex_y, uv), sample_uv(tex_uv, uv)) - offset;
+    var yuv_prev = vec3<f32>(sample_y(tex_y_prev, uv), sample_uv(tex_uv_prev, uv)) - offset;
+
+    // blend of 1.0 shows only the current frame
+    yuv = mix(yuv_prev, yuv, uniforms.blend);
+
+    // color balance, like `videobalance`: contrast and brightness on luma, hue and saturation on chroma
+    let balance = uniforms.balance;
+    let hue = balance.w * 3.14159265;
+    let chroma = mat2x2<f32>(cos(hue), sin(hue), -sin(hue), cos(hue)) * yuv.yz;
+    yuv = vec3<f32>(yuv.x * balance.y + balance.x, chroma * balance.z);
 
     var rgb = vec3<f32>(0.0);
-    rgb.x = dot(yuv, yuv2r);
-    rgb.y = dot(yuv, yuv2g);
-    rgb.z = dot(yuv, yuv2b);
+    if uniforms.transfer == 0u {
+        rgb.x = dot(yuv, yuv2r);
+        rgb.y = dot(yuv, yuv2g);
+        rgb.z = dot(yuv, yuv2b);
+    } else {
+        // HDR video is BT.2020, tone-mapped back into the sRGB encoding the filters expect
+        rgb.x = dot(yuv, vec3<f32>(1.164, 0.0, 1.679));
+        rgb.y = dot(yuv, vec3<f32>(1.164, -0.187, -0.650));
+        rgb.z = dot(yuv, vec3<f32>(1.164, 2.142, 0.0));
+        rgb = tone_map_hdr(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)));
+    }
 
-    let threshold = rgb <= vec3<f32>(0.04045);
-    let hi = pow((rgb + vec3<f32>(0.055)) / vec3<f32>(1.055), vec3<f32>(2.4));
-    let lo = rgb * vec3<f32>(1.0 / 12.92);
-    rgb = select(hi, lo, threshold);
+    if uniforms.lut != 0u {
+        rgb = apply_lut(rgb);
+    }
+
+    // `apply_filter` is appended per pipeline variant, calling one of the `filter_*` functions below
+    rgb = clamp(apply_filter(rgb, uv), vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // `LINEAR_OUTPUT` is appended per target: sRGB targets encode on write, so they're given linear colors
+    if LINEAR_OUTPUT {
+        let threshold = rgb <= vec3<f32>(0.04045);
+        let hi = pow((rgb + vec3<f32>(0.055)) / vec3<f32>(1.055), vec3<f32>(2.4));
+        let lo = rgb * vec3<f32>(1.0 / 12.92);
+        rgb = select(hi, lo, threshold);
+    }
 
     return vec4<f32>(rgb, 1.0);
 }
+
+// where `uv` of the transformed frame is in the original frame, matching `Transform::source`
+fn transform_uv(uv: vec2<f32>) -> vec2<f32> {
+    switch uniforms.transform {
+        // rotated 90, 180 and 270 degrees clockwise
+        case 1u: { return vec2<f32>(uv.y, 1.0 - uv.x); }
+        case 2u: { return vec2<f32>(1.0) - uv; }
+        case 3u: { return vec2<f32>(1.0 - uv.y, uv.x); }
+        // mirrored horizontally, along the main diagonal, vertically and along the other diagonal
+        case 4u: { return vec2<f32>(1.0 - uv.x, uv.y); }
+        case 5u: { return uv.yx; }
+        case 6u: { return vec2<f32>(uv.x, 1.0 - uv.y); }
+        case 7u: { return vec2<f32>(1.0) - uv.yx; }
+        default: { return uv; }
+    }
+}
+
+// grades `rgb` through the 3D lookup table, interpolating between its entries
+fn apply_lut(rgb: vec3<f32>) -> vec3<f32> {
+    let size = f32(textureDimensions(tex_lut).x);
+    let normalized = clamp(
+        (rgb - uniforms.lut_min.rgb) / (uniforms.lut_max.rgb - uniforms.lut_min.rgb),
+        vec3<f32>(0.0),
+        vec3<f32>(1.0),
+    );
+    // entries sit at texel centers, so the ends of the range map half a texel in
+    let coords = (normalized * (size - 1.0) + vec3<f32>(0.5)) / size;
+    return textureSampleLevel(tex_lut, s_lut, coords, 0.0).rgb;
+}
+
+// weights putting a (low byte, high byte) pair back together into a 16-bit sample
+const WORD: vec2<f32> = vec2<f32>(255.0 / 65535.0, 65280.0 / 65535.0);
+
+fn sample_y(tex: texture_2d<f32>, uv: vec2<f32>) -> f32 {
+    let texel = textureSample(tex, s, uv);
+    return select(texel.r, dot(texel.rg, WORD), uniforms.ten_bit != 0u);
+}
+
+fn sample_uv(tex: texture_2d<f32>, uv: vec2<f32>) -> vec2<f32> {
+    let texel = textureSample(tex, s, uv);
+    return select(texel.rg, vec2<f32>(dot(texel.rg, WORD), dot(texel.ba, WORD)), uniforms.ten_bit != 0u);
+}
+
+// nits of SDR reference white, per ITU-R BT.2408
+const SDR_WHITE: f32 = 203.0;
+// nits the tone-mapping curves roll off at
+const HDR_PEAK: f32 = 1000.0;
+
+// SMPTE ST 2084, returning nits
+fn pq_eotf(rgb: vec3<f32>) -> vec3<f32> {
+    let m1 = 0.1593017578125;
+    let m2 = 78.84375;
+    let c1 = 0.8359375;
+    let c2 = 18.8515625;
+    let c3 = 18.6875;
+    let p = pow(rgb, vec3<f32>(1.0 / m2));
+    let l = max(p - vec3<f32>(c1), vec3<f32>(0.0)) / (vec3<f32>(c2) - c3 * p);
+    return pow(l, vec3<f32>(1.0 / m1)) * 10000.0;
+}
+
+// ARIB STD-B67 inverse OETF and the BT.2100 OOTF for a display of `HDR_PEAK` nits, returning nits
+fn hlg_eotf(rgb: vec3<f32>) -> vec3<f32> {
+    let a = 0.17883277;
+    let b = 0.28466892;
+    let c = 0.55991073;
+    let lo = rgb * rgb / 3.0;
+    let hi = (exp((rgb - vec3<f32>(c)) / a) + vec3<f32>(b)) / 12.0;
+    let scene = select(hi, lo, rgb <= vec3<f32>(0.5));
+    let luminance = dot(scene, vec3<f32>(0.2627, 0.6780, 0.0593));
+    return scene * pow(max(luminance, 1e-6), 0.2) * HDR_PEAK;
+}
+
+fn hable(x: vec3<f32>) -> vec3<f32> {
+    let a = 0.15;
+    let b = 0.50;
+    let c = 0.10;
+    let d = 0.20;
+    let e = 0.02;
+    let f = 0.30;
+    return ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f;
+}
+
+// maps linear light relative to SDR white into 0..1
+fn tone_map(x: vec3<f32>) -> vec3<f32> {
+    let white = HDR_PEAK / SDR_WHITE;
+    switch uniforms.tone_mapping {
+        case 1u: {
+            return x * (vec3<f32>(1.0) + x / (white * white)) / (vec3<f32>(1.0) + x);
+        }
+        case 2u: {
+            return hable(x * 2.0) / hable(vec3<f32>(white * 2.0));
+        }
+        case 3u: {
+            return (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14);
+        }
+        default: {
+            return x;
+        }
+    }
+}
+
+fn tone_map_hdr(rgb: vec3<f32>) -> vec3<f32> {
+    let nits = select(pq_eotf(rgb), hlg_eotf(rgb), uniforms.transfer == 2u);
+    // BT.2020 to BT.709 primaries, one row per output channel
+    let linear = max(nits / SDR_WHITE * mat3x3<f32>(
+        vec3<f32>(1.6605, -0.5876, -0.0728),
+        vec3<f32>(-0.1246, 1.1329, -0.0083),
+        vec3<f32>(-0.0182, -0.1006, 1.1187),
+    ), vec3<f32>(0.0));
+    let mapped = clamp(tone_map(linear), vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // sRGB encode, `fs_main` decodes it again after filtering
+    let lo = mapped * 12.92;
+    let hi = 1.055 * pow(mapped, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(hi, lo, mapped <= vec3<f32>(0.0031308));
+}
+
+fn filter_none(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    return rgb;
+}
+
+fn filter_high_contrast(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    // S-curve around mid-grey, then push saturation
+    let curved = smoothstep(vec3<f32>(0.0), vec3<f32>(1.0), rgb);
+    let contrasted = mix(rgb, curved, 0.8) * 1.3 - vec3<f32>(0.15);
+    let luma = dot(contrasted, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return mix(vec3<f32>(luma), contrasted, 1.2);
+}
+
+fn filter_edge_enhance(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    // unsharp mask on luma
+    let texel = vec2<f32>(1.0) / vec2<f32>(textureDimensions(tex_y));
+    let center = sample_y(tex_y, uv);
+    let neighbors = sample_y(tex_y, uv + vec2<f32>(texel.x, 0.0))
+        + sample_y(tex_y, uv - vec2<f32>(texel.x, 0.0))
+        + sample_y(tex_y, uv + vec2<f32>(0.0, texel.y))
+        + sample_y(tex_y, uv - vec2<f32>(0.0, texel.y));
+    let edge = (center * 4.0 - neighbors) * 1.164;
+    return rgb + vec3<f32>(edge * 1.5);
+}
+
+// daltonization: simulate the deficiency, then move the lost information into channels still seen
+fn daltonize(rgb: vec3<f32>, simulation: mat3x3<f32>) -> vec3<f32> {
+    let lost = rgb - rgb * simulation;
+    return rgb + vec3<f32>(0.0, lost.r * 0.7 + lost.g, lost.r * 0.7 + lost.b);
+}
+
+fn daltonize_blue(rgb: vec3<f32>, simulation: mat3x3<f32>) -> vec3<f32> {
+    let lost = rgb - rgb * simulation;
+    return rgb + vec3<f32>(lost.b * 0.7 + lost.r, lost.b * 0.7 + lost.g, 0.0);
+}
+
+// simulation matrices from Machado et al. (2009), one row per output channel
+fn filter_protanopia(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    return daltonize(rgb, mat3x3<f32>(
+        vec3<f32>(0.152286, 1.052583, -0.204868),
+        vec3<f32>(0.114503, 0.786281, 0.099216),
+        vec3<f32>(-0.003882, -0.048116, 1.051998),
+    ));
+}
+
+fn filter_deuteranopia(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    return daltonize(rgb, mat3x3<f32>(
+        vec3<f32>(0.367322, 0.860646, -0.227968),
+        vec3<f32>(0.280085, 0.672501, 0.047413),
+        vec3<f32>(-0.011820, 0.042940, 0.968881),
+    ));
+}
+
+fn filter_tritanopia(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    return daltonize_blue(rgb, mat3x3<f32>(
+        vec3<f32>(1.255528, -0.076749, -0.178779),
+        vec3<f32>(-0.078411, 0.930809, 0.147602),
+        vec3<f32>(0.004733, 0.691367, 0.303900),
+    ));
+}
+
+fn luma(rgb: vec3<f32>) -> f32 {
+    return clamp(dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722)), 0.0, 1.0);
+}
+
+fn filter_grayscale(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    return vec3<f32>(luma(rgb));
+}
+
+// "ironbow": black, purple, red, orange, yellow, white at even steps
+fn filter_thermal(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let t = luma(rgb) * 5.0;
+    if t < 1.0 {
+        return mix(vec3<f32>(0.0, 0.0, 0.0), vec3<f32>(0.35, 0.0, 0.55), t);
+    } else if t < 2.0 {
+        return mix(vec3<f32>(0.35, 0.0, 0.55), vec3<f32>(0.85, 0.1, 0.2), t - 1.0);
+    } else if t < 3.0 {
+        return mix(vec3<f32>(0.85, 0.1, 0.2), vec3<f32>(1.0, 0.5, 0.0), t - 2.0);
+    } else if t < 4.0 {
+        return mix(vec3<f32>(1.0, 0.5, 0.0), vec3<f32>(1.0, 0.9, 0.1), t - 3.0);
+    }
+    return mix(vec3<f32>(1.0, 0.9, 0.1), vec3<f32>(1.0, 1.0, 1.0), t - 4.0);
+}
+
+// "jet": blue, cyan, green, yellow, red as brightness rises
+fn filter_false_color(rgb: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let t = luma(rgb) * 4.0;
+    return clamp(
+        vec3<f32>(
+            min(t - 1.5, 4.5 - t),
+            min(t - 0.5, 3.5 - t),
+            min(t + 0.5, 2.5 - t),
+        ),
+        vec3<f32>(0.0),
+        vec3<f32>(1.0),
+    );
+}
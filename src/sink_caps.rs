@@ -0,0 +1,131 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use std::sync::{Arc, Mutex};
+
+/// What the video sink accepts: the caps the video was built with, narrowed down to the format frames
+/// are decoded in and to the size they're shown at.
+///
+/// Shared between the pipeline (which learns the decoded format and size) and the [`Video`](crate::Video)
+/// (which scales the size), so neither undoes the other when setting the sink's caps.
+#[derive(Debug, Default)]
+pub(crate) struct SinkCaps {
+    base: Option<gst::Caps>,
+    /// Format, colorimetry and chroma siting of frames the shader samples as decoded.
+    pub(crate) native: Option<gst::Structure>,
+    /// Size frames are decoded at, which changes when switching video tracks, see `Video::set_video_track`.
+    pub(crate) decoded: Option<(i32, i32)>,
+    /// Size frames are scaled down to, see `Video::set_output_size`.
+    pub(crate) scaled: Option<(i32, i32)>,
+}
+
+impl SinkCaps {
+    pub(crate) fn new(base: Option<gst::Caps>) -> Self {
+        SinkCaps {
+            base,
+            ..SinkCaps::default()
+        }
+    }
+
+    /// The caps for the sink, `None` to accept anything.
+    pub(crate) fn caps(&self) -> Option<gst::Caps> {
+        if self.base.is_none() && self.native.is_none() && self.size().is_none() {
+            return None;
+        }
+        let mut caps = self
+            .base
+            .clone()
+            .unwrap_or_else(|| gst::Caps::new_empty_simple("video/x-raw"));
+        for (structure, features) in caps.make_mut().iter_with_features_mut() {
+            // DMA-BUF frames are described by their own format fields, which the native format would break
+            let system_memory =
+                features.is_empty() || features.contains(gst::CAPS_FEATURE_MEMORY_SYSTEM_MEMORY);
+            if let Some(native) = self.native.as_ref().filter(|_| system_memory) {
+                for (field, value) in native.iter() {
+                    structure.set_value(field, value.clone());
+                }
+            }
+            if let Some((width, height)) = self.size() {
+                structure.set("width", width);
+                structure.set("height", height);
+            }
+        }
+        Some(caps)
+    }
+
+    fn size(&self) -> Option<(i32, i32)> {
+        self.scaled
+    }
+
+    /// Sets the caps on `sink`; frames already flowing are renegotiated if `renegotiate` is set.
+    pub(crate) fn apply(&self, sink: &gst_app::AppSink, renegotiate: bool) {
+        sink.set_caps(self.caps().as_ref());
+        if renegotiate {
+            if let Some(pad) = sink.static_pad("sink") {
+                pad.push_event(gst::event::Reconfigure::new());
+            }
+        }
+    }
+}
+
+/// Records the size of the frames decoded for `sink`, as announced by the caps reaching its bin's `pad`.
+///
+/// A new size (e.g., after switching video tracks) drops the scaled size, which was fit to the old one,
+/// so the sink accepts the new one until [`Video::set_output_size`](crate::Video::set_output_size) fits it again.
+pub(crate) fn track_decoded_size(
+    pad: &gst::Pad,
+    sink: &gst_app::AppSink,
+    sink_caps: Arc<Mutex<SinkCaps>>,
+) {
+    let sink = sink.clone();
+    pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_, info| {
+        let Some(gst::PadProbeData::Event(ref event)) = info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let gst::EventView::Caps(decoded) = event.view() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Some(size) = decoded.caps().structure(0).and_then(|structure| {
+            Some((
+                structure.get::<i32>("width").ok()?,
+                structure.get::<i32>("height").ok()?,
+            ))
+        }) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Ok(mut sink_caps) = sink_caps.lock() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        if sink_caps
+            .decoded
+            .replace(size)
+            .is_some_and(|old| old != size)
+            && sink_caps.scaled.take().is_some()
+        {
+            sink_caps.apply(&sink, false);
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+/// Size frames `source` pixels big (each `pixel_aspect` wide) are scaled down to, to be shown within `bounds`
+/// with their aspect ratio kept, or `None` if they are about that small already.
+///
+/// Width is kept a multiple of 4 and height even, so the planes of a frame are laid out without padding.
+pub(crate) fn fit(source: (i32, i32), pixel_aspect: f64, bounds: (u32, u32)) -> Option<(i32, i32)> {
+    let (width, height) = (source.0.max(1) as f64, source.1.max(1) as f64);
+    let scale = (bounds.0 as f64 / (width * pixel_aspect)).min(bounds.1 as f64 / height);
+    if !scale.is_finite() || scale >= 0.9 {
+        return None;
+    }
+    let round_up = |value: f64, multiple: i32| {
+        ((value.ceil() as i32).max(1) + multiple - 1) / multiple * multiple
+    };
+    Some((round_up(width * scale, 4), round_up(height * scale, 2)))
+}
+
+/// Whether `a` and `b` are within a tenth of each other, so scaling from one to the other isn't worth renegotiating.
+pub(crate) fn similar(a: (i32, i32), b: (i32, i32)) -> bool {
+    let close = |a: i32, b: i32| (a - b).abs() * 10 <= a.max(b);
+    close(a.0, b.0) && close(a.1, b.1)
+}
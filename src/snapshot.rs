@@ -0,0 +1,146 @@
+use crate::Error;
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use gstreamer_app::prelude::*;
+use std::time::Duration;
+
+/// Where in the media a snapshot was taken, for its file name and metadata.
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot {
+    pub(crate) title: String,
+    pub(crate) source: String,
+    pub(crate) position: Duration,
+    pub(crate) framerate: f64,
+}
+
+impl Snapshot {
+    /// Number of the frame, counting from zero.
+    pub(crate) fn frame(&self) -> u64 {
+        (self.position.as_secs_f64() * self.framerate).round() as u64
+    }
+
+    /// `HH:MM:SS:FF` timecode of the frame, with `separator` between the fields.
+    pub(crate) fn timecode(&self, separator: char) -> String {
+        let fps = (self.framerate.round() as u64).max(1);
+        let frame = self.frame();
+        let seconds = frame / fps;
+        format!(
+            "{:02}{separator}{:02}{separator}{:02}{separator}{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            frame % fps,
+        )
+    }
+
+    /// Fills `{title}`, `{timecode}` and `{frame}` in a file name template.
+    pub(crate) fn file_name(&self, template: &str) -> String {
+        // keep the title from leaving the directory or tripping up file systems
+        let title: String = self
+            .title
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect();
+        template
+            .replace("{title}", &title)
+            .replace("{timecode}", &self.timecode('-'))
+            .replace("{frame}", &self.frame().to_string())
+    }
+
+    /// PNG text metadata tracing the snapshot back to its frame.
+    pub(crate) fn metadata(&self) -> [(&'static str, String); 4] {
+        [
+            ("Title", self.title.clone()),
+            ("Source", self.source.clone()),
+            ("Timecode", self.timecode(':')),
+            ("Frame", self.frame().to_string()),
+        ]
+    }
+}
+
+/// Encodes an RGBA image as PNG, with GStreamer's `pngenc`.
+pub(crate) fn encode_png(width: u32, height: u32, rgba: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let pipeline = gst::parse::launch(
+        "appsrc name=iced_src ! videoconvert ! pngenc snapshot=true ! appsink name=iced_png",
+    )?
+    .downcast::<gst::Pipeline>()
+    .map_err(|_| Error::Cast)?;
+    let src = pipeline
+        .by_name("iced_src")
+        .and_then(|src| src.downcast::<gst_app::AppSrc>().ok())
+        .ok_or_else(|| Error::Element("appsrc".into()))?;
+    let sink = pipeline
+        .by_name("iced_png")
+        .and_then(|sink| sink.downcast::<gst_app::AppSink>().ok())
+        .ok_or_else(|| Error::AppSink("iced_png".into()))?;
+
+    src.set_caps(Some(
+        &gst::Caps::builder("video/x-raw")
+            .field("format", "RGBA")
+            .field("width", width as i32)
+            .field("height", height as i32)
+            .field("framerate", gst::Fraction::new(0, 1))
+            .build(),
+    ));
+
+    pipeline.set_state(gst::State::Playing)?;
+    let png = (|| {
+        src.push_buffer(gst::Buffer::from_mut_slice(rgba))
+            .map_err(|_| Error::Encode)?;
+        src.end_of_stream().map_err(|_| Error::Encode)?;
+        let sample = sink.pull_sample()?;
+        let buffer = sample.buffer().ok_or(Error::Encode)?;
+        let png = buffer.map_readable()?.to_vec();
+        Ok(png)
+    })();
+    pipeline.set_state(gst::State::Null)?;
+    png
+}
+
+/// Adds `tEXt` chunks to a PNG, right after its header.
+pub(crate) fn embed_text(png: &[u8], entries: &[(&str, String)]) -> Vec<u8> {
+    // 8 byte signature, then IHDR: 4 byte length, 4 byte type, 13 bytes of data, 4 byte CRC
+    const HEADER: usize = 8 + 4 + 4 + 13 + 4;
+    if png.len() < HEADER {
+        return png.to_vec();
+    }
+
+    let mut out = png[..HEADER].to_vec();
+    for (keyword, text) in entries {
+        // tEXt is Latin-1; anything else is left out rather than mangled
+        let text: Vec<u8> = text
+            .chars()
+            .filter_map(|c| u8::try_from(u32::from(c)).ok())
+            .collect();
+        let mut chunk = b"tEXt".to_vec();
+        chunk.extend_from_slice(keyword.as_bytes());
+        chunk.push(0);
+        chunk.extend_from_slice(&text);
+
+        out.extend_from_slice(&(chunk.len() as u32 - 4).to_be_bytes());
+        out.extend_from_slice(&chunk);
+        out.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    }
+    out.extend_from_slice(&png[HEADER..]);
+    out
+}
+
+/// The CRC-32 PNG chunks end with.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
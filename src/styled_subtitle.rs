@@ -0,0 +1,309 @@
+use crate::Error;
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use gstreamer_app::prelude::*;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Largest canvas the subtitles are rendered on; on bigger videos they're scaled up when drawn,
+/// as rendering and unblending a 4K canvas every frame would take longer than the frame itself.
+const MAX_CANVAS: (u32, u32) = (1920, 1080);
+
+/// The styled subtitles on screen, see [`VideoBuilder::styled_subtitles`](crate::VideoBuilder::styled_subtitles).
+#[derive(Debug, Clone)]
+pub(crate) struct SubtitleImage {
+    /// Straight-alpha RGBA pixels, cropped to where subtitles are drawn.
+    pub(crate) pixels: Arc<Vec<u8>>,
+    pub(crate) size: (u32, u32),
+    /// Where the pixels go, normalized to the frame.
+    pub(crate) bounds: iced::Rectangle,
+    /// Differs for every image rendered, so the renderer only uploads new ones.
+    pub(crate) generation: u64,
+}
+
+/// Renders ASS/SSA subtitle events from the text sink with `assrender`, in a pipeline of its own,
+/// into a [`SubtitleImage`] drawn over the frame by the renderer.
+///
+/// `assrender` blends subtitles onto frames, and doesn't keep the alpha of a transparent one,
+/// so each image is rendered over black and over white, and the alpha worked out from the difference.
+#[derive(Debug)]
+pub(crate) struct StyledSubtitles {
+    pipeline: gst::Pipeline,
+    canvas: gst_app::AppSrc,
+    text: gst_app::AppSrc,
+    /// Start and end of every event seen, to only render while some are on screen.
+    /// They're kept after ending, as `assrender` still has them to show after seeking back.
+    events: Mutex<Vec<(u64, u64)>>,
+    /// Physical size the frame is drawn at, packed as `width << 32 | height`; `0` until it's drawn.
+    drawn_size: AtomicU64,
+    /// Subtitle offset in nanoseconds, see [`Video::set_subtitle_offset`](crate::Video::set_subtitle_offset).
+    offset: AtomicI64,
+    pub(crate) image: Arc<Mutex<Option<SubtitleImage>>>,
+}
+
+impl StyledSubtitles {
+    pub(crate) fn new() -> Result<Self, Error> {
+        let pipeline = gst::Pipeline::new();
+        let canvas = gst_app::AppSrc::builder().format(gst::Format::Time).build();
+        let text = gst_app::AppSrc::builder().format(gst::Format::Time).build();
+        let render = gst::ElementFactory::make("assrender").build()?;
+        // frames only come in while events are on screen, they mustn't wait for the next one
+        if render.has_property("wait-text", None) {
+            render.set_property("wait-text", false);
+        }
+
+        let image: Arc<Mutex<Option<SubtitleImage>>> = Arc::new(Mutex::new(None));
+        let image_ref = Arc::clone(&image);
+        // the same frame rendered over black, waiting for its render over white
+        let mut over_black: Option<gst::Buffer> = None;
+        let sink = gst_app::AppSink::builder()
+            .caps(
+                &gst::Caps::builder("video/x-raw")
+                    .field("format", "RGBx")
+                    .build(),
+            )
+            .sync(false)
+            .callbacks(
+                gst_app::AppSinkCallbacks::builder()
+                    .new_sample(move |sink| {
+                        static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+                        let sample = sink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                        let buffer = sample.buffer_owned().ok_or(gst::FlowError::Error)?;
+                        let black = match over_black.take() {
+                            Some(black) if black.pts() == buffer.pts() => black,
+                            _ => {
+                                over_black = Some(buffer);
+                                return Ok(gst::FlowSuccess::Ok);
+                            }
+                        };
+                        let s = sample
+                            .caps()
+                            .and_then(|caps| caps.structure(0))
+                            .ok_or(gst::FlowError::Error)?;
+                        let width = s.get::<i32>("width").map_err(|_| gst::FlowError::Error)?;
+                        let height = s.get::<i32>("height").map_err(|_| gst::FlowError::Error)?;
+
+                        let black = black.map_readable().map_err(|_| gst::FlowError::Error)?;
+                        let white = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+                        let image = unblend(&black, &white, (width as u32, height as u32)).map(
+                            |(pixels, [x, y, crop_width, crop_height])| SubtitleImage {
+                                pixels: Arc::new(pixels),
+                                size: (crop_width, crop_height),
+                                bounds: iced::Rectangle {
+                                    x: x as f32 / width as f32,
+                                    y: y as f32 / height as f32,
+                                    width: crop_width as f32 / width as f32,
+                                    height: crop_height as f32 / height as f32,
+                                },
+                                generation: NEXT_GENERATION.fetch_add(1, Ordering::SeqCst),
+                            },
+                        );
+                        let mut current = image_ref.lock().map_err(|_| gst::FlowError::Error)?;
+                        // static subtitles are rendered again every frame, but only uploaded when they change
+                        let unchanged = match (current.as_ref(), image.as_ref()) {
+                            (Some(current), Some(image)) => {
+                                current.bounds == image.bounds && current.pixels == image.pixels
+                            }
+                            (None, None) => true,
+                            _ => false,
+                        };
+                        if !unchanged {
+                            *current = image;
+                        }
+                        Ok(gst::FlowSuccess::Ok)
+                    })
+                    .build(),
+            )
+            .build();
+
+        pipeline.add_many([
+            canvas.upcast_ref(),
+            text.upcast_ref(),
+            &render,
+            sink.upcast_ref(),
+        ])?;
+        canvas.link_pads(Some("src"), &render, Some("video_sink"))?;
+        text.link_pads(Some("src"), &render, Some("text_sink"))?;
+        render.link(&sink)?;
+        pipeline.set_state(gst::State::Playing)?;
+
+        Ok(StyledSubtitles {
+            pipeline,
+            canvas,
+            text,
+            events: Mutex::new(Vec::new()),
+            drawn_size: AtomicU64::new(0),
+            offset: AtomicI64::new(0),
+            image,
+        })
+    }
+
+    /// Whether `sample` is from a subtitle stream rendered here rather than sent as text.
+    pub(crate) fn accepts(sample: &gst::Sample) -> bool {
+        sample
+            .caps()
+            .and_then(|caps| caps.structure(0))
+            .is_some_and(|s| matches!(s.name().as_str(), "application/x-ass" | "application/x-ssa"))
+    }
+
+    /// Hands a subtitle event from the text sink to `assrender`.
+    pub(crate) fn push_text(&self, sample: &gst::Sample) {
+        let (Some(caps), Some(buffer)) = (sample.caps(), sample.buffer()) else {
+            return;
+        };
+        // the header with the styles comes along in the caps, and changes with the stream
+        if self.text.caps().as_ref().map(|current| current.as_ref()) != Some(caps) {
+            self.text.set_caps(Some(&caps.to_owned()));
+        }
+        let offset = self.offset.load(Ordering::SeqCst);
+        let start = buffer
+            .pts()
+            .unwrap_or_default()
+            .nseconds()
+            .saturating_add_signed(offset);
+        let end = start.saturating_add(buffer.duration().unwrap_or_default().nseconds());
+        if let Ok(mut events) = self.events.lock() {
+            if !events.contains(&(start, end)) {
+                events.push((start, end));
+            }
+        }
+
+        let mut buffer = buffer.copy();
+        buffer
+            .make_mut()
+            .set_pts(gst::ClockTime::from_nseconds(start));
+        if let Err(err) = self.text.push_buffer(buffer) {
+            log::warn!("cannot render subtitles: {err}");
+        }
+    }
+
+    /// Renders the subtitles at `pts` for a frame `frame_size` big, if there are any on screen,
+    /// or clears the ones shown.
+    pub(crate) fn render(&self, pts: gst::ClockTime, frame_size: (u32, u32)) {
+        let pts = pts.nseconds();
+        let showing = self
+            .events
+            .lock()
+            .is_ok_and(|events| events.iter().any(|&(start, end)| start <= pts && pts < end));
+        let shown = self.image.lock().is_ok_and(|image| image.is_some());
+        // rendering nothing clears them, after any renders still on their way
+        if !showing && !shown {
+            return;
+        }
+        // still busy with an earlier frame, which would only pile up
+        if self.canvas.current_level_bytes() > 0 {
+            return;
+        }
+
+        let drawn_size = self.drawn_size.load(Ordering::SeqCst);
+        let (width, height) = match ((drawn_size >> 32) as u32, drawn_size as u32) {
+            (0, _) | (_, 0) => frame_size,
+            size => size,
+        };
+        let scale = (MAX_CANVAS.0 as f32 / width as f32)
+            .min(MAX_CANVAS.1 as f32 / height as f32)
+            .min(1.0);
+        // even sizes, as some formats `assrender` may go through are subsampled
+        let width = ((width as f32 * scale) as u32 & !1).max(2);
+        let height = ((height as f32 * scale) as u32 & !1).max(2);
+
+        let caps = gst::Caps::builder("video/x-raw")
+            .field("format", "RGBx")
+            .field("width", width as i32)
+            .field("height", height as i32)
+            .field("framerate", gst::Fraction::new(0, 1))
+            .build();
+        if self.canvas.caps().as_ref() != Some(&caps) {
+            self.canvas.set_caps(Some(&caps));
+        }
+        let len = width as usize * height as usize * 4;
+        for fill in [0x00, 0xff] {
+            let mut buffer = gst::Buffer::from_mut_slice(vec![fill; len]);
+            buffer
+                .make_mut()
+                .set_pts(gst::ClockTime::from_nseconds(pts));
+            if let Err(err) = self.canvas.push_buffer(buffer) {
+                log::warn!("cannot render subtitles: {err}");
+                return;
+            }
+        }
+    }
+
+    /// Sets the physical size the frame is drawn at, which the subtitles are rendered at to stay sharp.
+    pub(crate) fn set_drawn_size(&self, (width, height): (u32, u32)) {
+        self.drawn_size
+            .store(u64::from(width) << 32 | u64::from(height), Ordering::SeqCst);
+    }
+
+    /// Delays (or advances) the subtitles by `offset` nanoseconds.
+    pub(crate) fn set_offset(&self, offset: i64) {
+        self.offset.store(offset, Ordering::SeqCst);
+    }
+}
+
+impl Drop for StyledSubtitles {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(gst::State::Null);
+    }
+}
+
+/// Recovers the straight-alpha RGBA pixels of subtitles from two `RGBx` frames `size` big, rendered over black
+/// and over white, cropped to where subtitles are drawn; along with the crop as `[x, y, width, height]`.
+pub(crate) fn unblend(
+    black: &[u8],
+    white: &[u8],
+    (width, height): (u32, u32),
+) -> Option<(Vec<u8>, [u32; 4])> {
+    let (width, height) = (width as usize, height as usize);
+    if black.len() < width * height * 4 || white.len() < width * height * 4 {
+        return None;
+    }
+    // over black, a channel is `alpha * color`; over white, `alpha * color + (1 - alpha) * 255`
+    let alpha = |i: usize| {
+        let uncovered: u32 = (0..3)
+            .map(|c| u32::from(white[i + c].saturating_sub(black[i + c])))
+            .sum();
+        255 - (uncovered / 3) as u8
+    };
+
+    let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            if alpha((y * width + x) * 4) > 0 {
+                left = left.min(x);
+                right = right.max(x + 1);
+                top = top.min(y);
+                bottom = bottom.max(y + 1);
+            }
+        }
+    }
+    if left >= right || top >= bottom {
+        return None;
+    }
+
+    let mut pixels = Vec::with_capacity((right - left) * (bottom - top) * 4);
+    for y in top..bottom {
+        for x in left..right {
+            let i = (y * width + x) * 4;
+            let a = alpha(i);
+            for c in 0..3 {
+                let color = match a {
+                    0 => 0,
+                    a => (u32::from(black[i + c]) * 255 / u32::from(a)).min(255) as u8,
+                };
+                pixels.push(color);
+            }
+            pixels.push(a);
+        }
+    }
+    Some((
+        pixels,
+        [
+            left as u32,
+            top as u32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ],
+    ))
+}
@@ -0,0 +1,100 @@
+use crate::Error;
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use gstreamer_app::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Subtitles decoded from teletext, handed out like those of the text sink.
+#[derive(Debug, Clone)]
+pub(crate) struct Teletext {
+    pub(crate) text: Arc<Mutex<Option<String>>>,
+    pub(crate) updated: Arc<AtomicBool>,
+    /// Page to decode, see [`Video::set_teletext_page`](crate::Video::set_teletext_page).
+    pub(crate) page: Arc<AtomicI32>,
+}
+
+/// Decodes the teletext stream `decodebin` (a `uridecodebin` in `bin`) exposes into `teletext`.
+///
+/// `teletextdec` isn't ranked for autoplugging, so the stream comes out undecoded and playbin leaves it
+/// unlinked; it gets a decoder of its own in `bin` instead of changing the rank for the whole process.
+pub(crate) fn decode_teletext(bin: &gst::Bin, decodebin: &gst::Element, teletext: Teletext) {
+    // the decodebin lives in the bin, so holding on to the bin would keep both alive forever
+    let bin = bin.downgrade();
+    // the decoder of this decodebin, linked to its teletext stream of the media playing
+    let decoder: Mutex<Option<gst::Element>> = Mutex::new(None);
+    decodebin.connect_pad_added(move |_, pad| {
+        let Some(bin) = bin.upgrade() else {
+            return;
+        };
+        let caps = pad.current_caps().unwrap_or_else(|| pad.query_caps(None));
+        let is_teletext = caps
+            .structure(0)
+            .is_some_and(|s| s.name() == "application/x-teletext");
+        if !is_teletext || pad.is_linked() {
+            return;
+        }
+        let Ok(mut decoder) = decoder.lock() else {
+            return;
+        };
+        // the next media of a playlist comes from the same decodebin, so its stream reuses the decoder
+        let result = match *decoder {
+            Some(ref decoder) => link(pad, decoder),
+            None => link_decoder(&bin, teletext.clone()).and_then(|new| {
+                link(pad, &new)?;
+                *decoder = Some(new);
+                Ok(())
+            }),
+        };
+        if let Err(err) = result {
+            log::warn!("cannot decode teletext: {err}");
+        }
+    });
+}
+
+/// Links `pad` to `decoder`, unless it already decodes another stream (e.g., a second teletext service).
+fn link(pad: &gst::Pad, decoder: &gst::Element) -> Result<(), Error> {
+    let sink_pad = decoder.static_pad("sink").ok_or(Error::Caps)?;
+    if sink_pad.is_linked() {
+        return Ok(());
+    }
+    pad.link(&sink_pad)
+        .map_err(|_| Error::Element("teletextdec".into()))?;
+    Ok(())
+}
+
+fn link_decoder(bin: &gst::Bin, teletext: Teletext) -> Result<gst::Element, Error> {
+    let decoder = gst::ElementFactory::make("teletextdec")
+        .property("subtitles-mode", true)
+        .property("page", teletext.page.load(Ordering::SeqCst))
+        .build()?;
+    let sink = gst_app::AppSink::builder()
+        .caps(&gst::Caps::builder("text/x-raw").build())
+        .sync(true)
+        // pages may never come, which mustn't hold up prerolling the rest of the pipeline
+        .async_(false)
+        .callbacks(
+            gst_app::AppSinkCallbacks::builder()
+                .new_sample(move |sink| {
+                    let sample = sink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                    let buffer = sample.buffer().ok_or(gst::FlowError::Error)?;
+                    let map = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+                    let text = String::from_utf8_lossy(&map).trim().to_owned();
+                    if let Ok(mut current) = teletext.text.lock() {
+                        *current = (!text.is_empty()).then_some(text);
+                    }
+                    teletext.updated.store(true, Ordering::SeqCst);
+                    Ok(gst::FlowSuccess::Ok)
+                })
+                .build(),
+        )
+        .build();
+
+    let elements = [&decoder, sink.upcast_ref()];
+    bin.add_many(elements)?;
+    gst::Element::link_many(elements)?;
+    for element in elements {
+        element.sync_state_with_parent()?;
+    }
+    Ok(decoder)
+}
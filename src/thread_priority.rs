@@ -0,0 +1,128 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+
+/// Scheduling priority of the threads decoding frames and handing them to the UI,
+/// see [`VideoBuilder::thread_priority`](crate::VideoBuilder::thread_priority).
+///
+/// Raising it keeps frames flowing (e.g., from a camera) while the UI thread is busy.
+/// Priorities above normal usually need privileges (e.g., `CAP_SYS_NICE` or an rtkit grant on Linux);
+/// if they can't be applied, a warning is logged and the threads run at normal priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ThreadPriority {
+    /// Leave the threads as created.
+    #[default]
+    Normal,
+    /// Favor the threads over normal ones (a niceness of -10 on Unix).
+    High,
+    /// Real-time, first-in first-out scheduling at the given priority (1 to 99 on Linux).
+    Realtime(u8),
+}
+
+/// Priority and CPU pinning applied to a video's threads.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Scheduling {
+    pub(crate) priority: ThreadPriority,
+    pub(crate) cpus: Vec<usize>,
+}
+
+impl Scheduling {
+    fn is_default(&self) -> bool {
+        self.priority == ThreadPriority::Normal && self.cpus.is_empty()
+    }
+
+    /// Applies the scheduling to GStreamer's streaming threads as they start.
+    pub(crate) fn watch_streaming_threads(&self, pipeline: &gst::Pipeline) {
+        if self.is_default() {
+            return;
+        }
+        let Some(bus) = pipeline.bus() else {
+            return;
+        };
+        let scheduling = self.clone();
+        // stream status messages are posted from the streaming thread itself when it starts
+        bus.set_sync_handler(move |_, msg| {
+            if let gst::MessageView::StreamStatus(status) = msg.view() {
+                if status.get().0 == gst::StreamStatusType::Enter {
+                    scheduling.apply_to_current_thread();
+                }
+            }
+            gst::BusSyncReply::Pass
+        });
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn apply_to_current_thread(&self) {
+        match self.priority {
+            ThreadPriority::Normal => {}
+            ThreadPriority::High => {
+                #[cfg(target_os = "linux")]
+                // SAFETY: plain syscalls on the calling thread
+                let result =
+                    unsafe { libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as _, -10) };
+                #[cfg(not(target_os = "linux"))]
+                // SAFETY: plain syscall on the calling process
+                let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, -10) };
+                if result != 0 {
+                    log::warn!(
+                        "cannot raise thread priority: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+            }
+            ThreadPriority::Realtime(priority) => {
+                let param = libc::sched_param {
+                    sched_priority: priority.into(),
+                };
+                // SAFETY: `param` outlives the call, which only affects the calling thread
+                let result = unsafe {
+                    libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param)
+                };
+                if result != 0 {
+                    log::warn!(
+                        "cannot set real-time thread priority: {}",
+                        std::io::Error::from_raw_os_error(result)
+                    );
+                }
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if !self.cpus.is_empty() {
+            // `CPU_SET` panics on CPUs past the end of the bitmask
+            let (cpus, unsupported): (Vec<usize>, Vec<usize>) = self
+                .cpus
+                .iter()
+                .partition(|&&cpu| cpu < libc::CPU_SETSIZE as usize);
+            if !unsupported.is_empty() {
+                log::warn!(
+                    "cannot pin thread to CPUs {unsupported:?}, past the last supported CPU"
+                );
+            }
+            if cpus.is_empty() {
+                return;
+            }
+            // SAFETY: `set` is a plain bitmask, initialized before use, and `cpus` are within it
+            let result = unsafe {
+                let mut set: libc::cpu_set_t = std::mem::zeroed();
+                for &cpu in &cpus {
+                    libc::CPU_SET(cpu, &mut set);
+                }
+                libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+            };
+            if result != 0 {
+                log::warn!(
+                    "cannot pin thread to CPUs {:?}: {}",
+                    cpus,
+                    std::io::Error::last_os_error()
+                );
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn apply_to_current_thread(&self) {
+        if !self.is_default() {
+            log::warn!("thread priorities aren't supported on this platform");
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::Error;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Audio levels of one audio track, see [`Video::track_levels`](crate::Video::track_levels).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TrackLevel {
+    /// Index of the track, as in [`AudioTrack::index`](crate::AudioTrack::index).
+    pub index: usize,
+    /// Peak level of each channel over the last 100ms, in dBFS.
+    pub peak: Vec<f64>,
+    /// RMS level of each channel over the last 100ms, in dBFS.
+    pub rms: Vec<f64>,
+}
+
+/// Decodes every audio track of the media next to playback, only to measure their levels.
+#[derive(Debug)]
+pub(crate) struct TrackMeters {
+    pub(crate) pipeline: gst::Pipeline,
+    levels: Arc<Mutex<BTreeMap<usize, TrackLevel>>>,
+}
+
+impl TrackMeters {
+    pub(crate) fn new(uri: &url::Url, clock: Option<gst::Clock>) -> Result<Self, Error> {
+        let pipeline = gst::Pipeline::new();
+        // everything but audio is left encoded, nothing looks at it
+        let decodebin = gst::ElementFactory::make("uridecodebin")
+            .property("uri", uri.as_str())
+            .property("caps", gst::Caps::builder("audio/x-raw").build())
+            .build()?;
+        pipeline.add(&decodebin)?;
+
+        let tracks = AtomicUsize::new(0);
+        let bin = pipeline.downgrade();
+        decodebin.connect_pad_added(move |_, pad| {
+            let Some(bin) = bin.upgrade() else {
+                return;
+            };
+            let is_audio = pad
+                .current_caps()
+                .and_then(|caps| caps.structure(0).map(|s| s.name() == "audio/x-raw"))
+                .unwrap_or(false);
+            // pads show up in stream order, like playbin numbers its tracks
+            let index = is_audio.then(|| tracks.fetch_add(1, Ordering::SeqCst));
+            if let Err(err) = link_meter(bin.upcast_ref(), pad, index) {
+                log::warn!("cannot meter audio track: {err}");
+            }
+        });
+
+        let levels = Arc::new(Mutex::new(BTreeMap::new()));
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        let sink = Arc::clone(&levels);
+        bus.set_sync_handler(move |_, msg| {
+            match msg.view() {
+                gst::MessageView::Element(element) => {
+                    if let Some(level) = element
+                        .structure()
+                        .filter(|s| s.name() == "level")
+                        .zip(msg.src().and_then(|src| track_index(&src.name())))
+                        .map(|(s, index)| TrackLevel {
+                            index,
+                            peak: decibels(s, "peak"),
+                            rms: decibels(s, "rms"),
+                        })
+                    {
+                        if let Ok(mut levels) = sink.lock() {
+                            levels.insert(level.index, level);
+                        }
+                    }
+                }
+                gst::MessageView::Error(err) => {
+                    log::warn!("audio track metering failed: {}", err.error());
+                }
+                _ => {}
+            }
+            // nothing pops this bus, so nothing may be left on it
+            gst::BusSyncReply::Drop
+        });
+
+        // run off the same clock as playback, like the secondary audio
+        if let Some(clock) = clock {
+            pipeline.use_clock(Some(&clock));
+        }
+        pipeline.set_state(gst::State::Paused)?;
+
+        Ok(TrackMeters { pipeline, levels })
+    }
+
+    /// Latest levels of every audio track, in track order.
+    pub(crate) fn levels(&self) -> Vec<TrackLevel> {
+        self.levels
+            .lock()
+            .map(|levels| levels.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Re-seeks the meters if they drifted too far from the main playback `position`.
+    pub(crate) fn sync(&self, position: Duration, speed: f64) {
+        const MAX_DRIFT: Duration = Duration::from_millis(250);
+
+        let Some(meter_position) = self.pipeline.query_position::<gst::ClockTime>() else {
+            return;
+        };
+        if position.abs_diff(Duration::from_nanos(meter_position.nseconds())) <= MAX_DRIFT {
+            return;
+        }
+        let position = gst::ClockTime::from_nseconds(position.as_nanos() as _);
+        let result = if speed > 0.0 {
+            self.pipeline.seek(
+                speed,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                position,
+                gst::SeekType::None,
+                gst::ClockTime::NONE,
+            )
+        } else {
+            self.pipeline.seek(
+                speed,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                gst::ClockTime::ZERO,
+                gst::SeekType::Set,
+                position,
+            )
+        };
+        if let Err(err) = result {
+            log::debug!("cannot sync audio track meters: {err}");
+        }
+        // stale levels would otherwise show until the meters catch up
+        if let Ok(mut levels) = self.levels.lock() {
+            levels.clear();
+        }
+    }
+}
+
+impl Drop for TrackMeters {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(gst::State::Null);
+    }
+}
+
+/// Meters `pad` as the audio track `index`, or throws its data away if it isn't audio.
+fn link_meter(bin: &gst::Bin, pad: &gst::Pad, index: Option<usize>) -> Result<(), Error> {
+    let sink = gst::ElementFactory::make("fakesink")
+        .property("sync", true)
+        .build()?;
+    let mut elements = Vec::new();
+    if let Some(index) = index {
+        elements.push(gst::ElementFactory::make("audioconvert").build()?);
+        elements.push(
+            gst::ElementFactory::make("level")
+                .name(format!("iced_meter_{index}"))
+                .property("interval", gst::ClockTime::from_mseconds(100).nseconds())
+                .property("post-messages", true)
+                .build()?,
+        );
+    }
+    elements.push(sink);
+
+    bin.add_many(&elements)?;
+    gst::Element::link_many(&elements)?;
+    for element in &elements {
+        element.sync_state_with_parent()?;
+    }
+    let sink_pad = elements[0].static_pad("sink").ok_or(Error::Caps)?;
+    pad.link(&sink_pad)
+        .map_err(|_| Error::Element("level".into()))?;
+    Ok(())
+}
+
+fn track_index(name: &str) -> Option<usize> {
+    name.strip_prefix("iced_meter_")?.parse().ok()
+}
+
+fn decibels(level: &gst::StructureRef, field: &str) -> Vec<f64> {
+    level
+        .get::<glib::ValueArray>(field)
+        .map(|values| values.iter().filter_map(|value| value.get().ok()).collect())
+        .unwrap_or_default()
+}
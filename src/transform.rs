@@ -0,0 +1,127 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// Rotation or mirroring of the frame, see [`VideoPlayer::transform`](crate::VideoPlayer::transform).
+///
+/// Also how frames have to be turned to show upright, as given by the stream's `image-orientation` tag
+/// (e.g., portrait phone recordings are stored sideways).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Transform {
+    /// Show the frame as-is.
+    #[default]
+    Identity,
+    /// Rotate 90° clockwise.
+    Rotate90,
+    /// Rotate 180°.
+    Rotate180,
+    /// Rotate 90° counterclockwise.
+    Rotate270,
+    /// Mirror left to right, e.g. for webcams.
+    Mirror,
+    /// Mirror along the top-left to bottom-right diagonal.
+    Transpose,
+    /// Flip upside down.
+    Flip,
+    /// Mirror along the top-right to bottom-left diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::Mirror,
+        Transform::Transpose,
+        Transform::Flip,
+        Transform::AntiTranspose,
+    ];
+
+    /// This transform followed by `next`.
+    pub fn then(self, next: Transform) -> Transform {
+        // a transform is known by where it takes the corners
+        let corners = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        Self::ALL
+            .into_iter()
+            .find(|transform| {
+                corners
+                    .iter()
+                    .all(|&corner| transform.source(corner) == self.source(next.source(corner)))
+            })
+            .unwrap_or_default()
+    }
+
+    fn from_tag(tag: &str) -> Self {
+        match tag {
+            "rotate-90" => Transform::Rotate90,
+            "rotate-180" => Transform::Rotate180,
+            "rotate-270" => Transform::Rotate270,
+            "flip-rotate-0" => Transform::Mirror,
+            "flip-rotate-90" => Transform::Transpose,
+            "flip-rotate-180" => Transform::Flip,
+            "flip-rotate-270" => Transform::AntiTranspose,
+            _ => Transform::Identity,
+        }
+    }
+
+    /// Get if width and height trade places.
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Transform::Rotate90
+                | Transform::Rotate270
+                | Transform::Transpose
+                | Transform::AntiTranspose
+        )
+    }
+
+    /// `(width, height)` of a frame `size` big, once transformed.
+    pub(crate) fn display_size<T>(self, (width, height): (T, T)) -> (T, T) {
+        if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where the point at `(x, y)` of the transformed frame is in the original frame, both normalized to 0..1.
+    ///
+    /// Has to match `transform_uv` in the shader.
+    pub(crate) fn source(self, (x, y): (f32, f32)) -> (f32, f32) {
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (y, 1.0 - x),
+            Transform::Rotate180 => (1.0 - x, 1.0 - y),
+            Transform::Rotate270 => (1.0 - y, x),
+            Transform::Mirror => (1.0 - x, y),
+            Transform::Transpose => (y, x),
+            Transform::Flip => (x, 1.0 - y),
+            Transform::AntiTranspose => (1.0 - y, 1.0 - x),
+        }
+    }
+}
+
+/// Watches the tags reaching `pad` and keeps the orientation they give in `orientation`.
+pub(crate) fn watch_orientation(pad: &gst::Pad, orientation: Arc<Mutex<Transform>>) {
+    let update = move |tags: &gst::TagListRef| {
+        if let Some(tag) = tags.get::<gst::tags::ImageOrientation>() {
+            if let Ok(mut orientation) = orientation.lock() {
+                *orientation = Transform::from_tag(tag.get());
+            }
+        }
+    };
+
+    // the demuxer may have sent them before the watch was set up
+    if let Some(tags) = pad.sticky_event::<gst::event::Tag>(0) {
+        update(tags.tag());
+    }
+
+    pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_pad, info| {
+        if let Some(gst::EventView::Tag(tags)) = info.event().map(|event| event.view()) {
+            update(tags.tag());
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
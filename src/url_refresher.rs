@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Provides fresh URLs for streams whose URL expires (e.g., CDN-tokenized streams),
+/// see [`Video::set_url_refresher`](crate::Video::set_url_refresher).
+///
+/// Called on a background thread, so it may block (e.g., on an HTTP request to the app's backend).
+/// Implemented for closures.
+pub trait UrlRefresher: Send + Sync {
+    /// Returns a fresh URL for the `expired` one, or `None` to give up.
+    fn refresh(&self, expired: &url::Url) -> Option<url::Url>;
+}
+
+impl<F> UrlRefresher for F
+where
+    F: Fn(&url::Url) -> Option<url::Url> + Send + Sync,
+{
+    fn refresh(&self, expired: &url::Url) -> Option<url::Url> {
+        self(expired)
+    }
+}
+
+/// Runs a [`UrlRefresher`] in the background when the URL expires.
+pub(crate) struct Refresher {
+    refresher: Arc<dyn UrlRefresher>,
+    ttl: Option<Duration>,
+    since: Instant,
+    pending: bool,
+    // `Some` once the refresher returned, with `None` if it gave up
+    result: Arc<Mutex<Option<Option<url::Url>>>>,
+}
+
+impl std::fmt::Debug for Refresher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Refresher")
+            .field("ttl", &self.ttl)
+            .field("since", &self.since)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Refresher {
+    pub(crate) fn new(refresher: Arc<dyn UrlRefresher>, ttl: Option<Duration>) -> Self {
+        Refresher {
+            refresher,
+            ttl,
+            since: Instant::now(),
+            pending: false,
+            result: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        self.ttl.is_some_and(|ttl| self.since.elapsed() >= ttl)
+    }
+
+    /// Starts refreshing `uri` in the background, unless already refreshing.
+    pub(crate) fn refresh(&mut self, uri: url::Url) {
+        if self.pending {
+            return;
+        }
+        self.pending = true;
+        let refresher = Arc::clone(&self.refresher);
+        let result = Arc::clone(&self.result);
+        std::thread::spawn(move || {
+            let fresh = refresher.refresh(&uri);
+            if let Ok(mut result) = result.lock() {
+                *result = Some(fresh);
+            }
+        });
+    }
+
+    /// Returns `Some` once a refresh finished, with the fresh URL if there is one.
+    pub(crate) fn poll(&mut self) -> Option<Option<url::Url>> {
+        let result = self.result.try_lock().ok()?.take()?;
+        self.pending = false;
+        self.since = Instant::now();
+        Some(result)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::quality_cap::Demuxers;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// A quality variant of an adaptive stream (HLS or DASH), as its manifest lists it,
+/// see [`Video::variants`](crate::Video::variants).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Variant {
+    /// Bitrate of the variant in bits per second.
+    pub bitrate: u32,
+    /// `(width, height)` of the variant's video, if the manifest tells.
+    pub resolution: Option<(u32, u32)>,
+}
+
+/// Reads the variants off the manifest flowing into the adaptive `demuxer`.
+pub(crate) fn watch_manifest(demuxer: &gst::Element, demuxers: Arc<Mutex<Demuxers>>) {
+    // manifests are small, anything bigger isn't one
+    const MAX_LEN: usize = 4 << 20;
+
+    let Some(pad) = demuxer.static_pad("sink") else {
+        return;
+    };
+    let manifest = Mutex::new(Vec::new());
+    pad.add_probe(
+        gst::PadProbeType::BUFFER | gst::PadProbeType::EVENT_DOWNSTREAM,
+        move |_pad, info| {
+            let Ok(mut manifest) = manifest.lock() else {
+                return gst::PadProbeReturn::Ok;
+            };
+            match info.data {
+                Some(gst::PadProbeData::Buffer(ref buffer)) => {
+                    if let Ok(map) = buffer.map_readable() {
+                        if manifest.len() + map.len() <= MAX_LEN {
+                            manifest.extend_from_slice(&map);
+                        }
+                    }
+                }
+                Some(gst::PadProbeData::Event(ref event))
+                    if event.type_() == gst::EventType::Eos =>
+                {
+                    let variants = parse_manifest(&String::from_utf8_lossy(&manifest));
+                    manifest.clear();
+                    // media playlists (e.g., reloaded live ones) list no variants
+                    if !variants.is_empty() {
+                        if let Ok(mut demuxers) = demuxers.lock() {
+                            demuxers.variants = variants;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            gst::PadProbeReturn::Ok
+        },
+    );
+}
+
+/// The video variants listed by an HLS master playlist or a DASH MPD, by bitrate.
+fn parse_manifest(manifest: &str) -> Vec<Variant> {
+    let mut variants = if manifest.trim_start().starts_with("#EXTM3U") {
+        parse_hls(manifest)
+    } else if manifest.contains("<MPD") {
+        parse_dash(manifest)
+    } else {
+        Vec::new()
+    };
+    variants.sort_by_key(|variant| (variant.bitrate, variant.resolution));
+    variants.dedup();
+    variants
+}
+
+fn parse_hls(playlist: &str) -> Vec<Variant> {
+    playlist
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#EXT-X-STREAM-INF:"))
+        .filter_map(|attributes| {
+            let mut bitrate = None;
+            let mut resolution = None;
+            for (name, value) in hls_attributes(attributes) {
+                match name {
+                    "BANDWIDTH" => bitrate = value.parse().ok(),
+                    "RESOLUTION" => {
+                        resolution = value
+                            .split_once('x')
+                            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                    }
+                    _ => {}
+                }
+            }
+            Some(Variant {
+                bitrate: bitrate?,
+                resolution,
+            })
+        })
+        .collect()
+}
+
+/// `NAME=value` pairs of an HLS attribute list, where quoted values may hold commas.
+fn hls_attributes(list: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut quoted = false;
+    list.split(move |c| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ',' && !quoted
+    })
+    .filter_map(|attribute| {
+        let (name, value) = attribute.split_once('=')?;
+        Some((name.trim(), value.trim().trim_matches('"')))
+    })
+}
+
+fn parse_dash(mpd: &str) -> Vec<Variant> {
+    mpd.split("<Representation")
+        .skip(1)
+        .filter_map(|representation| {
+            let tag = &representation[..representation.find('>')?];
+            let attribute = |name: &str| {
+                let pattern = format!("{name}=\"");
+                let (start, _) = tag
+                    .match_indices(&pattern)
+                    .find(|&(i, _)| tag[..i].ends_with(char::is_whitespace))?;
+                let start = start + pattern.len();
+                let end = start + tag[start..].find('"')?;
+                tag[start..end].parse::<u32>().ok()
+            };
+            // audio representations have no size
+            Some(Variant {
+                bitrate: attribute("bandwidth")?,
+                resolution: Some((attribute("width")?, attribute("height")?)),
+            })
+        })
+        .collect()
+}
@@ -1,13 +1,34 @@
-use crate::Error;
+use crate::analytics::{Analytics, AnalyticsSink};
+use crate::audio_devices::AudioDevices;
+use crate::bookmark::Bookmark;
+use crate::chapter::Chapter;
+use crate::closed_caption::Captions;
+use crate::content_gate::ContentGate;
+use crate::data_track::{DataInterpolation, DataSample};
+use crate::frame::{FrameBuffer, FrameData, PixelFormat};
+use crate::pipeline::{ColorBalance, Transfer};
+use crate::quality_cap::QualityCapper;
+use crate::registry::VideoId;
+use crate::sink_caps::SinkCaps;
+use crate::snapshot;
+use crate::styled_subtitle::StyledSubtitles;
+use crate::teletext::Teletext;
+use crate::thread_priority::Scheduling;
+use crate::track_meter::{TrackLevel, TrackMeters};
+use crate::transform::Transform;
+use crate::url_refresher::Refresher;
+use crate::visualization::Visualizer;
+use crate::{EditList, Error, FocusPolicy, FrameMetadata, Gap, Lut, Variant, VideoBuilder};
 use gstreamer as gst;
 use gstreamer_app as gst_app;
 use gstreamer_app::prelude::*;
+use gstreamer_base as gst_base;
 use iced::widget::image as img;
 use std::num::NonZeroU8;
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Position in the media.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -41,11 +62,350 @@ impl From<u64> for Position {
     }
 }
 
+/// How precisely [`Video::seek`] lands on the requested position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SeekMode {
+    /// Land exactly on the position; slower, as decoding starts from the keyframe before it.
+    /// Needed when pausing on an exact timestamp.
+    Accurate,
+    /// Land on the keyframe at or before the position; fast, for scrubbing.
+    #[default]
+    Keyframe,
+    /// Land on the keyframe closest to the position, before or after it.
+    Snap,
+    /// A plain flushing seek, leaving how precisely to land up to the demuxer.
+    Plain,
+}
+
+impl SeekMode {
+    fn flags(self) -> gst::SeekFlags {
+        match self {
+            SeekMode::Accurate => gst::SeekFlags::ACCURATE,
+            SeekMode::Keyframe => gst::SeekFlags::KEY_UNIT | gst::SeekFlags::SNAP_BEFORE,
+            SeekMode::Snap => gst::SeekFlags::KEY_UNIT | gst::SeekFlags::SNAP_NEAREST,
+            SeekMode::Plain => gst::SeekFlags::empty(),
+        }
+    }
+}
+
+/// `true` is [`SeekMode::Accurate`], `false` is [`SeekMode::Plain`], as `accurate` always meant to [`Video::seek`].
+impl From<bool> for SeekMode {
+    fn from(accurate: bool) -> Self {
+        if accurate {
+            SeekMode::Accurate
+        } else {
+            SeekMode::Plain
+        }
+    }
+}
+
+/// Seeks closer together than this count as scrubbing, see [`Video::set_mute_on_scrub`].
+const SCRUB_INTERVAL: Duration = Duration::from_millis(250);
+/// How long audio takes to fade back in after scrubbing.
+const SCRUB_FADE: Duration = Duration::from_millis(300);
+
+/// Audio lowered by [`Video::duck`], ramping from one gain to another.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Ducking {
+    /// The volume the user set, which the gain applies to.
+    pub(crate) volume: f64,
+    from: f64,
+    to: f64,
+    start: Instant,
+    fade: Duration,
+    /// Whether the end of the ramp was applied already.
+    settled: bool,
+}
+
+impl Ducking {
+    fn gain(&self) -> f64 {
+        let t = if self.fade.is_zero() {
+            1.0
+        } else {
+            (self.start.elapsed().as_secs_f64() / self.fade.as_secs_f64()).min(1.0)
+        };
+        self.from + (self.to - self.from) * t
+    }
+
+    fn finished(&self) -> bool {
+        self.start.elapsed() >= self.fade
+    }
+}
+
+/// A gradual change of the playback speed, see [`Video::ramp_speed`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SpeedRamp {
+    from: f64,
+    to: f64,
+    start: Instant,
+    duration: Duration,
+    /// When the last rate change was made.
+    stepped: Instant,
+}
+
+impl SpeedRamp {
+    fn speed(&self) -> f64 {
+        let t = (self.start.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0);
+        self.from + (self.to - self.from) * t
+    }
+
+    fn finished(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+}
+
+/// `GstPlayFlags` nicks and their bit values, in declaration order.
+const PLAY_FLAGS: [(&str, u32); 13] = [
+    ("video", 1 << 0),
+    ("audio", 1 << 1),
+    ("text", 1 << 2),
+    ("vis", 1 << 3),
+    ("soft-volume", 1 << 4),
+    ("native-audio", 1 << 5),
+    ("native-video", 1 << 6),
+    ("download", 1 << 7),
+    ("buffering", 1 << 8),
+    ("deinterlace", 1 << 9),
+    ("soft-colorbalance", 1 << 10),
+    ("force-filters", 1 << 11),
+    ("force-sw-decoders", 1 << 12),
+];
+
+pub(crate) fn play_flag(nick: &str) -> u32 {
+    PLAY_FLAGS
+        .iter()
+        .find_map(|&(n, bit)| (n == nick).then_some(bit))
+        .unwrap_or(0)
+}
+
+/// Raw `GstPlayFlags` bits of the pipeline, or zero if it isn't a `playbin`.
+pub(crate) fn play_flags(pipeline: &gst::Pipeline) -> u32 {
+    if !pipeline.has_property("flags", None) {
+        return 0;
+    }
+    pipeline
+        .property_value("flags")
+        .transform::<u32>()
+        .ok()
+        .and_then(|value| value.get::<u32>().ok())
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_play_flags(pipeline: &gst::Pipeline, bits: u32) {
+    if !pipeline.has_property("flags", None) {
+        return;
+    }
+    let nicks = PLAY_FLAGS
+        .iter()
+        .filter(|&&(_, bit)| bits & bit != 0)
+        .map(|&(nick, _)| nick)
+        .collect::<Vec<_>>()
+        .join("+");
+    pipeline.set_property_from_str("flags", if nicks.is_empty() { "0" } else { &nicks });
+}
+
+/// Playback features of a `playbin` pipeline, mapping to its `flags` property.
+///
+/// Most features can be toggled at runtime; GStreamer ignores changes it can't apply mid-stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Features {
+    /// Render the video stream.
+    pub video: bool,
+    /// Render the audio stream.
+    pub audio: bool,
+    /// Render subtitles.
+    pub text: bool,
+    /// Render a visualization when there is no video stream.
+    pub vis: bool,
+    /// Download progressive media to disk while playing.
+    pub download: bool,
+    /// Buffer network streams.
+    pub buffering: bool,
+    /// Deinterlace interlaced video.
+    pub deinterlace: bool,
+    /// Use software volume control instead of the audio sink's.
+    pub soft_volume: bool,
+}
+
+impl Default for Features {
+    /// The features `playbin` enables by default.
+    fn default() -> Self {
+        Features {
+            video: true,
+            audio: true,
+            text: true,
+            vis: false,
+            download: false,
+            buffering: false,
+            deinterlace: true,
+            soft_volume: true,
+        }
+    }
+}
+
+impl Features {
+    fn fields(&self) -> [(&'static str, bool); 8] {
+        [
+            ("video", self.video),
+            ("audio", self.audio),
+            ("text", self.text),
+            ("vis", self.vis),
+            ("download", self.download),
+            ("buffering", self.buffering),
+            ("deinterlace", self.deinterlace),
+            ("soft-volume", self.soft_volume),
+        ]
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        let set = |nick| bits & play_flag(nick) != 0;
+        Features {
+            video: set("video"),
+            audio: set("audio"),
+            text: set("text"),
+            vis: set("vis"),
+            download: set("download"),
+            buffering: set("buffering"),
+            deinterlace: set("deinterlace"),
+            soft_volume: set("soft-volume"),
+        }
+    }
+
+    /// Merges these features into `bits`, leaving flags not covered by [`Features`] untouched.
+    pub(crate) fn apply_bits(&self, bits: u32) -> u32 {
+        self.fields().iter().fold(bits, |bits, &(nick, on)| {
+            if on {
+                bits | play_flag(nick)
+            } else {
+                bits & !play_flag(nick)
+            }
+        })
+    }
+}
+
+/// How a segment set with [`Video::set_skip_segments`] is skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SkipMode {
+    /// Seek past the segment as soon as playback enters it.
+    Auto,
+    /// Only notify the app (see [`VideoPlayer::on_skip_segment`](crate::VideoPlayer::on_skip_segment)),
+    /// which can then offer to skip with [`Video::skip_segment`].
+    Prompt,
+}
+
+/// How a secondary audio track set with [`Video::set_secondary_audio`] is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioMix {
+    /// Mix the secondary audio over the main audio (e.g., a commentary track).
+    Over,
+    /// Play the secondary audio in place of the main audio (e.g., a dub).
+    Replace,
+}
+
+/// Vocal removal settings of the `audiokaraoke` filter, see [`Video::set_karaoke`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Karaoke {
+    /// Strength of the effect (`1.0` = 100%), up to `1.0`.
+    pub level: f32,
+    /// Strength of the effect on mono (centered) audio, where vocals usually are, up to `1.0`.
+    pub mono_level: f32,
+    /// Center of the frequency band to remove, in Hz, up to `441.0`.
+    pub filter_band: f32,
+    /// Width of the frequency band to remove, in Hz, up to `100.0`.
+    pub filter_width: f32,
+}
+
+impl Default for Karaoke {
+    fn default() -> Self {
+        Karaoke {
+            level: 1.0,
+            mono_level: 1.0,
+            filter_band: 220.0,
+            filter_width: 100.0,
+        }
+    }
+}
+
+/// Audible playback position reported by the audio sink, see [`Video::audio_clock_position`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioClockPosition {
+    /// Position of the audio currently being heard.
+    pub position: Duration,
+    /// How far the audio is ahead of the latest video frame (negative if behind), in seconds.
+    pub drift: f64,
+}
+
+/// An audio track of the media, see [`Video::audio_tracks`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AudioTrack {
+    /// Index of the track, as passed to [`Video::set_audio_track`].
+    pub index: i32,
+    /// Language code of the track, if tagged.
+    pub language: Option<String>,
+    /// Title of the track, if tagged.
+    pub title: Option<String>,
+    /// Codec of the track, if known.
+    pub codec: Option<String>,
+    /// Whether the track is an audio description (narration for visually impaired viewers).
+    pub audio_description: bool,
+}
+
+/// A video track of the media (e.g., another camera angle), see [`Video::video_tracks`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VideoTrack {
+    /// Index of the track, as passed to [`Video::set_video_track`].
+    pub index: i32,
+    /// Language code of the track, if tagged.
+    pub language: Option<String>,
+    /// Title of the track, if tagged.
+    pub title: Option<String>,
+    /// Codec of the track, if known.
+    pub codec: Option<String>,
+}
+
+/// Guesses from its tags whether an audio track is an audio description, as containers don't agree on a flag.
+fn is_audio_description(tags: &gst::TagListRef) -> bool {
+    let matches = |text: &str| {
+        let text = text.to_lowercase();
+        text.contains("description")
+            || text.contains("descriptive")
+            || text.contains("visually impaired")
+            || text
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word == "ad")
+    };
+    tags.get::<gst::tags::Title>()
+        .is_some_and(|title| matches(title.get()))
+        || tags
+            .get::<gst::tags::Comment>()
+            .is_some_and(|comment| matches(comment.get()))
+}
+
+/// Whether `msg` was posted by a demuxer, e.g. `hlsdemux` or `qtdemux`.
+fn from_demuxer(msg: &gst::Message) -> bool {
+    msg.src()
+        .and_then(|src| src.downcast_ref::<gst::Element>())
+        .and_then(|element| element.factory())
+        .and_then(|factory| {
+            factory
+                .metadata("klass")
+                .map(|klass| klass.contains("Demux"))
+        })
+        .unwrap_or(false)
+}
+
+/// Media to continue with, and the durations to trim off its beginning and end.
+type NextMedia = (url::Url, (Duration, Duration));
+
 #[derive(Debug)]
 pub(crate) struct Internal {
     pub(crate) id: u64,
+    pub(crate) video_id: VideoId,
 
     pub(crate) bus: gst::Bus,
+    /// Audio device changes, once a player has an `on_audio_device_changed` handler.
+    pub(crate) audio_devices: Option<AudioDevices>,
     pub(crate) source: gst::Pipeline,
     pub(crate) alive: Arc<AtomicBool>,
     pub(crate) worker: Option<std::thread::JoinHandle<()>>,
@@ -53,50 +413,187 @@ pub(crate) struct Internal {
     pub(crate) width: i32,
     pub(crate) height: i32,
     pub(crate) framerate: f64,
+    pub(crate) format: PixelFormat,
+    pub(crate) transfer: Transfer,
+    pub(crate) color_balance: ColorBalance,
+    pub(crate) lut: Option<Lut>,
+    pub(crate) orientation: Arc<Mutex<Transform>>,
+    /// Width over height of a pixel, per the stream's caps.
+    pub(crate) pixel_aspect: f64,
+    pub(crate) pixel_aspect_override: Option<f64>,
     pub(crate) duration: Duration,
     pub(crate) speed: f64,
     pub(crate) sync_av: bool,
 
-    pub(crate) frame: Arc<Mutex<Vec<u8>>>,
+    pub(crate) frame: Arc<FrameBuffer>,
+    pub(crate) video_sink: gst_app::AppSink,
+    pub(crate) sink_caps: Arc<Mutex<SinkCaps>>,
     pub(crate) upload_frame: Arc<AtomicBool>,
     pub(crate) last_frame_time: Arc<Mutex<Instant>>,
+    pub(crate) frame_pts: Arc<AtomicU64>,
+    pub(crate) frames_dropped: Arc<AtomicU32>,
+    pub(crate) static_content: Arc<AtomicBool>,
+    pub(crate) frame_hash: Arc<Mutex<Option<u64>>>,
+    pub(crate) hash_updated: Arc<AtomicBool>,
+    /// Whether frames are hashed, see [`Video::set_frame_hashing`].
+    pub(crate) frame_hashing: Arc<AtomicBool>,
+    /// Set while every frame has to be uploaded, even if it's the same as the last one (e.g., for thumbnails).
+    pub(crate) force_upload: Arc<AtomicBool>,
+    pub(crate) frame_metadata: Arc<Mutex<Option<FrameMetadata>>>,
+    pub(crate) metadata_updated: Arc<AtomicBool>,
+    pub(crate) renderer: Arc<AtomicU64>,
+    pub(crate) renderer_reset: Arc<AtomicBool>,
+    /// Scale factor of the window the video was last drawn in, as `f32` bits.
+    pub(crate) scale_factor: Arc<AtomicU32>,
+    pub(crate) gaps: Arc<Mutex<Vec<Gap>>>,
+    pub(crate) chapters: Arc<Mutex<Vec<Chapter>>>,
+    pub(crate) bookmarks: Vec<Bookmark>,
+    pub(crate) chapter: Option<usize>,
+    pub(crate) program: Arc<AtomicI32>,
+    pub(crate) teletext_page: Arc<AtomicI32>,
+    pub(crate) upload_time: Instant,
     pub(crate) looping: bool,
     pub(crate) is_eos: bool,
     pub(crate) restart_stream: bool,
     pub(crate) sync_av_avg: u64,
     pub(crate) sync_av_counter: u64,
+    pub(crate) presenting_frame_time: Option<Instant>,
+    pub(crate) measure_latency: Arc<AtomicBool>,
+    /// Wall-clock time the latest frame was captured, while measuring latency.
+    pub(crate) capture_time: Arc<Mutex<Option<SystemTime>>>,
+    /// [`Internal::capture_time`] of the frame being presented.
+    pub(crate) presenting_capture: Option<SystemTime>,
+    /// Moving average of the capture-to-display latency, see [`Video::latency`].
+    pub(crate) latency: Option<Duration>,
 
     pub(crate) subtitle_text: Arc<Mutex<Option<String>>>,
     pub(crate) upload_text: Arc<AtomicBool>,
+    /// Renders ASS/SSA subtitles for the subtitle layer, see [`VideoBuilder::styled_subtitles`].
+    pub(crate) styled_subtitles: Option<Arc<StyledSubtitles>>,
+    pub(crate) subtitle_offset: i64,
+    pub(crate) captions: Captions,
+
+    pub(crate) skip_segments: Vec<(Duration, Duration, SkipMode)>,
+    pub(crate) skip_segment: Option<usize>,
+
+    pub(crate) rate_map: Vec<(Duration, Duration, f64)>,
+    pub(crate) rate_segment: Option<usize>,
+    pub(crate) base_speed: f64,
+    pub(crate) speed_ramp: Option<SpeedRamp>,
+
+    pub(crate) secondary_audio: Option<(gst::Pipeline, AudioMix)>,
+    pub(crate) track_meters: Option<TrackMeters>,
+    pub(crate) visualizer: Option<Visualizer>,
+
+    pub(crate) load_time: Duration,
+    pub(crate) analytics: Option<Analytics>,
+    /// How full the network buffer is while playback waits for it to refill.
+    pub(crate) buffering: Option<i32>,
+    /// Whether playback resumes once the buffer is full.
+    pub(crate) buffering_resume: bool,
+    /// What was done to playback when the window lost focus, to undo once it is focused again.
+    pub(crate) focus_held: Option<FocusPolicy>,
+    pub(crate) quality: QualityCapper,
+    /// Index of the adaptive stream's variant playing, in [`Video::variants`].
+    pub(crate) variant: Option<usize>,
+
+    pub(crate) lyrics: Vec<(Duration, String)>,
+    pub(crate) lyric_line: Option<usize>,
+
+    pub(crate) data_track: Vec<DataSample>,
+    pub(crate) data_interpolation: DataInterpolation,
+    pub(crate) data_pts: Option<u64>,
+
+    pub(crate) main_audio_track: Option<i32>,
+
+    pub(crate) motion_allowed: bool,
+    pub(crate) motion_poster: bool,
+
+    pub(crate) reported_framerate: Option<f64>,
+    pub(crate) reported_position: Option<Instant>,
+    /// The last position the pipeline reported, and when it changed to it.
+    pub(crate) position_sample: Mutex<Option<(Duration, Instant)>>,
+
+    pub(crate) trim: (Duration, Duration),
+    pub(crate) loop_region: Option<(Duration, Duration)>,
+
+    pub(crate) mute_on_scrub: bool,
+    pub(crate) last_seek: Option<Instant>,
+    /// The smooth seek the pipeline is working on, and the one to run once it's done.
+    pub(crate) smooth_seek: Option<Position>,
+    pub(crate) next_smooth_seek: Option<Position>,
+    /// The volume to fade back to while muted for scrubbing.
+    pub(crate) scrub_volume: Option<f64>,
+    pub(crate) ducking: Option<Ducking>,
+
+    pub(crate) next_uri: Arc<Mutex<Option<NextMedia>>>,
+    /// The trim of the next media once playbin was handed it, until its stream starts.
+    pub(crate) advance_queued: Arc<Mutex<Option<(Duration, Duration)>>>,
+    pub(crate) outro_second: Option<u64>,
+
+    pub(crate) url_refresher: Option<Refresher>,
+    /// Where to seek to once the restarted pipeline prerolled, see [`Internal::finish_restart`].
+    pub(crate) restart_position: Option<Duration>,
+
+    pub(crate) builder: Option<VideoBuilder>,
+
+    pub(crate) content_gate: Option<ContentGate>,
+    pub(crate) gate_muted: bool,
+
+    pub(crate) capture_protected: bool,
+
+    #[cfg(feature = "media-session")]
+    pub(crate) media_session: Option<crate::media_session::MediaSession>,
 }
 
 impl Internal {
-    pub(crate) fn seek(&self, position: impl Into<Position>, accurate: bool) -> Result<(), Error> {
-        let position = position.into();
+    pub(crate) fn seek(
+        &self,
+        position: impl Into<Position>,
+        mode: impl Into<SeekMode>,
+    ) -> Result<(), Error> {
+        let mut position = position.into();
+        let flags = gst::SeekFlags::FLUSH | mode.into().flags();
+
+        // every seek carries the trimmed range, so the trim holds across seeks and loops
+        if self.trim != (Duration::ZERO, Duration::ZERO) {
+            let mut time = match position {
+                Position::Time(t) => t,
+                Position::Frame(f) => Duration::from_secs_f64(f as f64 / self.framerate),
+            };
+            time = time.max(self.trim.0);
+            if let Some(stop) = self.trim_stop() {
+                time = time.min(Duration::from_nanos(stop.nseconds()));
+            }
+            position = Position::Time(time);
+        }
+
+        // seeking before the end of the loop region keeps looping it, seeking past it leaves it
+        if let Some((_, end)) = self.loop_region.filter(|_| self.speed > 0.0) {
+            let time = match position {
+                Position::Time(t) => t,
+                Position::Frame(f) => Duration::from_secs_f64(f as f64 / self.framerate),
+            };
+            if time < end {
+                self.seek_segment(self.speed, time, end, flags)?;
+                self.seek_secondary_audio(time);
+                return Ok(());
+            }
+        }
 
         // gstreamer complains if the start & end value types aren't the same
         match &position {
             Position::Time(_) => self.source.seek(
                 self.speed,
-                gst::SeekFlags::FLUSH
-                    | if accurate {
-                        gst::SeekFlags::ACCURATE
-                    } else {
-                        gst::SeekFlags::empty()
-                    },
+                flags,
                 gst::SeekType::Set,
                 gst::GenericFormattedValue::from(position),
                 gst::SeekType::Set,
-                gst::ClockTime::NONE,
+                self.trim_stop(),
             )?,
             Position::Frame(_) => self.source.seek(
                 self.speed,
-                gst::SeekFlags::FLUSH
-                    | if accurate {
-                        gst::SeekFlags::ACCURATE
-                    } else {
-                        gst::SeekFlags::empty()
-                    },
+                flags,
                 gst::SeekType::Set,
                 gst::GenericFormattedValue::from(position),
                 gst::SeekType::Set,
@@ -104,44 +601,449 @@ impl Internal {
             )?,
         };
 
+        if self.secondary_audio.is_some() {
+            let time = match position {
+                Position::Time(t) => t,
+                Position::Frame(f) => Duration::from_secs_f64(f as f64 / self.framerate),
+            };
+            self.seek_secondary_audio(time);
+        }
+
+        Ok(())
+    }
+
+    /// Seeks to play from `from` to `to` as a segment, so reaching `to` posts a segment-done message
+    /// instead of ending the stream.
+    fn seek_segment(
+        &self,
+        speed: f64,
+        from: Duration,
+        to: Duration,
+        flags: gst::SeekFlags,
+    ) -> Result<(), Error> {
+        self.source.seek(
+            speed,
+            flags | gst::SeekFlags::SEGMENT,
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(from.as_nanos() as _),
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(to.as_nanos() as _),
+        )?;
         Ok(())
     }
 
+    /// Starts the loop region over once playback reached its end; without flushing,
+    /// so the frames already queued play out and the jump back isn't visible.
+    pub(crate) fn restart_loop_region(&self) {
+        let Some((start, end)) = self.loop_region else {
+            return;
+        };
+        // accurate, as a keyframe could be before the start
+        if let Err(err) = self.seek_segment(self.speed, start, end, gst::SeekFlags::ACCURATE) {
+            log::error!("cannot restart loop region: {err}");
+        }
+    }
+
+    /// Moves playback into the loop region from `position`, as when the region was just set.
+    pub(crate) fn enter_loop_region(&self, position: Duration) -> Result<(), Error> {
+        match self.loop_region.filter(|_| self.speed > 0.0) {
+            Some((start, end)) => {
+                let position = position.clamp(start, end);
+                self.seek_segment(
+                    self.speed,
+                    position,
+                    end,
+                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                )?;
+                self.seek_secondary_audio(position);
+                Ok(())
+            }
+            // a flushing seek ends the segment right away
+            None => self.seek(position, true),
+        }
+    }
+
+    fn seek_secondary_audio(&self, position: Duration) {
+        let Some((ref audio, _)) = self.secondary_audio else {
+            return;
+        };
+        let position = gst::ClockTime::from_nseconds(position.as_nanos() as _);
+        let result = if self.speed > 0.0 {
+            audio.seek(
+                self.speed,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                position,
+                gst::SeekType::None,
+                gst::ClockTime::NONE,
+            )
+        } else {
+            audio.seek(
+                self.speed,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                gst::ClockTime::ZERO,
+                gst::SeekType::Set,
+                position,
+            )
+        };
+        if let Err(err) = result {
+            log::error!("cannot seek secondary audio: {err}");
+        }
+    }
+
+    /// Returns `Some` with the new lyric line (`None` for no line) when the line at the playback position changed.
+    pub(crate) fn update_lyric_line(&mut self) -> Option<Option<String>> {
+        let current = crate::lyrics::line_at(&self.lyrics, self.position());
+        if current == self.lyric_line {
+            return None;
+        }
+        self.lyric_line = current;
+        Some(
+            current
+                .map(|i| self.lyrics[i].1.clone())
+                .filter(|line| !line.is_empty()),
+        )
+    }
+
+    /// Returns `Some` with the new chapter (`None` for no chapter) when the chapter at the playback position changed.
+    pub(crate) fn update_chapter(&mut self) -> Option<Option<usize>> {
+        let current = {
+            let chapters = self.chapters.try_lock().ok()?;
+            crate::chapter::chapter_at(&chapters, self.position())
+        };
+        if current == self.chapter {
+            return None;
+        }
+        self.chapter = current;
+        Some(current)
+    }
+
+    /// Returns the data sample for the frame being shown, once per frame.
+    pub(crate) fn update_data_sample(&mut self) -> Option<DataSample> {
+        if self.data_track.is_empty() {
+            return None;
+        }
+        let pts = self.frame_pts.load(Ordering::SeqCst);
+        if self.data_pts == Some(pts) {
+            return None;
+        }
+        self.data_pts = Some(pts);
+        crate::data_track::sample_at(
+            &self.data_track,
+            Duration::from_nanos(pts),
+            self.data_interpolation,
+        )
+    }
+
+    /// Follows the commands of the desktop's media controls and reports playback back to it.
+    #[cfg(feature = "media-session")]
+    pub(crate) fn update_media_session(&mut self) {
+        use crate::media_session::Command;
+
+        let Some(session) = self.media_session.take() else {
+            return;
+        };
+        while let Some(command) = session.command() {
+            let result = match command {
+                Command::Play => {
+                    self.motion_allowed = true;
+                    self.motion_poster = false;
+                    self.set_paused(false);
+                    Ok(())
+                }
+                Command::Pause => {
+                    self.set_paused(true);
+                    Ok(())
+                }
+                Command::PlayPause => {
+                    let paused = self.paused();
+                    if paused {
+                        self.motion_allowed = true;
+                        self.motion_poster = false;
+                    }
+                    self.set_paused(!paused);
+                    Ok(())
+                }
+                Command::Stop => {
+                    self.set_paused(true);
+                    self.seek(self.trim.0, false)
+                }
+                Command::Seek(offset) => {
+                    let distance = Duration::from_micros(offset.unsigned_abs());
+                    let position = if offset >= 0 {
+                        self.position() + distance
+                    } else {
+                        self.position().saturating_sub(distance)
+                    };
+                    self.seek(position.min(self.duration), false)
+                }
+                Command::SetPosition(position) => {
+                    let position = Duration::from_micros(position.max(0) as u64);
+                    self.seek(position.min(self.duration), true)
+                }
+            };
+            if let Err(err) = result {
+                log::error!("cannot follow media session command {command:?}: {err}");
+            }
+        }
+        // MPRIS has no reverse playback, so that reports as its speed
+        session.update(
+            self.paused(),
+            self.position(),
+            self.duration,
+            self.speed.abs(),
+        );
+        self.media_session = Some(session);
+    }
+
+    /// Re-seeks the secondary audio if it drifted too far from the main playback position.
+    pub(crate) fn sync_secondary_audio(&self) {
+        const MAX_DRIFT: Duration = Duration::from_millis(150);
+
+        let Some((ref audio, _)) = self.secondary_audio else {
+            return;
+        };
+        let Some(audio_position) = audio.query_position::<gst::ClockTime>() else {
+            return;
+        };
+        let position = self.position();
+        let audio_position = Duration::from_nanos(audio_position.nseconds());
+        if position.abs_diff(audio_position) > MAX_DRIFT {
+            self.seek_secondary_audio(position);
+        }
+    }
+
+    /// How frames have to be turned to show upright, per the stream's tags.
+    pub(crate) fn orientation(&self) -> Transform {
+        self.orientation
+            .lock()
+            .map(|orientation| *orientation)
+            .unwrap_or_default()
+    }
+
+    /// Width over height of a pixel, overridden or per the stream's caps.
+    pub(crate) fn pixel_aspect(&self) -> f64 {
+        self.pixel_aspect_override.unwrap_or(self.pixel_aspect)
+    }
+
+    /// `(width, height)` of frames once their pixels are made square.
+    pub(crate) fn square_size(&self) -> (i32, i32) {
+        let width = (self.width as f64 * self.pixel_aspect()).round() as i32;
+        (width.max(1), self.height)
+    }
+
+    /// `(width, height)` of frames once made square and turned upright.
+    pub(crate) fn display_size(&self) -> (i32, i32) {
+        self.orientation().display_size(self.square_size())
+    }
+
+    /// Re-seeks the audio track meters if they drifted too far from the main playback position.
+    pub(crate) fn sync_track_meters(&self) {
+        if let Some(ref meters) = self.track_meters {
+            meters.sync(self.position(), self.speed);
+        }
+    }
+
+    /// Passes a bus message on to the analytics sink, if any.
+    pub(crate) fn report_analytics(&mut self, msg: &gst::Message) {
+        let Some(ref mut analytics) = self.analytics else {
+            return;
+        };
+        match msg.view() {
+            gst::MessageView::Buffering(buffering) => analytics.buffering(buffering.percent()),
+            // decoders tag their own stream's bitrate, only demuxers know the whole stream's
+            gst::MessageView::Tag(tag) if from_demuxer(msg) => analytics.tags(&tag.tags()),
+            gst::MessageView::Error(err) => analytics.error(&err.error()),
+            _ => {}
+        }
+    }
+
+    /// Follows the variant an adaptive stream switched to, from the bitrate its demuxer tags,
+    /// returning its index if it changed.
+    pub(crate) fn update_variant(&mut self, msg: &gst::Message) -> Option<usize> {
+        let gst::MessageView::Tag(tag) = msg.view() else {
+            return None;
+        };
+        if !from_demuxer(msg) {
+            return None;
+        }
+        let tags = tag.tags();
+        let bitrate = tags
+            .get::<gst::tags::Bitrate>()
+            .or_else(|| tags.get::<gst::tags::NominalBitrate>())?
+            .get();
+        let demuxers = self.quality.demuxers();
+        let mut demuxers = demuxers.lock().ok()?;
+        // the closest, in case the demuxer measures rather than declares it
+        let index = (0..demuxers.variants.len())
+            .min_by_key(|&i| demuxers.variants[i].bitrate.abs_diff(bitrate))?;
+        if self.variant == Some(index) {
+            return None;
+        }
+        self.variant = Some(index);
+        demuxers.playing(index);
+        Some(index)
+    }
+
     pub(crate) fn set_speed(&mut self, speed: f64) -> Result<(), Error> {
         let Some(position) = self.source.query_position::<gst::ClockTime>() else {
             return Err(Error::Caps);
         };
-        if speed > 0.0 {
-            self.source.seek(
+        let trim_start = gst::ClockTime::from_nseconds(self.trim.0.as_nanos() as _);
+        let from = Duration::from_nanos(position.nseconds());
+        // like seeks, playback past the end of the loop region has left it
+        if let Some((_, end)) = self
+            .loop_region
+            .filter(|&(_, end)| speed > 0.0 && from < end)
+        {
+            self.seek_segment(
                 speed,
+                from,
+                end,
                 gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
-                gst::SeekType::Set,
-                position,
-                gst::SeekType::End,
-                gst::ClockTime::from_seconds(0),
             )?;
+        } else if speed > 0.0 {
+            match self.trim_stop() {
+                Some(stop) => self.source.seek(
+                    speed,
+                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                    gst::SeekType::Set,
+                    position.max(trim_start),
+                    gst::SeekType::Set,
+                    stop,
+                )?,
+                None => self.source.seek(
+                    speed,
+                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                    gst::SeekType::Set,
+                    position.max(trim_start),
+                    gst::SeekType::End,
+                    gst::ClockTime::from_seconds(0),
+                )?,
+            }
         } else {
             self.source.seek(
                 speed,
                 gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
                 gst::SeekType::Set,
-                gst::ClockTime::from_seconds(0),
+                trim_start,
                 gst::SeekType::Set,
                 position,
             )?;
         }
         self.speed = speed;
+        self.seek_secondary_audio(Duration::from_nanos(position.nseconds()));
         Ok(())
     }
 
+    /// Changes the playback speed without flushing, so playback doesn't stutter.
+    /// Falls back to [`Internal::set_speed`] if the pipeline doesn't support instant rate changes.
+    pub(crate) fn set_speed_instant(&mut self, speed: f64) -> Result<(), Error> {
+        if self.change_rate(speed) {
+            return Ok(());
+        }
+        self.set_speed(speed)
+    }
+
+    /// Changes the playback speed with an instant rate change, returning whether the pipeline supported it.
+    fn change_rate(&mut self, speed: f64) -> bool {
+        if speed <= 0.0 || self.speed <= 0.0 {
+            return false;
+        }
+        let instant = self.source.seek(
+            speed,
+            gst::SeekFlags::INSTANT_RATE_CHANGE,
+            gst::SeekType::None,
+            gst::ClockTime::NONE,
+            gst::SeekType::None,
+            gst::ClockTime::NONE,
+        );
+        if instant.is_err() {
+            return false;
+        }
+        self.speed = speed;
+        if let Some((ref audio, _)) = self.secondary_audio {
+            let _ = audio.seek(
+                speed,
+                gst::SeekFlags::INSTANT_RATE_CHANGE,
+                gst::SeekType::None,
+                gst::ClockTime::NONE,
+                gst::SeekType::None,
+                gst::ClockTime::NONE,
+            );
+        }
+        true
+    }
+
+    /// Steps the playback speed along its ramp, see [`Video::ramp_speed`].
+    pub(crate) fn update_speed_ramp(&mut self) {
+        // spaced out so rate changes don't pile up in the pipeline
+        const STEP: Duration = Duration::from_millis(30);
+
+        let Some(ramp) = self.speed_ramp else {
+            return;
+        };
+        let finished = ramp.finished();
+        if !finished && ramp.stepped.elapsed() < STEP {
+            return;
+        }
+        self.speed_ramp = None;
+        if self.change_rate(ramp.speed()) {
+            if !finished {
+                self.speed_ramp = Some(SpeedRamp {
+                    stepped: Instant::now(),
+                    ..ramp
+                });
+            }
+        } else if let Err(err) = self.set_speed(ramp.to) {
+            // a flushing seek at every step would stutter, so jump to the end instead
+            log::error!("cannot ramp speed to {}: {err}", ramp.to);
+        }
+    }
+
+    /// Applies the rate map speed for the current playback position, restoring the base speed outside of it.
+    pub(crate) fn update_rate_map(&mut self) {
+        // the ramp owns the speed while it runs; entering or leaving a segment is caught up with after it
+        if self.speed_ramp.is_some() {
+            return;
+        }
+        let position = self.position();
+        let current = self
+            .rate_map
+            .iter()
+            .position(|&(start, end, _)| position >= start && position < end);
+        if current == self.rate_segment {
+            return;
+        }
+
+        if self.rate_segment.is_none() {
+            self.base_speed = self.speed;
+        }
+        self.rate_segment = current;
+
+        let speed = current.map_or(self.base_speed, |i| self.rate_map[i].2);
+        if let Err(err) = self.set_speed_instant(speed) {
+            log::error!("cannot apply rate map speed {speed}: {err}");
+        }
+    }
+
     pub(crate) fn restart_stream(&mut self) -> Result<(), Error> {
         self.is_eos = false;
         self.set_paused(false);
-        self.seek(0, false)?;
+        self.seek(self.trim.0, false)?;
         Ok(())
     }
 
+    /// Where playback stops because of the trim at the end, if any.
+    fn trim_stop(&self) -> Option<gst::ClockTime> {
+        (!self.trim.1.is_zero() && self.duration > self.trim.1)
+            .then(|| gst::ClockTime::from_nseconds((self.duration - self.trim.1).as_nanos() as _))
+    }
+
     pub(crate) fn set_paused(&mut self, paused: bool) {
+        // the one place playback starts, so nothing can slip past a locked gate
+        let paused = paused || self.content_blocked();
         self.source
             .set_state(if paused {
                 gst::State::Paused
@@ -150,81 +1052,554 @@ impl Internal {
             })
             .unwrap(/* state was changed in ctor; state errors caught there */);
 
+        if let Some((ref audio, _)) = self.secondary_audio {
+            if let Err(err) = audio.set_state(if paused {
+                gst::State::Paused
+            } else {
+                gst::State::Playing
+            }) {
+                log::error!("cannot change secondary audio state: {err}");
+            }
+        }
+
+        if let Some(ref meters) = self.track_meters {
+            if let Err(err) = meters.pipeline.set_state(if paused {
+                gst::State::Paused
+            } else {
+                gst::State::Playing
+            }) {
+                log::error!("cannot change audio track meter state: {err}");
+            }
+        }
+
+        if let Some(ref mut analytics) = self.analytics {
+            analytics.update(!paused);
+        }
+
         // Set restart_stream flag to make the stream restart on the next Message::NextFrame
         if self.is_eos && !paused {
             self.restart_stream = true;
         }
     }
 
-    pub(crate) fn paused(&self) -> bool {
-        self.source.state(gst::ClockTime::ZERO).1 == gst::State::Paused
-    }
-
-    /// Syncs audio with video when there is (inevitably) latency presenting the frame.
-    pub(crate) fn set_av_offset(&mut self, offset: Duration) {
-        if self.sync_av {
-            self.sync_av_counter += 1;
-            self.sync_av_avg = self.sync_av_avg * (self.sync_av_counter - 1) / self.sync_av_counter
-                + offset.as_nanos() as u64 / self.sync_av_counter;
-            if self.sync_av_counter % 128 == 0 {
-                self.source
-                    .set_property("av-offset", -(self.sync_av_avg as i64));
+    /// Follows a buffering message filled to `percent`, holding playback while the buffer refills.
+    ///
+    /// Live streams can't wait, so they play on regardless.
+    pub(crate) fn buffer(&mut self, percent: i32, mode: gst::BufferingMode) {
+        if mode == gst::BufferingMode::Live {
+            return;
+        }
+        match (self.buffering, percent < 100) {
+            (None, true) => {
+                self.buffering_resume = !self.paused();
+                self.buffering = Some(percent);
+                if self.buffering_resume {
+                    self.set_paused(true);
+                }
+            }
+            (Some(_), true) => self.buffering = Some(percent),
+            (Some(_), false) => {
+                self.buffering = None;
+                if self.buffering_resume {
+                    self.set_paused(false);
+                }
             }
+            (None, false) => {}
         }
     }
-}
 
-/// A multimedia video loaded from a URI (e.g., a local file path or HTTP stream).
-#[derive(Debug)]
-pub struct Video(pub(crate) RwLock<Internal>);
-
-impl Drop for Video {
-    fn drop(&mut self) {
-        let inner = self.0.get_mut().expect("failed to lock");
+    /// Pauses or ducks playback as the window loses focus, per `policy`.
+    pub(crate) fn unfocus(&mut self, policy: FocusPolicy) {
+        // fade of the focus ducking, quick as it follows the user switching windows
+        const FADE: Duration = Duration::from_millis(250);
 
-        inner
-            .source
-            .set_state(gst::State::Null)
-            .expect("failed to set state");
+        if self.focus_held.is_some() {
+            return;
+        }
+        match policy {
+            FocusPolicy::Ignore => {}
+            FocusPolicy::Pause => {
+                let playing = if self.buffering.is_some() {
+                    std::mem::take(&mut self.buffering_resume)
+                } else if !self.paused() && !self.is_eos {
+                    self.set_paused(true);
+                    true
+                } else {
+                    false
+                };
+                if playing {
+                    self.focus_held = Some(policy);
+                }
+            }
+            FocusPolicy::Duck(fraction) => {
+                if self.ducking.is_none() {
+                    let fraction = if fraction.is_nan() { 0.0 } else { fraction };
+                    self.ramp_ducking(fraction.clamp(0.0, 1.0), FADE);
+                    self.focus_held = Some(policy);
+                }
+            }
+        }
+    }
 
-        inner.alive.store(false, Ordering::SeqCst);
-        if let Some(worker) = inner.worker.take() {
-            worker.join().expect("failed to stop video thread");
+    /// Undoes what [`Internal::unfocus`] did, as the window is focused again.
+    pub(crate) fn refocus(&mut self) {
+        match self.focus_held.take() {
+            Some(FocusPolicy::Pause) if self.buffering.is_some() => self.buffering_resume = true,
+            Some(FocusPolicy::Pause) => self.set_paused(false),
+            Some(FocusPolicy::Duck(_)) => {
+                if let Some(ducking) = self.ducking {
+                    self.ramp_ducking(1.0, ducking.fade);
+                }
+            }
+            Some(FocusPolicy::Ignore) | None => {}
         }
     }
-}
 
-impl Video {
-    /// Create a new video player from a given video which loads from `uri`.
-    /// Note that live sources will report the duration to be zero.
-    pub fn new(uri: &url::Url) -> Result<Self, Error> {
-        gst::init()?;
+    /// Whether playback is locked by a [`ContentGate`] that doesn't allow obscured playback.
+    pub(crate) fn content_blocked(&self) -> bool {
+        self.content_gate.as_ref().is_some_and(|gate| !gate.obscure)
+    }
 
-        let pipeline = format!("playbin uri=\"{}\" text-sink=\"appsink name=iced_text sync=true caps=text/x-raw\" video-sink=\"videoscale ! videoconvert ! appsink name=iced_video drop=true caps=video/x-raw,format=NV12,pixel-aspect-ratio=1/1\"", uri.as_str());
-        let pipeline = gst::parse::launch(pipeline.as_ref())?
-            .downcast::<gst::Pipeline>()
-            .map_err(|_| Error::Cast)?;
+    /// Whether playback is locked by a [`ContentGate`] and has to be obscured.
+    pub(crate) fn content_obscured(&self) -> bool {
+        self.content_gate.as_ref().is_some_and(|gate| gate.obscure)
+    }
 
-        let video_sink: gst::Element = pipeline.property("video-sink");
-        let pad = video_sink.pads().first().cloned().unwrap();
-        let pad = pad.dynamic_cast::<gst::GhostPad>().unwrap();
-        let bin = pad
-            .parent_element()
-            .unwrap()
-            .downcast::<gst::Bin>()
-            .unwrap();
-        let video_sink = bin.by_name("iced_video").unwrap();
-        let video_sink = video_sink.downcast::<gst_app::AppSink>().unwrap();
+    /// Locks playback behind `gate`, or lifts the current gate with `None`.
+    pub(crate) fn set_content_gate(&mut self, gate: Option<ContentGate>) {
+        let muted = if self.content_obscured() {
+            self.gate_muted
+        } else {
+            self.source.property("mute")
+        };
+        self.content_gate = gate;
+        if self.content_obscured() {
+            // remember what the user wants for once the content is unlocked
+            self.gate_muted = muted;
+            self.source.set_property("mute", true);
+        } else {
+            self.source.set_property("mute", muted);
+        }
+        if self.content_blocked() {
+            self.set_paused(true);
+        }
+    }
+
+    pub(crate) fn paused(&self) -> bool {
+        self.source.state(gst::ClockTime::ZERO).1 == gst::State::Paused
+    }
+
+    pub(crate) fn position(&self) -> Duration {
+        Duration::from_nanos(
+            self.source
+                .query_position::<gst::ClockTime>()
+                .map_or(0, |pos| pos.nseconds()),
+        )
+    }
+
+    /// Returns `Some` with the time left when playback is within `within` of the end,
+    /// once for every second counted down.
+    pub(crate) fn update_outro(&mut self, within: Duration) -> Option<Duration> {
+        if self.duration.is_zero() {
+            return None;
+        }
+        let end = self
+            .trim_stop()
+            .map_or(self.duration, |stop| Duration::from_nanos(stop.nseconds()));
+        let remaining = end.saturating_sub(self.position());
+        if remaining > within {
+            self.outro_second = None;
+            return None;
+        }
+        let second = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        if self.outro_second == Some(second) {
+            return None;
+        }
+        self.outro_second = Some(second);
+        Some(remaining)
+    }
+
+    /// Returns the playback position at most once every `interval`.
+    pub(crate) fn update_position(&mut self, interval: Duration) -> Option<Duration> {
+        let now = Instant::now();
+        if self
+            .reported_position
+            .is_some_and(|reported| now.duration_since(reported) < interval)
+        {
+            return None;
+        }
+        self.reported_position = Some(now);
+        Some(self.position())
+    }
+
+    /// Asks the URL refresher for a fresh URL, e.g., after the server refused the current one.
+    pub(crate) fn request_url_refresh(&mut self) {
+        let uri = self.source.property::<Option<String>>("current-uri");
+        let Some(uri) = uri.and_then(|uri| url::Url::parse(&uri).ok()) else {
+            return;
+        };
+        if let Some(ref mut refresher) = self.url_refresher {
+            refresher.refresh(uri);
+        }
+    }
+
+    /// Refreshes the URL when it expired, and reconnects once a fresh one arrives.
+    pub(crate) fn update_url_refresh(&mut self) {
+        if self
+            .url_refresher
+            .as_ref()
+            .is_some_and(|refresher| refresher.expired())
+        {
+            self.request_url_refresh();
+        }
+        let Some(result) = self.url_refresher.as_mut().and_then(|r| r.poll()) else {
+            return;
+        };
+        match result {
+            Some(uri) => {
+                if let Err(err) = self.reconnect(&uri) {
+                    log::error!("cannot reconnect to refreshed URL: {err}");
+                }
+            }
+            None => log::warn!("URL refresher gave up, stream will stop when its URL expires"),
+        }
+    }
+
+    /// Switches to `uri` (the same media at another URL), resuming from the current position.
+    fn reconnect(&mut self, uri: &url::Url) -> Result<(), Error> {
+        let position = self.position();
+        self.load_uri(uri)?;
+        self.restart_from(position);
+        Ok(())
+    }
+
+    /// Seeks to `position` once the pipeline, just restarted, prerolled again, see [`Internal::finish_restart`].
+    pub(crate) fn restart_from(&mut self, position: Duration) {
+        // a restart already underway still knows where the media was
+        self.restart_position = Some(self.restart_position.unwrap_or(position));
+    }
+
+    /// Seeks back to where the media was before the pipeline was restarted (e.g., to reconnect,
+    /// switch programs or add subtitles), once it prerolled; waiting for that on the caller's thread would freeze the UI.
+    pub(crate) fn finish_restart(&mut self) {
+        let Some(position) = self.restart_position.take() else {
+            return;
+        };
+        if let Err(err) = self.seek(position, true) {
+            log::error!("cannot restore the position after restarting: {err}");
+        }
+    }
+
+    /// Replaces the media with `uri`, keeping the play/pause state.
+    pub(crate) fn load_uri(&mut self, uri: &url::Url) -> Result<(), Error> {
+        let paused = self.paused();
+        self.source.set_state(gst::State::Ready)?;
+        self.source.set_property("uri", uri.as_str());
+        self.set_paused(paused);
+        self.is_eos = false;
+        Ok(())
+    }
+
+    /// Scales frames down to fit within `size`, see [`Video::set_output_size`].
+    pub(crate) fn set_output_size(&self, size: Option<(u32, u32)>) {
+        let Ok(mut sink_caps) = self.sink_caps.lock() else {
+            return;
+        };
+        let source = sink_caps.decoded.unwrap_or((self.width, self.height));
+        let scaled = size.and_then(|size| crate::sink_caps::fit(source, self.pixel_aspect(), size));
+        let unchanged = match (sink_caps.scaled, scaled) {
+            (Some(current), Some(scaled)) => crate::sink_caps::similar(current, scaled),
+            (current, scaled) => current == scaled,
+        };
+        if !unchanged {
+            sink_caps.scaled = scaled;
+            sink_caps.apply(&self.video_sink, true);
+        }
+    }
+
+    /// Catches up with the size frames are decoded at, e.g., after switching video tracks.
+    pub(crate) fn update_size(&mut self) {
+        let decoded = self
+            .sink_caps
+            .lock()
+            .ok()
+            .and_then(|sink_caps| sink_caps.decoded);
+        if let Some((width, height)) = decoded {
+            self.width = width;
+            self.height = height;
+        }
+    }
+
+    /// Catches up with playbin having switched to the next media once a stream starts;
+    /// returns whether it did.
+    pub(crate) fn update_advanced(&mut self) -> bool {
+        let Some(trim) = self
+            .advance_queued
+            .lock()
+            .ok()
+            .and_then(|mut queued| queued.take())
+        else {
+            return false;
+        };
+        // trims are per media, the next one's was queued along with it
+        self.trim = trim;
+        // the duration of the next media usually follows with a duration-changed message
+        self.update_duration();
+        self.outro_second = None;
+        self.is_eos = false;
+        if trim != (Duration::ZERO, Duration::ZERO) {
+            if let Err(err) = self.seek(trim.0, true) {
+                log::error!("cannot trim the next media: {err}");
+            }
+        }
+        true
+    }
+
+    /// Queries the duration of the media again, e.g., after it changed.
+    pub(crate) fn update_duration(&mut self) {
+        let unknown = self.duration.is_zero();
+        self.duration = Duration::from_nanos(
+            self.source
+                .query_duration::<gst::ClockTime>()
+                .map_or(0, |duration| duration.nseconds()),
+        );
+        // the trim at the end needs the duration, which may only be known once the media started
+        if unknown && !self.duration.is_zero() && !self.trim.1.is_zero() {
+            if let Err(err) = self.seek(self.position(), true) {
+                log::error!("cannot trim the end of the media: {err}");
+            }
+        }
+    }
+
+    /// Checks the playback position against the skip segments, skipping [`SkipMode::Auto`] ones.
+    ///
+    /// Returns `Some` when playback entered (`Some(segment)`) or left (`None`) a [`SkipMode::Prompt`] segment.
+    pub(crate) fn update_skip_segment(&mut self) -> Option<Option<(Duration, Duration)>> {
+        let position = self.position();
+        let current = self
+            .skip_segments
+            .iter()
+            .position(|&(start, end, _)| position >= start && position < end);
+        if current == self.skip_segment {
+            return None;
+        }
+
+        let was_prompt = self
+            .skip_segment
+            .is_some_and(|i| self.skip_segments[i].2 == SkipMode::Prompt);
+        self.skip_segment = current;
+
+        match current.map(|i| self.skip_segments[i]) {
+            Some((_, end, SkipMode::Auto)) => {
+                if let Err(err) = self.seek(end, true) {
+                    log::error!("cannot skip segment: {err}");
+                }
+                was_prompt.then_some(None)
+            }
+            Some((start, end, SkipMode::Prompt)) => Some(Some((start, end))),
+            None => was_prompt.then_some(None),
+        }
+    }
+
+    /// Seeks right away if the pipeline is done with the previous smooth seek,
+    /// otherwise replaces whatever seek was waiting for it.
+    pub(crate) fn seek_smooth(&mut self, position: Position) -> Result<(), Error> {
+        if self.smooth_seek.is_some() && !self.seek_settled() {
+            self.next_smooth_seek = Some(position);
+            return Ok(());
+        }
+        self.smooth_seek = Some(position);
+        self.seek(position, SeekMode::Accurate)
+    }
+
+    /// Runs the waiting smooth seek once the previous one finished;
+    /// returns the position once the last one did.
+    pub(crate) fn update_smooth_seek(&mut self) -> Option<Duration> {
+        if self.smooth_seek.is_none() || !self.seek_settled() {
+            return None;
+        }
+        if let Some(position) = self.next_smooth_seek.take() {
+            self.smooth_seek = Some(position);
+            if let Err(err) = self.seek(position, SeekMode::Accurate) {
+                log::error!("cannot seek to {position:?}: {err}");
+            }
+            return None;
+        }
+        self.smooth_seek = None;
+        Some(self.position())
+    }
+
+    /// Whether the pipeline finished the last flushing seek, i.e., it is not prerolling anymore.
+    fn seek_settled(&self) -> bool {
+        self.source.state(gst::ClockTime::ZERO).2 == gst::State::VoidPending
+    }
+
+    /// Mutes the audio once seeks come in fast enough to be scrubbing.
+    pub(crate) fn debounce_scrub(&mut self) {
+        let now = Instant::now();
+        let scrubbing = self
+            .last_seek
+            .is_some_and(|last| now.duration_since(last) < SCRUB_INTERVAL);
+        self.last_seek = Some(now);
+        if self.mute_on_scrub && scrubbing && self.scrub_volume.is_none() {
+            self.scrub_volume = Some(self.volume());
+            self.set_volume(0.0);
+        }
+    }
+
+    /// Fades the audio back in once scrubbing stopped.
+    pub(crate) fn update_scrub_fade(&mut self) {
+        let (Some(volume), Some(last)) = (self.scrub_volume, self.last_seek) else {
+            return;
+        };
+        let Some(elapsed) = last.elapsed().checked_sub(SCRUB_INTERVAL) else {
+            return;
+        };
+        let t = (elapsed.as_secs_f64() / SCRUB_FADE.as_secs_f64()).min(1.0);
+        self.set_volume(volume * t);
+        if t >= 1.0 {
+            self.scrub_volume = None;
+        }
+    }
+
+    /// Ramps the audio towards the volume it is ducked to, see [`Video::duck`].
+    pub(crate) fn update_ducking(&mut self) {
+        let Some(ducking) = self.ducking else {
+            return;
+        };
+        if ducking.settled {
+            return;
+        }
+        // the scrub fade applies the gain as it goes
+        if self.scrub_volume.is_none() {
+            self.set_volume(ducking.volume);
+        }
+        if !ducking.finished() {
+            return;
+        }
+        if ducking.to >= 1.0 {
+            self.ducking = None;
+        } else if let Some(ref mut ducking) = self.ducking {
+            ducking.settled = true;
+        }
+    }
+
+    /// Ramps the ducking gain to `to` over `fade`, see [`Video::duck`].
+    fn ramp_ducking(&mut self, to: f64, fade: Duration) {
+        let volume = self.volume();
+        // carry on from wherever a previous ramp got to
+        let from = self.ducking.map_or(1.0, |ducking| ducking.gain());
+        self.ducking = Some(Ducking {
+            volume,
+            from,
+            to,
+            start: Instant::now(),
+            fade,
+            settled: false,
+        });
+        self.update_ducking();
+    }
+
+    /// The volume the user set, leaving ducking aside.
+    pub(crate) fn volume(&self) -> f64 {
+        self.ducking
+            .map_or_else(|| self.source.property("volume"), |ducking| ducking.volume)
+    }
+
+    /// Sets the volume of the pipeline, lowered by ducking if any.
+    pub(crate) fn set_volume(&self, volume: f64) {
+        let volume = volume * self.ducking.map_or(1.0, |ducking| ducking.gain());
+        // for some reason gstreamer unmutes when changing volume?
+        let muted: bool = self.source.property("mute");
+        self.source.set_property("volume", volume);
+        self.source.set_property("mute", muted);
+    }
+
+    /// Delays (or advances) subtitles by the subtitle offset.
+    pub(crate) fn apply_subtitle_offset(&self) {
+        let offset = self.subtitle_offset.saturating_mul(1_000_000);
+        // events are rendered by their timestamps, whenever they get to the text sink
+        if let Some(styled_subtitles) = &self.styled_subtitles {
+            styled_subtitles.set_offset(offset);
+        }
+        let text_sink = if self.source.has_property("text-sink", None) {
+            self.source.property::<Option<gst::Element>>("text-sink")
+        } else {
+            None
+        };
+        match text_sink {
+            Some(sink) => sink.set_property("ts-offset", offset),
+            // subtitles rendered onto the frames (GStreamer 1.20+)
+            None if self.source.has_property("text-offset", None) => {
+                self.source.set_property("text-offset", offset)
+            }
+            None => log::warn!("cannot offset the subtitles of this pipeline"),
+        }
+    }
+
+    /// Averages in the latency of a frame, see [`Video::latency`].
+    pub(crate) fn record_latency(&mut self, latency: Duration) {
+        // weight of the latest frame in the average
+        const SMOOTHING: f64 = 0.1;
+
+        self.latency = Some(match self.latency {
+            Some(average) => average.mul_f64(1.0 - SMOOTHING) + latency.mul_f64(SMOOTHING),
+            None => latency,
+        });
+    }
+
+    /// Syncs audio with video when there is (inevitably) latency presenting the frame.
+    // `u64::is_multiple_of` is only stable since Rust 1.87, which this crate doesn't require
+    // (`unknown_lints` for the clippy releases before the lint)
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+    pub(crate) fn set_av_offset(&mut self, offset: Duration) {
+        if self.sync_av {
+            self.sync_av_counter += 1;
+            // running average over the last ~128 frames, so it keeps following the compositor's latency
+            let weight = self.sync_av_counter.min(128);
+            self.sync_av_avg =
+                self.sync_av_avg * (weight - 1) / weight + offset.as_nanos() as u64 / weight;
+            if self.sync_av_counter % 128 == 0 {
+                self.source
+                    .set_property("av-offset", -(self.sync_av_avg as i64));
+            }
+        }
+    }
+}
+
+/// A multimedia video loaded from a URI (e.g., a local file path or HTTP stream).
+#[derive(Debug)]
+pub struct Video(pub(crate) RwLock<Internal>);
+
+impl Drop for Video {
+    fn drop(&mut self) {
+        let inner = self.0.get_mut().expect("failed to lock");
+
+        inner
+            .source
+            .set_state(gst::State::Null)
+            .expect("failed to set state");
+
+        if let Some((audio, _)) = inner.secondary_audio.take() {
+            let _ = audio.set_state(gst::State::Null);
+        }
 
-        let text_sink: gst::Element = pipeline.property("text-sink");
-        //let pad = text_sink.pads().get(0).cloned().unwrap();
-        let text_sink = text_sink.downcast::<gst_app::AppSink>().unwrap();
+        inner.alive.store(false, Ordering::SeqCst);
+        if let Some(worker) = inner.worker.take() {
+            worker.join().expect("failed to stop video thread");
+        }
+    }
+}
 
-        Self::from_gst_pipeline(pipeline, video_sink, Some(text_sink))
+impl Video {
+    /// Create a new video player from a given video which loads from `uri`.
+    /// Note that live sources will report the duration to be zero.
+    pub fn new(uri: &url::Url) -> Result<Self, Error> {
+        VideoBuilder::new(uri).build()
     }
 
     /// Creates a new video based on an existing GStreamer pipeline and appsink.
-    /// Expects an `appsink` plugin with `caps=video/x-raw,format=NV12`.
+    /// Expects an `appsink` plugin with `caps=video/x-raw,format=NV12` (or `format={ NV12, P010_10LE }` for 10-bit video).
     ///
     /// An optional `text_sink` can be provided, which enables subtitle messages
     /// to be emitted.
@@ -236,13 +1611,141 @@ impl Video {
         video_sink: gst_app::AppSink,
         text_sink: Option<gst_app::AppSink>,
     ) -> Result<Self, Error> {
+        Self::from_gst_pipeline_scheduled(
+            pipeline,
+            video_sink,
+            text_sink,
+            None,
+            Scheduling::default(),
+            None,
+        )
+    }
+
+    /// Creates a new video from a `gst-launch-1.0` style pipeline `description` (e.g., a test source,
+    /// custom filters or a capture chain), ending in an `appsink` named `sink_name` (`iced_video` by default).
+    ///
+    /// Unless the description sets caps on the appsink, it gets those the widget needs, so put a `videoconvert`
+    /// before it. An `appsink` named `iced_text` is used as the text sink if there is one.
+    /// The same caveats as with [`Video::from_gst_pipeline`] apply.
+    pub fn from_pipeline(description: &str, sink_name: Option<&str>) -> Result<Self, Error> {
+        gst::init()?;
+        let sink_name = sink_name.unwrap_or("iced_video");
+        let pipeline = match gst::parse::launch(description)?.downcast::<gst::Pipeline>() {
+            Ok(pipeline) => pipeline,
+            // a lone element isn't put in a pipeline
+            Err(element) => {
+                let pipeline = gst::Pipeline::new();
+                pipeline.add(&element)?;
+                pipeline
+            }
+        };
+
+        let appsink = |name: &str| {
+            pipeline
+                .by_name(name)
+                .and_then(|sink| sink.downcast::<gst_app::AppSink>().ok())
+        };
+        let video_sink = appsink(sink_name).ok_or_else(|| Error::AppSink(sink_name.into()))?;
+        if video_sink.caps().is_none() {
+            video_sink.set_caps(Some(
+                &gst::Caps::builder("video/x-raw")
+                    .field("format", gst::List::new(["NV12", "P010_10LE"]))
+                    .build(),
+            ));
+        }
+        let text_sink = appsink("iced_text");
+
+        Self::from_gst_pipeline(pipeline, video_sink, text_sink)
+    }
+
+    pub(crate) fn from_gst_pipeline_scheduled(
+        pipeline: gst::Pipeline,
+        video_sink: gst_app::AppSink,
+        text_sink: Option<gst_app::AppSink>,
+        styled_subtitles: Option<StyledSubtitles>,
+        scheduling: Scheduling,
+        content_gate: Option<ContentGate>,
+    ) -> Result<Self, Error> {
+        let created = Instant::now();
         gst::init()?;
+        scheduling.watch_streaming_threads(&pipeline);
         static NEXT_ID: AtomicU64 = AtomicU64::new(0);
         let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
 
         let pad = video_sink.pads().first().cloned().unwrap();
+        crate::frame::reserve_buffers(&pad);
+
+        // MPEG-TS program to demux, -1 for the first one
+        let program = Arc::new(AtomicI32::new(-1));
+        let program_ref = Arc::clone(&program);
+        // teletext page to decode subtitles from, 888 being the usual subtitle page
+        let teletext_page = Arc::new(AtomicI32::new(888));
+        let teletext_page_ref = Arc::clone(&teletext_page);
+        let subtitle_text = Arc::new(Mutex::new(None));
+        let upload_text = Arc::new(AtomicBool::new(false));
+        let teletext = Teletext {
+            text: Arc::clone(&subtitle_text),
+            updated: Arc::clone(&upload_text),
+            page: Arc::clone(&teletext_page),
+        };
+        let quality = QualityCapper::default();
+        let demuxers = quality.demuxers();
+        let measure_latency = Arc::new(AtomicBool::new(false));
+        let stamp_latency = Arc::clone(&measure_latency);
+        pipeline.connect_deep_element_added(move |bin, _, element| {
+            let factory = element.factory().map(|factory| factory.name());
+            match factory.as_ref().map(|name| name.as_str()) {
+                Some(name) if QualityCapper::is_adaptive_demuxer(name) => {
+                    crate::variant::watch_manifest(element, Arc::clone(&demuxers));
+                    if let Ok(mut demuxers) = demuxers.lock() {
+                        demuxers.add(element);
+                    }
+                }
+                Some("tsdemux") => {
+                    element.set_property("program-number", program_ref.load(Ordering::SeqCst))
+                }
+                Some("teletextdec") => {
+                    element.set_property("subtitles-mode", true);
+                    element.set_property("page", teletext_page_ref.load(Ordering::SeqCst));
+                }
+                Some("uridecodebin") => {
+                    crate::teletext::decode_teletext(bin.upcast_ref(), element, teletext.clone())
+                }
+                Some("rtspsrc") => crate::latency::report_captures(element),
+                _ if element.is::<gst_base::BaseSrc>() => {
+                    crate::latency::stamp_captures(element, Arc::clone(&stamp_latency))
+                }
+                _ => {}
+            }
+        });
 
-        pipeline.set_state(gst::State::Playing)?;
+        // queue the next media right before the current one runs out, so playbin switches to it without a gap
+        let next_uri: Arc<Mutex<Option<NextMedia>>> = Arc::new(Mutex::new(None));
+        // the switch itself only happens once the next media's stream starts, see `Internal::update_advanced`
+        let advance_queued = Arc::new(Mutex::new(None));
+        if pipeline.has_property("current-uri", None) {
+            let next_uri_ref = Arc::clone(&next_uri);
+            let advance_queued_ref = Arc::clone(&advance_queued);
+            pipeline.connect("about-to-finish", false, move |args| {
+                let playbin = args[0].get::<gst::Element>().ok()?;
+                let (next, trim) = next_uri_ref.lock().ok()?.take()?;
+                playbin.set_property("uri", next.as_str());
+                *advance_queued_ref.lock().ok()? = Some(trim);
+                None
+            });
+        }
+
+        // locked content must not play even for a moment, so it only prerolls
+        if content_gate.as_ref().is_some_and(|gate| gate.obscure)
+            && pipeline.has_property("mute", None)
+        {
+            pipeline.set_property("mute", true);
+        }
+        pipeline.set_state(if content_gate.as_ref().is_some_and(|gate| !gate.obscure) {
+            gst::State::Paused
+        } else {
+            gst::State::Playing
+        })?;
 
         // wait for up to 5 seconds until the decoder gets the source capabilities
         pipeline.state(gst::ClockTime::from_seconds(5)).0?;
@@ -267,6 +1770,22 @@ impl Video {
         {
             return Err(Error::Framerate(framerate));
         }
+        let format = PixelFormat::from_caps(s);
+        let transfer = Transfer::from_caps(s);
+        // non-square pixels (e.g., anamorphic DVDs and DV) are stretched when drawn, not scaled in the pipeline
+        let pixel_aspect = s
+            .get::<gst::Fraction>("pixel-aspect-ratio")
+            .ok()
+            .map(|par| par.numer() as f64 / par.denom() as f64)
+            .filter(|par| par.is_finite() && *par > 0.0)
+            .unwrap_or(1.0);
+
+        let gaps = Arc::new(Mutex::new(Vec::new()));
+        crate::gap::watch_gaps(&pad, framerate, Arc::clone(&gaps));
+        let chapters = Arc::new(Mutex::new(Vec::new()));
+        crate::chapter::watch_chapters(&pad, Arc::clone(&chapters));
+        let orientation = Arc::new(Mutex::new(Transform::default()));
+        crate::transform::watch_orientation(&pad, Arc::clone(&orientation));
 
         let duration = Duration::from_nanos(
             pipeline
@@ -277,248 +1796,1788 @@ impl Video {
 
         let sync_av = pipeline.has_property("av-offset", None);
 
-        // NV12 = 12bpp
-        let frame = Arc::new(Mutex::new(vec![
-            0u8;
-            (width as usize * height as usize * 3)
-                .div_ceil(2)
-        ]));
+        let (frame, mut frame_writer) = FrameBuffer::new(format, (width as _, height as _));
         let upload_frame = Arc::new(AtomicBool::new(false));
         let alive = Arc::new(AtomicBool::new(true));
         let last_frame_time = Arc::new(Mutex::new(Instant::now()));
+        let frame_pts = Arc::new(AtomicU64::new(0));
 
-        let frame_ref = Arc::clone(&frame);
         let upload_frame_ref = Arc::clone(&upload_frame);
         let alive_ref = Arc::clone(&alive);
         let last_frame_time_ref = Arc::clone(&last_frame_time);
+        let frame_pts_ref = Arc::clone(&frame_pts);
+        let frames_dropped = Arc::new(AtomicU32::new(0));
+        let frames_dropped_ref = Arc::clone(&frames_dropped);
+        let static_content = Arc::new(AtomicBool::new(false));
+        let static_content_ref = Arc::clone(&static_content);
+        let frame_hash = Arc::new(Mutex::new(None));
+        let frame_hash_ref = Arc::clone(&frame_hash);
+        let hash_updated = Arc::new(AtomicBool::new(false));
+        let hash_updated_ref = Arc::clone(&hash_updated);
+        let frame_hashing = Arc::new(AtomicBool::new(false));
+        let frame_hashing_ref = Arc::clone(&frame_hashing);
+        let force_upload = Arc::new(AtomicBool::new(false));
+        let force_upload_ref = Arc::clone(&force_upload);
+        let frame_metadata = Arc::new(Mutex::new(None));
+        let frame_metadata_ref = Arc::clone(&frame_metadata);
+        let metadata_updated = Arc::new(AtomicBool::new(false));
+        let metadata_updated_ref = Arc::clone(&metadata_updated);
+        let capture_time = Arc::new(Mutex::new(None));
+        let capture_time_ref = Arc::clone(&capture_time);
+        let measure_latency_ref = Arc::clone(&measure_latency);
 
-        let subtitle_text = Arc::new(Mutex::new(None));
-        let upload_text = Arc::new(AtomicBool::new(false));
         let subtitle_text_ref = Arc::clone(&subtitle_text);
         let upload_text_ref = Arc::clone(&upload_text);
+        let styled_subtitles = styled_subtitles.map(Arc::new);
+        let styled_subtitles_ref = styled_subtitles.clone();
 
         let pipeline_ref = pipeline.clone();
+        let sink = video_sink.clone();
+        let sink_caps = SinkCaps::new(video_sink.caps());
 
         let worker = std::thread::spawn(move || {
+            scheduling.apply_to_current_thread();
             let mut clear_subtitles_at = None;
+            let mut last_hash = None;
 
             while alive_ref.load(Ordering::Acquire) {
                 if let Err(gst::FlowError::Error) = (|| -> Result<(), gst::FlowError> {
-                    let sample =
-                        if pipeline_ref.state(gst::ClockTime::ZERO).1 != gst::State::Playing {
-                            video_sink
-                                .try_pull_preroll(gst::ClockTime::from_mseconds(16))
-                                .ok_or(gst::FlowError::Eos)?
-                        } else {
-                            video_sink
-                                .try_pull_sample(gst::ClockTime::from_mseconds(16))
-                                .ok_or(gst::FlowError::Eos)?
-                        };
+                    let playing = pipeline_ref.state(gst::ClockTime::ZERO).1 == gst::State::Playing;
+                    let sample = if !playing {
+                        video_sink
+                            .try_pull_preroll(gst::ClockTime::from_mseconds(16))
+                            .ok_or(gst::FlowError::Eos)?
+                    } else {
+                        video_sink
+                            .try_pull_sample(gst::ClockTime::from_mseconds(16))
+                            .ok_or(gst::FlowError::Eos)?
+                    };
 
                     *last_frame_time_ref
                         .lock()
                         .map_err(|_| gst::FlowError::Error)? = Instant::now();
 
-                    let buffer = sample.buffer().ok_or(gst::FlowError::Error)?;
+                    // frames shrink and grow as the sink is rescaled (see `Video::set_output_size`)
+                    let size = sample
+                        .caps()
+                        .and_then(|caps| caps.structure(0))
+                        .and_then(|s| {
+                            Some((s.get::<i32>("width").ok()?, s.get::<i32>("height").ok()?))
+                        })
+                        .map_or((width as u32, height as u32), |(width, height)| {
+                            (((width + 4 - 1) / 4 * 4) as u32, height as u32)
+                        });
+                    let buffer = sample.buffer_owned().ok_or(gst::FlowError::Error)?;
                     let pts = buffer.pts().unwrap_or_default();
-                    let map = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+                    let flags = buffer.flags();
+                    if measure_latency_ref.load(Ordering::SeqCst) {
+                        *capture_time_ref.lock().map_err(|_| gst::FlowError::Error)? =
+                            crate::latency::capture_time(&buffer);
+                    }
+                    let metadata = crate::frame_metadata::read(&buffer);
+                    if metadata.is_some() {
+                        metadata_updated_ref.store(true, Ordering::SeqCst);
+                    }
+                    *frame_metadata_ref
+                        .lock()
+                        .map_err(|_| gst::FlowError::Error)? = metadata;
+                    // hashing reads every byte of the frame, so it's only done when asked for;
+                    // frames left in DMA-BUFs aren't hashed, as that would read them back from the GPU
+                    let hashing = frame_hashing_ref.load(Ordering::SeqCst);
+                    #[cfg(feature = "dmabuf")]
+                    let dmabuf =
+                        crate::dmabuf::DmaBufFrame::new(&buffer, size).map(FrameData::DmaBuf);
+                    #[cfg(not(feature = "dmabuf"))]
+                    let dmabuf = None;
+                    let (frame, hash) = match dmabuf {
+                        Some(frame) => (frame, None),
+                        None => {
+                            let map = buffer
+                                .into_mapped_buffer_readable()
+                                .map_err(|_| gst::FlowError::Error)?;
+                            // decoders may pad the end of the buffer, which would make equal frames hash differently
+                            let frame_len = format.frame_len(size.0, size.1);
+                            let hash = hashing
+                                .then(|| hash_frame(&map.as_slice()[..frame_len.min(map.size())]));
+                            (FrameData::Mapped(map), hash)
+                        }
+                    };
+
+                    // skip uploading frames identical to the last one (e.g., slides or screen shares);
+                    // the first frame after a seek is always shown, as something may be waiting for it
+                    *frame_hash_ref.lock().map_err(|_| gst::FlowError::Error)? = hash;
+                    hash_updated_ref.store(hash.is_some(), Ordering::SeqCst);
+                    let unchanged = !force_upload_ref.load(Ordering::SeqCst)
+                        && (flags.contains(gst::BufferFlags::GAP)
+                            || (hash.is_some()
+                                && !flags.contains(gst::BufferFlags::DISCONT)
+                                && last_hash == hash));
+                    last_hash = hash;
+                    static_content_ref.store(unchanged, Ordering::SeqCst);
+                    frame_pts_ref.store(pts.nseconds(), Ordering::SeqCst);
+
+                    if !unchanged {
+                        // never waits on the renderer; if it hasn't picked up the previous frame yet,
+                        // that frame is dropped in favor of this one
+                        let replaced = frame_writer.write(frame, size);
+
+                        upload_frame_ref.store(true, Ordering::SeqCst);
+                        if replaced && playing {
+                            frames_dropped_ref.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+
+                    if let Some(at) = clear_subtitles_at {
+                        if pts >= at {
+                            *subtitle_text_ref
+                                .lock()
+                                .map_err(|_| gst::FlowError::Error)? = None;
+                            upload_text_ref.store(true, Ordering::SeqCst);
+                            clear_subtitles_at = None;
+                        }
+                    }
+
+                    let text = text_sink
+                        .as_ref()
+                        .and_then(|sink| sink.try_pull_sample(gst::ClockTime::from_seconds(0)));
+                    // ASS/SSA events go to the subtitle layer rather than out as text
+                    let text = match (text, &styled_subtitles_ref) {
+                        (Some(text), Some(styled_subtitles)) if StyledSubtitles::accepts(&text) => {
+                            styled_subtitles.push_text(&text);
+                            None
+                        }
+                        (text, _) => text,
+                    };
+                    if let Some(text) = text {
+                        let text = text.buffer().ok_or(gst::FlowError::Error)?;
+                        let pts = text.pts().unwrap_or_default();
+                        let duration = text.duration().unwrap_or(gst::ClockTime::ZERO);
+                        let map = text.map_readable().map_err(|_| gst::FlowError::Error)?;
+
+                        let text = html_escape::decode_html_entities(
+                            std::str::from_utf8(map.as_slice())
+                                .map_err(|_| gst::FlowError::Error)?,
+                        )
+                        .to_string();
+                        *subtitle_text_ref
+                            .lock()
+                            .map_err(|_| gst::FlowError::Error)? = Some(text);
+                        upload_text_ref.store(true, Ordering::SeqCst);
+
+                        clear_subtitles_at = Some(pts + duration);
+                    }
+                    if let Some(styled_subtitles) = &styled_subtitles_ref {
+                        styled_subtitles.render(pts, size);
+                    }
+
+                    Ok(())
+                })() {
+                    log::error!("error pulling frame");
+                }
+            }
+        });
+
+        Ok(Video(RwLock::new(Internal {
+            id,
+            video_id: VideoId(id),
+
+            bus: pipeline.bus().unwrap(),
+            audio_devices: None,
+            source: pipeline,
+            alive,
+            worker: Some(worker),
+
+            width,
+            height,
+            framerate,
+            format,
+            transfer,
+            color_balance: ColorBalance::default(),
+            lut: None,
+            orientation,
+            pixel_aspect,
+            pixel_aspect_override: None,
+            duration,
+            speed: 1.0,
+            sync_av,
+
+            frame,
+            video_sink: sink,
+            sink_caps: Arc::new(Mutex::new(sink_caps)),
+            upload_frame,
+            last_frame_time,
+            frame_pts,
+            frames_dropped,
+            static_content,
+            frame_hash,
+            hash_updated,
+            frame_hashing,
+            force_upload,
+            frame_metadata,
+            metadata_updated,
+            renderer: Arc::new(AtomicU64::new(0)),
+            renderer_reset: Arc::new(AtomicBool::new(false)),
+            scale_factor: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            gaps,
+            chapters,
+            bookmarks: Vec::new(),
+            chapter: None,
+            program,
+            teletext_page,
+            upload_time: Instant::now(),
+            looping: false,
+            is_eos: false,
+            restart_stream: false,
+            sync_av_avg: 0,
+            sync_av_counter: 0,
+            presenting_frame_time: None,
+            measure_latency,
+            capture_time,
+            presenting_capture: None,
+            latency: None,
+
+            subtitle_text,
+            upload_text,
+            styled_subtitles,
+            subtitle_offset: 0,
+            captions: Captions::default(),
+
+            skip_segments: Vec::new(),
+            skip_segment: None,
+
+            rate_map: Vec::new(),
+            rate_segment: None,
+            base_speed: 1.0,
+            speed_ramp: None,
+
+            secondary_audio: None,
+            track_meters: None,
+            visualizer: None,
+
+            load_time: created.elapsed(),
+            analytics: None,
+            buffering: None,
+            buffering_resume: false,
+            focus_held: None,
+            quality,
+            variant: None,
+
+            lyrics: Vec::new(),
+            lyric_line: None,
+
+            data_track: Vec::new(),
+            data_interpolation: DataInterpolation::default(),
+            data_pts: None,
+
+            main_audio_track: None,
+
+            motion_allowed: false,
+            motion_poster: false,
+
+            reported_framerate: None,
+            reported_position: None,
+            position_sample: Mutex::new(None),
+
+            trim: (Duration::ZERO, Duration::ZERO),
+            loop_region: None,
+
+            mute_on_scrub: false,
+            last_seek: None,
+            smooth_seek: None,
+            next_smooth_seek: None,
+            scrub_volume: None,
+            ducking: None,
+
+            next_uri,
+            advance_queued,
+            outro_second: None,
+
+            url_refresher: None,
+            restart_position: None,
+
+            builder: None,
+
+            content_gate,
+            gate_muted: false,
+
+            capture_protected: false,
+
+            #[cfg(feature = "media-session")]
+            media_session: None,
+        })))
+    }
+
+    pub(crate) fn read(&self) -> impl Deref<Target = Internal> + '_ {
+        self.0.read().expect("lock")
+    }
+
+    pub(crate) fn write(&self) -> impl DerefMut<Target = Internal> + '_ {
+        self.0.write().expect("lock")
+    }
+
+    pub(crate) fn get_mut(&mut self) -> impl DerefMut<Target = Internal> + '_ {
+        self.0.get_mut().expect("lock")
+    }
+
+    /// Get the id of the video, which stays the same across rebuilds, see [`VideoRegistry`](crate::VideoRegistry).
+    pub fn id(&self) -> VideoId {
+        self.read().video_id
+    }
+
+    /// Get the size of the video as `(width, height)`, with its pixels made square: stretched to the
+    /// pixel aspect ratio (see [`Video::set_pixel_aspect_ratio`]), as it should be laid out.
+    pub fn size(&self) -> (i32, i32) {
+        self.read().square_size()
+    }
+
+    /// Get the resolution of the video as `(width, height)`, as decoded (e.g., 720 by 576 for anamorphic PAL).
+    pub fn decoded_size(&self) -> (i32, i32) {
+        let inner = self.read();
+        (inner.width, inner.height)
+    }
+
+    /// Get the size of the video as `(width, height)` as shown: stretched to the pixel aspect ratio
+    /// (see [`Video::set_pixel_aspect_ratio`]), then rotated by the stream's orientation tag
+    /// (e.g., swapped for a portrait phone recording stored sideways).
+    pub fn display_size(&self) -> (i32, i32) {
+        self.read().display_size()
+    }
+
+    /// Set whether decoded frames are hashed, for [`Video::frame_hash`] and to skip uploading and redrawing
+    /// frames identical to the last one (e.g., of slide decks or screen shares).
+    ///
+    /// Off by default, as it reads every byte of every frame on the streaming thread, which adds up for
+    /// high resolutions and framerates. [`VideoPlayer::on_frame_hash`](crate::VideoPlayer::on_frame_hash)
+    /// turns it on. Frames flagged as gaps by GStreamer are skipped either way.
+    pub fn set_frame_hashing(&mut self, frame_hashing: bool) {
+        self.get_mut()
+            .frame_hashing
+            .store(frame_hashing, Ordering::SeqCst);
+    }
+
+    /// Get whether decoded frames are hashed, see [`Video::set_frame_hashing`].
+    pub fn frame_hashing(&self) -> bool {
+        self.read().frame_hashing.load(Ordering::SeqCst)
+    }
+
+    /// Get the hash of the latest decoded frame's content (all of its planes), `None` until the first frame,
+    /// unless frames are hashed (see [`Video::set_frame_hashing`]), and for frames left in DMA-BUFs
+    /// (see [`VideoBuilder::dmabuf`](crate::VideoBuilder::dmabuf)).
+    ///
+    /// Equal frames hash the same, across runs and platforms too, so it serves to find duplicate frames
+    /// or to compare frames against recorded (golden) hashes in tests. It isn't a cryptographic hash.
+    pub fn frame_hash(&self) -> Option<u64> {
+        self.read().frame_hash.lock().ok().and_then(|hash| *hash)
+    }
+
+    /// Get the metadata attached to the latest decoded frame with [`attach_frame_metadata`](crate::attach_frame_metadata),
+    /// if it has any.
+    pub fn frame_metadata(&self) -> Option<FrameMetadata> {
+        self.read()
+            .frame_metadata
+            .lock()
+            .ok()
+            .and_then(|metadata| metadata.clone())
+    }
+
+    /// Set whether to measure the latency of frames, see [`Video::latency`].
+    ///
+    /// Meant for tuning live sources, e.g. with [`VideoBuilder::low_latency`](crate::VideoBuilder::low_latency).
+    pub fn set_measure_latency(&mut self, measure_latency: bool) {
+        let mut inner = self.get_mut();
+        inner
+            .measure_latency
+            .store(measure_latency, Ordering::SeqCst);
+        if !measure_latency {
+            inner.latency = None;
+            inner.presenting_capture = None;
+            if let Ok(mut capture_time) = inner.capture_time.lock() {
+                *capture_time = None;
+            }
+        }
+    }
+
+    /// Get the capture-to-display latency of frames, averaged over recent ones,
+    /// if it is being measured with [`Video::set_measure_latency`].
+    ///
+    /// While measuring, live sources (e.g., `v4l2src` or `pipewiresrc` capturing a camera) stamp every frame
+    /// with the wall-clock time as they capture it, and the time from there until the frame is on screen is
+    /// measured. Delays before the source gets a frame (e.g., in the camera itself) aren't seen.
+    /// RTSP streams carry the time the camera captured each frame (with GStreamer 1.22 and later), which
+    /// covers the camera and the network too, but is only right when both clocks are synced (e.g., with NTP).
+    /// Files and other non-live sources aren't measured.
+    ///
+    /// iced doesn't report when frames are presented, so a frame counts as shown at the next redraw,
+    /// which makes this up to one vsync interval too high.
+    pub fn latency(&self) -> Option<Duration> {
+        self.read().latency
+    }
+
+    /// Get the format frames are handed to the widget in, as negotiated,
+    /// see [`VideoBuilder::pixel_formats`](crate::VideoBuilder::pixel_formats).
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.read().format
+    }
+
+    /// Get the framerate of the video as frames per second.
+    pub fn framerate(&self) -> f64 {
+        self.read().framerate
+    }
+
+    /// Get if the video is HDR (HDR10 or HLG), and so gets tone-mapped when drawn,
+    /// see [`VideoPlayer::tone_mapping`](crate::VideoPlayer::tone_mapping).
+    pub fn is_hdr(&self) -> bool {
+        self.read().transfer != Transfer::Sdr
+    }
+
+    /// Overrides the pixel aspect ratio (width over height of a pixel, e.g. `64.0 / 45.0` for a widescreen PAL DVD)
+    /// of frames, for media declaring the wrong one. `None` (the default) follows the stream.
+    pub fn set_pixel_aspect_ratio(&mut self, pixel_aspect: Option<f64>) {
+        self.get_mut().pixel_aspect_override =
+            pixel_aspect.filter(|par| par.is_finite() && *par > 0.0);
+    }
+
+    /// Get the pixel aspect ratio frames are shown with, see [`Video::set_pixel_aspect_ratio`].
+    pub fn pixel_aspect_ratio(&self) -> f64 {
+        self.read().pixel_aspect()
+    }
+
+    /// Set the brightness, contrast, saturation and hue adjustments applied when drawing frames.
+    ///
+    /// They are applied on the GPU, so they cost nothing, but don't affect [`Video::current_frame`]
+    /// and other CPU-side frames.
+    pub fn set_color_balance(&mut self, balance: ColorBalance) {
+        self.get_mut().color_balance = balance.clamped();
+    }
+
+    /// Get the color adjustments applied when drawing frames, see [`Video::set_color_balance`].
+    pub fn color_balance(&self) -> ColorBalance {
+        self.read().color_balance
+    }
+
+    /// Set a 3D lookup table (e.g., a colorist's grading LUT) to preview frames through, or `None` to stop grading.
+    ///
+    /// Like [`Video::set_color_balance`], it is applied on the GPU when drawing, after the color balance.
+    pub fn set_lut(&mut self, lut: Option<Lut>) {
+        self.get_mut().lut = lut;
+    }
+
+    /// Get the lookup table frames are graded with, see [`Video::set_lut`].
+    pub fn lut(&self) -> Option<Lut> {
+        self.read().lut.clone()
+    }
+
+    /// Picks the display refresh rate out of `rates` that shows the video without judder,
+    /// i.e., the highest one that is a whole multiple of the video's framerate.
+    ///
+    /// Returns `None` if none of the rates match, in which case some frames will be shown longer than others.
+    pub fn matching_refresh_rate(&self, rates: &[f64]) -> Option<f64> {
+        let framerate = self.framerate();
+        rates
+            .iter()
+            .copied()
+            .filter(|&rate| {
+                let multiple = (rate / framerate).round();
+                // 0.2% still drifts by only one frame every ~20 seconds at 24fps
+                multiple >= 1.0 && (rate - multiple * framerate).abs() / rate < 0.002
+            })
+            .max_by(f64::total_cmp)
+    }
+
+    /// Get the name of the audio sink in use (e.g., `"pulsesink"`), if any.
+    pub fn audio_sink(&self) -> Option<String> {
+        let inner = self.read();
+        if !inner.source.has_property("audio-sink", None) {
+            return None;
+        }
+        let sink = inner
+            .source
+            .property::<Option<gst::Element>>("audio-sink")?;
+        // report the device sink that auto-pluggers like `autoaudiosink` settled on
+        let sink = sink
+            .downcast_ref::<gst::Bin>()
+            .and_then(|bin| bin.children().into_iter().next())
+            .unwrap_or(sink);
+        sink.factory().map(|factory| factory.name().to_string())
+    }
+
+    /// Set the volume multiplier of the audio.
+    /// `0.0` = 0% volume, `1.0` = 100% volume.
+    ///
+    /// This uses a linear scale, for example `0.5` is perceived as half as loud.
+    /// Values are clamped to the `0.0..=10.0` range `playbin` accepts.
+    pub fn set_volume(&mut self, volume: f64) {
+        // out of range values would make setting the property panic
+        let volume = if volume.is_nan() {
+            0.0
+        } else {
+            volume.clamp(0.0, 10.0)
+        };
+        let mut inner = self.get_mut();
+        if let Some(ref mut ducking) = inner.ducking {
+            ducking.volume = volume;
+        }
+        if inner.scrub_volume.is_some() {
+            // applied once the audio fades back in
+            inner.scrub_volume = Some(volume);
+        } else {
+            inner.set_volume(volume);
+        }
+    }
+
+    /// Get the volume multiplier of the audio, as set with [`Video::set_volume`] (i.e., leaving ducking aside).
+    pub fn volume(&self) -> f64 {
+        let inner = self.read();
+        inner.scrub_volume.unwrap_or_else(|| inner.volume())
+    }
+
+    /// Lowers the audio to `volume_fraction` (`0.0..=1.0`) of its volume, ramping down over `fade`,
+    /// e.g. while text-to-speech or a notification sound plays. Undo it with [`Video::unduck`].
+    ///
+    /// [`Video::set_volume`] keeps working while ducked, the fraction applying to the new volume.
+    /// The ramp advances while a [`VideoPlayer`](crate::VideoPlayer) shows the video.
+    pub fn duck(&mut self, volume_fraction: f64, fade: Duration) {
+        let to = if volume_fraction.is_nan() {
+            0.0
+        } else {
+            volume_fraction.clamp(0.0, 1.0)
+        };
+        let mut inner = self.get_mut();
+        inner.focus_held = None;
+        inner.ramp_ducking(to, fade);
+    }
+
+    /// Brings the audio back up to its volume after [`Video::duck`], ramping up over the same fade.
+    pub fn unduck(&mut self) {
+        let mut inner = self.get_mut();
+        let Some(ducking) = inner.ducking else {
+            return;
+        };
+        inner.focus_held = None;
+        inner.ramp_ducking(1.0, ducking.fade);
+    }
+
+    /// Get if the audio is ducked (or ramping back up), see [`Video::duck`].
+    pub fn is_ducked(&self) -> bool {
+        self.read().ducking.is_some()
+    }
+
+    /// Sets if audio is muted while scrubbing (seeking in quick succession),
+    /// fading back in once playback settles, instead of stuttering out bits of audio at every seek.
+    pub fn set_mute_on_scrub(&mut self, mute_on_scrub: bool) {
+        self.get_mut().mute_on_scrub = mute_on_scrub;
+    }
+
+    /// Get if audio is muted while scrubbing, see [`Video::set_mute_on_scrub`].
+    pub fn mute_on_scrub(&self) -> bool {
+        self.read().mute_on_scrub
+    }
+
+    /// Set if the audio is muted or not, without changing the volume.
+    ///
+    /// Obscured content stays muted until unlocked, see [`ContentGate::obscure`].
+    pub fn set_muted(&mut self, muted: bool) {
+        let mut inner = self.get_mut();
+        if inner.content_obscured() {
+            inner.gate_muted = muted;
+        } else {
+            inner.source.set_property("mute", muted);
+        }
+    }
+
+    /// Get if the audio is muted or not.
+    pub fn muted(&self) -> bool {
+        let inner = self.read();
+        if inner.content_obscured() {
+            inner.gate_muted
+        } else {
+            inner.source.property("mute")
+        }
+    }
+
+    /// Set which playback features of the `playbin` pipeline are enabled.
+    pub fn set_features(&mut self, features: Features) {
+        let inner = self.get_mut();
+        set_play_flags(
+            &inner.source,
+            features.apply_bits(play_flags(&inner.source)),
+        );
+    }
+
+    /// Get which playback features of the `playbin` pipeline are enabled.
+    pub fn features(&self) -> Features {
+        Features::from_bits(play_flags(&self.read().source))
+    }
+
+    /// Set the visualization plugin (e.g., `"goom"` or `"wavescope"`) to render in place of video for audio-only media.
+    /// Passing `None` disables visualizations.
+    pub fn set_visualization(&mut self, name: Option<&str>) -> Result<(), Error> {
+        let inner = self.get_mut();
+        let mut bits = play_flags(&inner.source);
+        if let Some(name) = name {
+            let vis = gst::ElementFactory::make(name).build()?;
+            inner.source.set_property("vis-plugin", &vis);
+            bits |= play_flag("vis");
+        } else {
+            bits &= !play_flag("vis");
+        }
+        set_play_flags(&inner.source, bits);
+        Ok(())
+    }
+
+    /// Get the name of the visualization plugin, if visualizations are enabled and one has been chosen.
+    pub fn visualization(&self) -> Option<String> {
+        let inner = self.read();
+        if play_flags(&inner.source) & play_flag("vis") == 0 {
+            return None;
+        }
+        inner
+            .source
+            .property::<Option<gst::Element>>("vis-plugin")
+            .and_then(|vis| vis.factory())
+            .map(|factory| factory.name().to_string())
+    }
+
+    /// Set a secondary audio track to play along with the video (e.g., a commentary track or a dub),
+    /// kept in sync with the main playback position. Passing `None` removes it.
+    pub fn set_secondary_audio(
+        &mut self,
+        uri: Option<&url::Url>,
+        mix: AudioMix,
+    ) -> Result<(), Error> {
+        let paused = self.paused();
+        let position = self.position();
+        let mut inner = self.get_mut();
+
+        if let Some((audio, mix)) = inner.secondary_audio.take() {
+            audio.set_state(gst::State::Null)?;
+            if mix == AudioMix::Replace {
+                set_play_flags(
+                    &inner.source,
+                    play_flags(&inner.source) | play_flag("audio"),
+                );
+            }
+        }
+
+        let Some(uri) = uri else {
+            return Ok(());
+        };
+
+        let audio = gst::ElementFactory::make("playbin")
+            .property("uri", uri.as_str())
+            .build()?
+            .downcast::<gst::Pipeline>()
+            .map_err(|_| Error::Cast)?;
+        set_play_flags(
+            &audio,
+            Features {
+                video: false,
+                text: false,
+                vis: false,
+                ..Features::default()
+            }
+            .apply_bits(play_flags(&audio)),
+        );
+        // run off the same clock so the tracks don't drift apart
+        if let Some(clock) = inner.source.clock() {
+            audio.use_clock(Some(&clock));
+        }
+        audio.set_state(gst::State::Paused)?;
+        audio.state(gst::ClockTime::from_seconds(5)).0?;
+
+        if mix == AudioMix::Replace {
+            set_play_flags(
+                &inner.source,
+                play_flags(&inner.source) & !play_flag("audio"),
+            );
+        }
+
+        inner.secondary_audio = Some((audio, mix));
+        inner.seek_secondary_audio(position);
+        inner.set_paused(paused);
+        Ok(())
+    }
+
+    /// Get the latest levels of every audio track, in track order, if enabled with
+    /// [`VideoBuilder::meter_all_audio_tracks`]. Empty otherwise, or until the tracks are decoded.
+    pub fn track_levels(&self) -> Vec<TrackLevel> {
+        self.read()
+            .track_meters
+            .as_ref()
+            .map(TrackMeters::levels)
+            .unwrap_or_default()
+    }
+
+    /// List the quality variants of an adaptive stream (HLS or DASH), by bitrate, as its manifest declares them.
+    ///
+    /// Empty for other media, and until the manifest is read.
+    pub fn variants(&self) -> Vec<Variant> {
+        self.read()
+            .quality
+            .demuxers()
+            .lock()
+            .map(|demuxers| demuxers.variants.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the index of the variant playing in [`Video::variants`], once the stream tells.
+    pub fn variant(&self) -> Option<usize> {
+        self.read().variant
+    }
+
+    /// Holds an adaptive stream to the variant at `index` in [`Video::variants`] (e.g., from a quality picker),
+    /// overriding the [`QualityPolicy`](crate::QualityPolicy) until [`Video::set_auto_quality`] is enabled again.
+    pub fn set_variant(&mut self, index: usize) -> Result<(), Error> {
+        let demuxers = self.read().quality.demuxers();
+        let Ok(mut demuxers) = demuxers.lock() else {
+            return Err(Error::Variant(index));
+        };
+        let variant = demuxers
+            .variants
+            .get(index)
+            .copied()
+            .ok_or(Error::Variant(index))?;
+        demuxers.pin(Some(variant.bitrate));
+        Ok(())
+    }
+
+    /// Sets if an adaptive stream switches variants by itself (the default), within the
+    /// [`QualityPolicy`](crate::QualityPolicy), or stays on the variant playing.
+    ///
+    /// Before the variant playing is known (see [`Video::variant`]), the stream is held to the first one
+    /// it reports.
+    pub fn set_auto_quality(&mut self, auto_quality: bool) {
+        let inner = self.read();
+        if let Ok(mut demuxers) = inner.quality.demuxers().lock() {
+            demuxers.hold(!auto_quality, inner.variant);
+        }
+    }
+
+    /// Get if an adaptive stream switches variants by itself, see [`Video::set_auto_quality`].
+    pub fn auto_quality(&self) -> bool {
+        self.read()
+            .quality
+            .demuxers()
+            .lock()
+            .map(|demuxers| demuxers.pinned().is_none() && !demuxers.held())
+            .unwrap_or(true)
+    }
+
+    /// Set the sink receiving playback lifecycle events (load time, rebuffering, bitrate switches, errors,
+    /// watch time) for quality-of-experience telemetry, or `None` to stop reporting.
+    ///
+    /// The sink hears of the load time right away. Replacing or dropping it reports the watch time so far.
+    pub fn set_analytics_sink(&mut self, sink: Option<Box<dyn AnalyticsSink>>) {
+        let mut inner = self.get_mut();
+        let playing = !inner.paused() && !inner.is_eos;
+        let load_time = inner.load_time;
+        inner.analytics = sink.map(|sink| Analytics::new(sink, load_time));
+        if let Some(ref mut analytics) = inner.analytics {
+            analytics.update(playing);
+        }
+    }
+
+    /// Set the volume multiplier of the secondary audio track, independent of the main volume.
+    ///
+    /// Values are clamped to the `0.0..=10.0` range, like [`Video::set_volume`].
+    pub fn set_secondary_volume(&mut self, volume: f64) {
+        // out of range values would make setting the property panic
+        let volume = if volume.is_nan() {
+            0.0
+        } else {
+            volume.clamp(0.0, 10.0)
+        };
+        if let Some((ref audio, _)) = self.get_mut().secondary_audio {
+            audio.set_property("volume", volume);
+        }
+    }
+
+    /// Get the volume multiplier of the secondary audio track, if there is one.
+    pub fn secondary_volume(&self) -> Option<f64> {
+        self.read()
+            .secondary_audio
+            .as_ref()
+            .map(|(audio, _)| audio.property("volume"))
+    }
+
+    /// Set the vocal removal settings, or disable vocal removal with `None`.
+    ///
+    /// Requires the video to be built with [`VideoBuilder::karaoke`]. Values outside the ranges
+    /// [`Karaoke`] documents are clamped.
+    pub fn set_karaoke(&mut self, karaoke: Option<Karaoke>) -> Result<(), Error> {
+        let filter = self.karaoke_filter()?;
+        let karaoke = karaoke.unwrap_or(Karaoke {
+            level: 0.0,
+            mono_level: 0.0,
+            ..Karaoke::default()
+        });
+        // out of range values would make setting the properties panic (and again on every rebuild)
+        let clamp = |value: f32, max: f32| {
+            if value.is_nan() {
+                0.0
+            } else {
+                value.clamp(0.0, max)
+            }
+        };
+        let karaoke = Karaoke {
+            level: clamp(karaoke.level, 1.0),
+            mono_level: clamp(karaoke.mono_level, 1.0),
+            filter_band: clamp(karaoke.filter_band, 441.0),
+            filter_width: clamp(karaoke.filter_width, 100.0),
+        };
+        filter.set_property("level", karaoke.level);
+        filter.set_property("mono-level", karaoke.mono_level);
+        filter.set_property("filter-band", karaoke.filter_band);
+        filter.set_property("filter-width", karaoke.filter_width);
+        Ok(())
+    }
+
+    /// Get the vocal removal settings, or `None` if vocal removal is disabled.
+    pub fn karaoke(&self) -> Option<Karaoke> {
+        let filter = self.karaoke_filter().ok()?;
+        let karaoke = Karaoke {
+            level: filter.property("level"),
+            mono_level: filter.property("mono-level"),
+            filter_band: filter.property("filter-band"),
+            filter_width: filter.property("filter-width"),
+        };
+        (karaoke.level > 0.0 || karaoke.mono_level > 0.0).then_some(karaoke)
+    }
+
+    fn karaoke_filter(&self) -> Result<gst::Element, Error> {
+        let inner = self.read();
+        if !inner.source.has_property("audio-filter", None) {
+            return Err(Error::Element("audiokaraoke".into()));
+        }
+        let is_karaoke = |element: &gst::Element| {
+            element
+                .factory()
+                .is_some_and(|factory| factory.name() == "audiokaraoke")
+        };
+        let filter = inner
+            .source
+            .property::<Option<gst::Element>>("audio-filter")
+            .ok_or_else(|| Error::Element("audiokaraoke".into()))?;
+        if is_karaoke(&filter) {
+            return Ok(filter);
+        }
+        // chained with other filters, e.g. for pitch correction
+        filter
+            .downcast_ref::<gst::Bin>()
+            .and_then(|bin| {
+                bin.iterate_elements()
+                    .into_iter()
+                    .flatten()
+                    .find(is_karaoke)
+            })
+            .ok_or_else(|| Error::Element("audiokaraoke".into()))
+    }
+
+    /// Get if the stream ended or not.
+    pub fn eos(&self) -> bool {
+        self.read().is_eos
+    }
+
+    /// Get if the media will loop or not.
+    pub fn looping(&self) -> bool {
+        self.read().looping
+    }
+
+    /// Set if the media will loop or not.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.get_mut().looping = looping;
+    }
+
+    /// Set if the media is paused or not.
+    pub fn set_paused(&mut self, paused: bool) {
+        let mut inner = self.get_mut();
+        if !paused {
+            // playing on purpose overrides a reduced motion preference
+            inner.motion_allowed = true;
+            inner.motion_poster = false;
+        }
+        // pausing or playing on purpose isn't undone when the window is focused again
+        if inner.focus_held == Some(FocusPolicy::Pause) {
+            inner.focus_held = None;
+        }
+        if inner.buffering.is_some() {
+            // taken up once the buffer is full
+            inner.buffering_resume = !paused;
+            return;
+        }
+        inner.set_paused(paused)
+    }
+
+    /// Get how full the network buffer is (`0..100`) while playback waits for it to refill, `None` otherwise.
+    ///
+    /// Playback is paused meanwhile, and resumes by itself unless [`Video::set_paused`] pauses it.
+    pub fn buffering(&self) -> Option<u8> {
+        self.read()
+            .buffering
+            .map(|percent| percent.clamp(0, 100) as u8)
+    }
+
+    /// Get if the media is paused or not.
+    pub fn paused(&self) -> bool {
+        self.read().paused()
+    }
+
+    /// Jumps to a specific position in the media, as precisely as `mode` asks for.
+    /// Passing `true` (or [`SeekMode::Accurate`]) will result in more accurate seeking,
+    /// however, it is also slower. For most seeks (e.g., scrubbing) this is not needed.
+    pub fn seek(
+        &mut self,
+        position: impl Into<Position>,
+        mode: impl Into<SeekMode>,
+    ) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.debounce_scrub();
+        inner.seek(position, mode)
+    }
+
+    /// Jumps to a position like [`Video::seek`], but coalesces seeks that come in faster than the pipeline
+    /// can handle them (e.g., while dragging a slider): only the latest runs once the previous seek finished,
+    /// so the pipeline never stalls on a backlog of seeks. Seeks are accurate.
+    ///
+    /// [`VideoPlayer::on_seek_settled`](crate::VideoPlayer::on_seek_settled) reports where playback ended up.
+    pub fn seek_smooth(&mut self, position: impl Into<Position>) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.debounce_scrub();
+        inner.seek_smooth(position.into())
+    }
+
+    /// Pauses the media and advances it by exactly one frame, e.g., for frame-accurate review.
+    pub fn step_forward(&mut self) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.set_paused(true);
+        // stepping the whole pipeline would step the audio sink by a buffer too
+        let sink = if inner.source.has_property("video-sink", None) {
+            inner.source.property::<Option<gst::Element>>("video-sink")
+        } else {
+            None
+        };
+        let sink = sink.unwrap_or_else(|| inner.source.clone().upcast());
+        let step = gst::event::Step::new(gst::format::Buffers::from_u64(1), 1.0, true, false);
+        if !sink.send_event(step) {
+            return Err(Error::Step);
+        }
+        Ok(())
+    }
+
+    /// Pauses the media and moves it back by exactly one frame.
+    ///
+    /// GStreamer can only step forward, so this is an accurate seek to the previous frame
+    /// and takes as long as one.
+    pub fn step_backward(&mut self) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.set_paused(true);
+        let frame = Duration::from_nanos(inner.frame_pts.load(Ordering::SeqCst));
+        let previous = frame
+            .saturating_sub(Duration::try_from_secs_f64(1.0 / inner.framerate).unwrap_or_default());
+        inner.seek(previous, true)
+    }
+
+    /// Set the playback speed of the media.
+    /// The default speed is `1.0`.
+    ///
+    /// Audio pitch changes with the speed, unless the video was built with [`VideoBuilder::preserve_pitch`].
+    pub fn set_speed(&mut self, speed: f64) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.speed_ramp = None;
+        inner.set_speed(speed)
+    }
+
+    /// Changes the playback speed gradually over `duration` (e.g., 1.0 to 2.0 over 300ms) instead of at once,
+    /// through small instant rate changes, so speed toggles feel smooth and the audio doesn't pop.
+    ///
+    /// Jumps to `speed` like [`Video::set_speed`] when playing backwards or if the pipeline doesn't support instant rate changes.
+    /// The ramp advances while a [`VideoPlayer`](crate::VideoPlayer) shows the video.
+    pub fn ramp_speed(&mut self, speed: f64, duration: Duration) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.speed_ramp = None;
+        if duration.is_zero() || speed <= 0.0 || inner.speed <= 0.0 {
+            return inner.set_speed(speed);
+        }
+        let now = Instant::now();
+        inner.speed_ramp = Some(SpeedRamp {
+            from: inner.speed,
+            to: speed,
+            start: now,
+            duration,
+            stepped: now,
+        });
+        Ok(())
+    }
+
+    /// Get if the playback speed is ramping, see [`Video::ramp_speed`].
+    pub fn is_ramping_speed(&self) -> bool {
+        self.read().speed_ramp.is_some()
+    }
+
+    /// Get the current playback speed.
+    pub fn speed(&self) -> f64 {
+        self.read().speed
+    }
+
+    /// Get the current playback position in time.
+    pub fn position(&self) -> Duration {
+        self.read().position()
+    }
+
+    /// Get the playback position extrapolated from the last position the pipeline reported,
+    /// using the time since and the playback speed.
+    ///
+    /// The pipeline's position often only moves once per frame; this one moves on every call,
+    /// so seek bars animate smoothly at the display's refresh rate.
+    pub fn interpolated_position(&self) -> Duration {
+        let inner = self.read();
+        let position = inner.position();
+        let now = Instant::now();
+        let Ok(mut sample) = inner.position_sample.lock() else {
+            return position;
+        };
+        let (last, since) = match *sample {
+            Some((last, since)) if last == position && !inner.paused() && !inner.is_eos => {
+                (last, since)
+            }
+            _ => {
+                *sample = Some((position, now));
+                return position;
+            }
+        };
+
+        // never run further ahead than a few frames, in case playback stalls (e.g., buffering)
+        let limit = Duration::try_from_secs_f64(4.0 / inner.framerate).unwrap_or_default();
+        let elapsed = now.duration_since(since).min(limit).as_secs_f64() * inner.speed.abs();
+        let elapsed = Duration::from_secs_f64(elapsed);
+        if inner.speed < 0.0 {
+            last.saturating_sub(elapsed)
+        } else {
+            (last + elapsed).min(inner.duration.max(last))
+        }
+    }
+
+    /// Get the playback position of what is actually audible, as reported by the audio sink.
+    ///
+    /// More precise than [`Video::position`] for syncing visualizers or rhythm games to the audio.
+    /// Returns `None` if there is no audio sink or it can't report a position yet.
+    pub fn audio_clock_position(&self) -> Option<AudioClockPosition> {
+        let inner = self.read();
+        if !inner.source.has_property("audio-sink", None) {
+            return None;
+        }
+        let sink = inner
+            .source
+            .property::<Option<gst::Element>>("audio-sink")?;
+        let position = Duration::from_nanos(sink.query_position::<gst::ClockTime>()?.nseconds());
+        let frame = Duration::from_nanos(inner.frame_pts.load(Ordering::SeqCst));
+        Some(AudioClockPosition {
+            position,
+            drift: position.as_secs_f64() - frame.as_secs_f64(),
+        })
+    }
+
+    /// Get the media duration.
+    pub fn duration(&self) -> Duration {
+        self.read().duration
+    }
+
+    /// Get if the media can be seeked in; live streams (e.g., cameras) usually can't.
+    pub fn is_seekable(&self) -> bool {
+        let mut query = gst::query::Seeking::new(gst::Format::Time);
+        self.read().source.query(&mut query) && query.result().0
+    }
+
+    /// Get if the media is live, i.e. it plays as it is captured (e.g., a camera or TV broadcast).
+    pub fn is_live(&self) -> bool {
+        let mut query = gst::query::Latency::new();
+        self.read().source.query(&mut query) && query.result().0
+    }
+
+    /// Set segments of the media, as `(start, end, mode)`, to skip during playback (e.g., sponsored sections or intros).
+    pub fn set_skip_segments(&mut self, segments: &[(Duration, Duration, SkipMode)]) {
+        let mut inner = self.get_mut();
+        inner.skip_segments = segments.to_vec();
+        inner.skip_segment = None;
+    }
+
+    /// Get the segments to skip during playback.
+    pub fn skip_segments(&self) -> Vec<(Duration, Duration, SkipMode)> {
+        self.read().skip_segments.clone()
+    }
+
+    /// Skips to the end of the skip segment playback is currently in, if any.
+    /// Returns whether a segment was skipped.
+    pub fn skip_segment(&mut self) -> Result<bool, Error> {
+        let inner = self.get_mut();
+        let position = inner.position();
+        let segment = inner
+            .skip_segments
+            .iter()
+            .find(|&&(start, end, _)| position >= start && position < end);
+        match segment {
+            Some(&(_, end, _)) => {
+                inner.seek(end, true)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Set a rate map of `(start, end, speed)` segments, played at their own speed (e.g., `2.0` through silences).
+    ///
+    /// Outside of the segments, media plays at the speed it had before entering one.
+    /// Speeds are changed without flushing where supported, so transitions are seamless.
+    /// Only forward (positive) speeds are supported.
+    pub fn set_rate_map(&mut self, segments: &[(Duration, Duration, f64)]) {
+        let mut inner = self.get_mut();
+        if inner.rate_segment.take().is_some() {
+            let speed = inner.base_speed;
+            if let Err(err) = inner.set_speed_instant(speed) {
+                log::error!("cannot restore speed {speed}: {err}");
+            }
+        }
+        inner.rate_map = segments
+            .iter()
+            .copied()
+            .filter(|&(_, _, speed)| speed > 0.0)
+            .collect();
+    }
+
+    /// Get the rate map segments.
+    pub fn rate_map(&self) -> Vec<(Duration, Duration, f64)> {
+        self.read().rate_map.clone()
+    }
+
+    /// Trims `start` off the beginning and `end` off the end of the media (e.g., to skip an intro and credits).
+    ///
+    /// Playback, seeking and looping then stay within the trimmed range, and the end of stream is reached
+    /// `end` early. Pass zero durations to remove the trim. The trim only holds for this media;
+    /// queue the next one with [`Video::set_next_uri_trimmed`] to trim it too.
+    pub fn set_trim(&mut self, start: Duration, end: Duration) -> Result<(), Error> {
+        let position = self.position();
+        let mut inner = self.get_mut();
+        inner.trim = (start, end);
+        inner.seek(position, true)
+    }
+
+    /// Loops playback between `start` and `end`, e.g., to practice a passage of music or a sentence.
+    ///
+    /// Uses segment seeks, so jumping back to `start` doesn't flush the pipeline and shows no hiccup.
+    /// Only forward playback loops. Playback moves into the region when it's set; seeking before its end
+    /// keeps looping it, seeking past its end leaves it. Passing `None` (or an empty region) stops looping the region.
+    pub fn set_loop_region(&mut self, region: Option<(Duration, Duration)>) -> Result<(), Error> {
+        let position = self.position();
+        let mut inner = self.get_mut();
+        inner.loop_region = region.filter(|(start, end)| start < end);
+        inner.enter_loop_region(position)
+    }
+
+    /// Get the region playback loops in, see [`Video::set_loop_region`].
+    pub fn loop_region(&self) -> Option<(Duration, Duration)> {
+        self.read().loop_region
+    }
+
+    /// Get the durations trimmed off the beginning and end of the media, see [`Video::set_trim`].
+    pub fn trim(&self) -> (Duration, Duration) {
+        self.read().trim
+    }
+
+    /// Sets a [`UrlRefresher`](crate::UrlRefresher) providing fresh URLs for a stream whose URL expires.
+    ///
+    /// It is called when the server refuses the URL (HTTP 401 or 403) and, if given, every `ttl`;
+    /// playback then reconnects to the fresh URL from the same position.
+    pub fn set_url_refresher(
+        &mut self,
+        refresher: impl crate::UrlRefresher + 'static,
+        ttl: Option<Duration>,
+    ) {
+        self.get_mut().url_refresher = Some(Refresher::new(Arc::new(refresher), ttl));
+    }
+
+    /// Removes the [`UrlRefresher`](crate::UrlRefresher) set with [`Video::set_url_refresher`].
+    pub fn clear_url_refresher(&mut self) {
+        self.get_mut().url_refresher = None;
+    }
+
+    /// Sets the media to continue with once this one ends, switching seamlessly (without a gap).
+    ///
+    /// Pair with [`VideoPlayer::on_outro`](crate::VideoPlayer::on_outro) to show a countdown to it,
+    /// and pass `None` to cancel the advance. The next media should have the same resolution as the current one.
+    pub fn set_next_uri(&mut self, uri: Option<&url::Url>) {
+        if let Ok(mut next_uri) = self.get_mut().next_uri.lock() {
+            *next_uri = uri.map(|uri| (uri.clone(), (Duration::ZERO, Duration::ZERO)));
+        }
+    }
+
+    /// Sets the media to continue with once this one ends, like [`Video::set_next_uri`], trimmed like
+    /// [`Video::set_trim`] as soon as it starts (e.g., to skip the intro of every episode of a series).
+    pub fn set_next_uri_trimmed(&mut self, uri: &url::Url, start: Duration, end: Duration) {
+        if let Ok(mut next_uri) = self.get_mut().next_uri.lock() {
+            *next_uri = Some((uri.clone(), (start, end)));
+        }
+    }
+
+    /// Switches to another TV channel, keeping the play/pause state and the video's settings.
+    #[cfg(feature = "dvb")]
+    pub fn set_channel(&mut self, channel: &crate::dvb::Channel) -> Result<(), Error> {
+        let mut inner = self.get_mut();
+        inner.load_uri(&channel.uri())?;
+        // live channels have no duration, and trims are per media
+        inner.duration = Duration::ZERO;
+        inner.trim = (Duration::ZERO, Duration::ZERO);
+        Ok(())
+    }
+
+    /// Get the media queued to continue with once this one ends, see [`Video::set_next_uri`].
+    pub fn next_uri(&self) -> Option<url::Url> {
+        let next_uri = self.read().next_uri.lock().ok()?.clone();
+        next_uri.map(|(uri, _)| uri)
+    }
+
+    /// Restarts a stream; seeks to the first frame and unpauses, sets the `eos` flag to false.
+    pub fn restart_stream(&mut self) -> Result<(), Error> {
+        self.get_mut().restart_stream()
+    }
+
+    /// Tears down the pipeline and builds it again with the same options,
+    /// then seeks back to the frame that was playing.
+    ///
+    /// Use this to recover from hardware decoder crashes or GPU resets;
+    /// decoder ranks changed in the GStreamer registry in the meantime are picked up by the new pipeline.
+    /// Settings made on the video (e.g., looping, trim, volume, speed) carry over.
+    ///
+    /// Only works for videos created with [`Video::new`] or [`VideoBuilder`], not custom pipelines.
+    pub fn rebuild_preserving_position(&mut self) -> Result<(), Error> {
+        let builder = self.read().builder.clone().ok_or(Error::Uri)?;
+        self.rebuild_with(builder)
+    }
+
+    /// Like [`Video::rebuild_preserving_position`], but builds the new pipeline with different options.
+    /// The media playing now is kept, whatever URI `builder` was created with.
+    pub fn rebuild_with(&mut self, builder: VideoBuilder) -> Result<(), Error> {
+        let uri = {
+            let inner = self.read();
+            let property = if inner.source.has_property("current-uri", None) {
+                "current-uri"
+            } else {
+                "uri"
+            };
+            inner
+                .source
+                .property::<Option<String>>(property)
+                .and_then(|uri| url::Url::parse(&uri).ok())
+        };
+        let builder = match uri {
+            Some(uri) => builder.with_uri(uri),
+            None => builder,
+        };
 
-                    let mut frame = frame_ref.lock().map_err(|_| gst::FlowError::Error)?;
-                    let frame_len = frame.len();
-                    frame.copy_from_slice(&map.as_slice()[..frame_len]);
+        let position = self.position();
+        let paused = self.paused();
+        let mut video = builder.build()?;
 
-                    upload_frame_ref.swap(true, Ordering::SeqCst);
+        {
+            let mut old = self.get_mut();
+            let mut new = video.get_mut();
+            new.video_id = old.video_id;
+            new.looping = old.looping;
+            new.loop_region = old.loop_region;
+            new.mute_on_scrub = old.mute_on_scrub;
+            new.trim = old.trim;
+            new.skip_segments = old.skip_segments.clone();
+            new.rate_map = old.rate_map.clone();
+            new.base_speed = old.base_speed;
+            new.lyrics = old.lyrics.clone();
+            new.data_track = std::mem::take(&mut old.data_track);
+            new.data_interpolation = old.data_interpolation;
+            new.motion_allowed = old.motion_allowed;
+            new.motion_poster = old.motion_poster;
+            new.url_refresher = old.url_refresher.take();
+            // the content stays unlocked if it was, even though the builder locks it
+            new.content_gate = old.content_gate.clone();
+            new.capture_protected = old.capture_protected;
+            new.ducking = old.ducking;
+            new.bookmarks = std::mem::take(&mut old.bookmarks);
+            new.color_balance = old.color_balance;
+            new.lut = old.lut.clone();
+            new.pixel_aspect_override = old.pixel_aspect_override;
+            new.teletext_page
+                .store(old.teletext_page.load(Ordering::SeqCst), Ordering::SeqCst);
+            new.measure_latency
+                .store(old.measure_latency.load(Ordering::SeqCst), Ordering::SeqCst);
+            new.frame_hashing
+                .store(old.frame_hashing.load(Ordering::SeqCst), Ordering::SeqCst);
+            // a statement, so the lock guards are dropped before `new` and `old`
+            if let (Ok(mut next), Ok(mut old_next)) = (new.next_uri.lock(), old.next_uri.lock()) {
+                *next = old_next.take();
+            };
+        }
 
-                    if let Some(at) = clear_subtitles_at {
-                        if pts >= at {
-                            *subtitle_text_ref
-                                .lock()
-                                .map_err(|_| gst::FlowError::Error)? = None;
-                            upload_text_ref.store(true, Ordering::SeqCst);
-                            clear_subtitles_at = None;
-                        }
-                    }
+        if let Some(number) = self.program() {
+            video.select_program(number)?;
+        }
+        video.set_subtitle_offset(self.subtitle_offset());
+        video.set_volume(self.volume());
+        video.set_muted(self.muted());
+        video.set_audio_track(self.audio_track());
+        if self.video_track() > 0 {
+            video.set_video_track(self.video_track());
+        }
+        video.get_mut().main_audio_track = self.read().main_audio_track;
+        if let Some(karaoke) = self.karaoke() {
+            video.set_karaoke(Some(karaoke))?;
+        }
+        let secondary = self
+            .read()
+            .secondary_audio
+            .as_ref()
+            .and_then(|(audio, mix)| {
+                let uri = audio.property::<Option<String>>("uri")?;
+                Some((url::Url::parse(&uri).ok()?, *mix))
+            });
+        if let Some((uri, mix)) = secondary {
+            video.set_secondary_audio(Some(&uri), mix)?;
+        }
+        if let Some(volume) = self.secondary_volume() {
+            video.set_secondary_volume(volume);
+        }
+        if let Some(size) = self.output_size() {
+            video.set_output_size(Some(size));
+        }
 
-                    let text = text_sink
-                        .as_ref()
-                        .and_then(|sink| sink.try_pull_sample(gst::ClockTime::from_seconds(0)));
-                    if let Some(text) = text {
-                        let text = text.buffer().ok_or(gst::FlowError::Error)?;
-                        let pts = text.pts().unwrap_or_default();
-                        let duration = text.duration().unwrap_or(gst::ClockTime::ZERO);
-                        let map = text.map_readable().map_err(|_| gst::FlowError::Error)?;
+        // an accurate seek lands on the frame that was showing, not the keyframe before it
+        video.seek(position, true)?;
+        let speed = self.speed();
+        if speed != 1.0 {
+            video.set_speed(speed)?;
+        }
+        video.get_mut().set_paused(paused);
 
-                        let text = html_escape::decode_html_entities(
-                            std::str::from_utf8(map.as_slice())
-                                .map_err(|_| gst::FlowError::Error)?,
-                        )
-                        .to_string();
-                        *subtitle_text_ref
-                            .lock()
-                            .map_err(|_| gst::FlowError::Error)? = Some(text);
-                        upload_text_ref.store(true, Ordering::SeqCst);
+        *self = video;
+        Ok(())
+    }
 
-                        clear_subtitles_at = Some(pts + duration);
-                    }
+    /// Locks the content behind `gate` until [`Video::unlock_content`] is approved,
+    /// pausing it (or obscuring and muting it, see [`ContentGate::obscure`]).
+    ///
+    /// Prefer [`VideoBuilder::content_gate`], which keeps the content from ever starting.
+    /// Passing `None` lifts the gate without asking for approval.
+    pub fn set_content_gate(&mut self, gate: Option<ContentGate>) {
+        self.get_mut().set_content_gate(gate);
+    }
 
-                    Ok(())
-                })() {
-                    log::error!("error pulling frame");
-                }
-            }
-        });
+    /// Asks the [`ContentGate`] to unlock the content; returns whether it is unlocked now.
+    ///
+    /// Blocked content stays paused once unlocked, until [`Video::set_paused`] is called.
+    pub fn unlock_content(&mut self) -> bool {
+        let mut inner = self.get_mut();
+        let Some(gate) = inner.content_gate.clone() else {
+            return true;
+        };
+        let approved = (gate.unlock)(&gate.rating);
+        if approved {
+            inner.set_content_gate(None);
+        }
+        approved
+    }
 
-        Ok(Video(RwLock::new(Internal {
-            id,
+    /// Get if the content is locked behind a [`ContentGate`].
+    pub fn content_locked(&self) -> bool {
+        self.read().content_gate.is_some()
+    }
 
-            bus: pipeline.bus().unwrap(),
-            source: pipeline,
-            alive,
-            worker: Some(worker),
+    /// Sets if frames of the video must not be captured, for apps showing protected previews.
+    ///
+    /// While set, the frames never leave the video widget: [`Video::current_frame`] returns `None`,
+    /// [`Video::thumbnails`] fails and [`VideoTexture`](crate::VideoTexture) stops updating.
+    ///
+    /// This is best-effort: iced gives widgets no way to mark the window surface as secure,
+    /// so screenshots and screen recordings taken by the compositor (or through DRM planes) still
+    /// show the video. Apps that need that too have to protect the whole window with the platform's API
+    /// (e.g., `SetWindowDisplayAffinity` on Windows, `NSWindow.sharingType` on macOS);
+    /// Wayland has no protocol for it yet.
+    pub fn set_capture_protected(&mut self, capture_protected: bool) {
+        self.get_mut().capture_protected = capture_protected;
+    }
 
-            width,
-            height,
-            framerate,
-            duration,
-            speed: 1.0,
-            sync_av,
+    /// Get if frames of the video must not be captured, see [`Video::set_capture_protected`].
+    pub fn capture_protected(&self) -> bool {
+        self.read().capture_protected
+    }
 
-            frame,
-            upload_frame,
-            last_frame_time,
-            looping: false,
-            is_eos: false,
-            restart_stream: false,
-            sync_av_avg: 0,
-            sync_av_counter: 0,
+    /// Set the subtitle URL to display, see [`Video::set_external_subtitle`].
+    pub fn set_subtitle_url(&mut self, url: &url::Url) -> Result<(), Error> {
+        self.set_external_subtitle(url)
+    }
 
-            subtitle_text,
-            upload_text,
-        })))
+    /// Attaches an external subtitle file (e.g., SRT or VTT) to the loaded video,
+    /// with its text flowing through [`VideoPlayer::on_subtitle_text`](crate::VideoPlayer::on_subtitle_text).
+    ///
+    /// Playback picks up where it was; only the pipeline is restarted, not the whole video,
+    /// and the position is restored once a [`VideoPlayer`](crate::VideoPlayer) sees it's ready.
+    pub fn set_external_subtitle(&mut self, url: &url::Url) -> Result<(), Error> {
+        let paused = self.paused();
+        let position = self.position();
+        let mut inner = self.get_mut();
+        // playbin only reads suburi when (re)starting
+        inner.source.set_state(gst::State::Ready)?;
+        inner.source.set_property("suburi", url.as_str());
+        set_play_flags(&inner.source, play_flags(&inner.source) | play_flag("text"));
+        inner.set_paused(paused);
+        inner.restart_from(position);
+        Ok(())
     }
 
-    pub(crate) fn read(&self) -> impl Deref<Target = Internal> + '_ {
-        self.0.read().expect("lock")
+    /// Shifts subtitles by `offset_ms` milliseconds relative to the video, later if positive and earlier if negative.
+    ///
+    /// Applied live, so it can back a +/- delay control for out of sync (e.g., external) subtitles.
+    pub fn set_subtitle_offset(&mut self, offset_ms: i64) {
+        let mut inner = self.get_mut();
+        inner.subtitle_offset = offset_ms;
+        inner.apply_subtitle_offset();
     }
 
-    pub(crate) fn write(&self) -> impl DerefMut<Target = Internal> + '_ {
-        self.0.write().expect("lock")
+    /// Get the subtitle offset in milliseconds, see [`Video::set_subtitle_offset`].
+    pub fn subtitle_offset(&self) -> i64 {
+        self.read().subtitle_offset
     }
 
-    pub(crate) fn get_mut(&mut self) -> impl DerefMut<Target = Internal> + '_ {
-        self.0.get_mut().expect("lock")
+    /// Get the current subtitle URL.
+    pub fn subtitle_url(&self) -> Option<url::Url> {
+        url::Url::parse(&self.read().source.property::<String>("suburi")).ok()
     }
 
-    /// Get the size/resolution of the video as `(width, height)`.
-    pub fn size(&self) -> (i32, i32) {
-        (self.read().width, self.read().height)
+    /// Set the teletext page (100 to 899) to show subtitles from in DVB broadcasts.
+    ///
+    /// Teletext subtitles are emitted as text through
+    /// [`VideoPlayer::on_subtitle_text`](crate::VideoPlayer::on_subtitle_text).
+    /// Defaults to page 888. Needs the `teletextdec` GStreamer element.
+    pub fn set_teletext_page(&mut self, page: u16) {
+        let page = i32::from(page.clamp(100, 899));
+        let inner = self.get_mut();
+        inner.teletext_page.store(page, Ordering::SeqCst);
+        for element in inner.source.iterate_recurse().into_iter().flatten() {
+            if element
+                .factory()
+                .is_some_and(|factory| factory.name() == "teletextdec")
+            {
+                element.set_property("page", page);
+            }
+        }
     }
 
-    /// Get the framerate of the video as frames per second.
-    pub fn framerate(&self) -> f64 {
-        self.read().framerate
+    /// Get the teletext page subtitles are shown from.
+    pub fn teletext_page(&self) -> u16 {
+        self.read().teletext_page.load(Ordering::SeqCst) as u16
     }
 
-    /// Set the volume multiplier of the audio.
-    /// `0.0` = 0% volume, `1.0` = 100% volume.
+    /// List the chapters of the media, from its table of contents (e.g., MKV or MP4 chapter markers).
     ///
-    /// This uses a linear scale, for example `0.5` is perceived as half as loud.
-    pub fn set_volume(&mut self, volume: f64) {
-        self.get_mut().source.set_property("volume", volume);
-        self.set_muted(self.muted()); // for some reason gstreamer unmutes when changing volume?
+    /// Empty if the media has no chapters, or the table of contents hasn't been read yet.
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.read()
+            .chapters
+            .lock()
+            .map(|chapters| chapters.clone())
+            .unwrap_or_default()
     }
 
-    /// Get the volume multiplier of the audio.
-    pub fn volume(&self) -> f64 {
-        self.read().source.property("volume")
+    /// Get the index of the chapter at the playback position, see [`Video::chapters`].
+    pub fn chapter(&self) -> Option<usize> {
+        let inner = self.read();
+        let chapters = inner.chapters.lock().ok()?;
+        crate::chapter::chapter_at(&chapters, inner.position())
     }
 
-    /// Set if the audio is muted or not, without changing the volume.
-    pub fn set_muted(&mut self, muted: bool) {
-        self.get_mut().source.set_property("mute", muted);
+    /// Jumps to the start of the chapter at `index` in [`Video::chapters`].
+    pub fn seek_to_chapter(&mut self, index: usize) -> Result<(), Error> {
+        let start = self
+            .chapters()
+            .get(index)
+            .map(|chapter| chapter.start)
+            .ok_or(Error::Chapter(index))?;
+        self.seek(start, true)
     }
 
-    /// Get if the audio is muted or not.
-    pub fn muted(&self) -> bool {
-        self.read().source.property("mute")
+    /// Bookmarks the frame shown, with a thumbnail of it, returning the new bookmark.
+    pub fn add_bookmark(&mut self, label: impl Into<String>) -> Bookmark {
+        let thumbnail_png = {
+            let (width, _) = self.read().frame.latest().size();
+            let downscale = (width / Bookmark::THUMBNAIL_WIDTH).clamp(1, u8::MAX as u32);
+            self.current_frame_rgba(downscale)
+                .and_then(|(width, height, rgba)| snapshot::encode_png(width, height, rgba))
+                .unwrap_or_default()
+        };
+
+        let mut inner = self.get_mut();
+        let position = Duration::from_nanos(inner.frame_pts.load(Ordering::SeqCst));
+        let bookmark = Bookmark {
+            id: inner
+                .bookmarks
+                .iter()
+                .map(|bookmark| bookmark.id + 1)
+                .max()
+                .unwrap_or(0),
+            label: label.into(),
+            position,
+            frame: (position.as_secs_f64() * inner.framerate).round() as u64,
+            thumbnail_png,
+        };
+        let index = inner
+            .bookmarks
+            .partition_point(|bookmark| bookmark.position <= position);
+        inner.bookmarks.insert(index, bookmark.clone());
+        bookmark
     }
 
-    /// Get if the stream ended or not.
-    pub fn eos(&self) -> bool {
-        self.read().is_eos
+    /// List the bookmarks of the video, by position, see [`Video::add_bookmark`].
+    pub fn bookmarks(&self) -> Vec<Bookmark> {
+        self.read().bookmarks.clone()
     }
 
-    /// Get if the media will loop or not.
-    pub fn looping(&self) -> bool {
-        self.read().looping
+    /// Replaces the bookmarks of the video, e.g. with ones persisted in an earlier session.
+    pub fn set_bookmarks(&mut self, mut bookmarks: Vec<Bookmark>) {
+        bookmarks.sort_by_key(|bookmark| bookmark.position);
+        self.get_mut().bookmarks = bookmarks;
     }
 
-    /// Set if the media will loop or not.
-    pub fn set_looping(&mut self, looping: bool) {
-        self.get_mut().looping = looping;
+    /// Removes the bookmark `id`, returning it if there was one.
+    pub fn remove_bookmark(&mut self, id: u64) -> Option<Bookmark> {
+        let mut inner = self.get_mut();
+        let index = inner
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.id == id)?;
+        Some(inner.bookmarks.remove(index))
     }
 
-    /// Set if the media is paused or not.
-    pub fn set_paused(&mut self, paused: bool) {
-        self.get_mut().set_paused(paused)
+    /// Jumps exactly to the frame of the bookmark `id`.
+    pub fn seek_to_bookmark(&mut self, id: u64) -> Result<(), Error> {
+        let position = self
+            .read()
+            .bookmarks
+            .iter()
+            .find(|bookmark| bookmark.id == id)
+            .map(|bookmark| bookmark.position)
+            .ok_or(Error::Bookmark(id))?;
+        self.seek(position, SeekMode::Accurate)
     }
 
-    /// Get if the media is paused or not.
-    pub fn paused(&self) -> bool {
-        self.read().paused()
+    /// Get the trim, skip segments and bookmarks of the video, to exchange them with other tools.
+    pub fn edit_list(&self) -> EditList {
+        let inner = self.read();
+        EditList {
+            trim: inner.trim,
+            skip_segments: inner.skip_segments.clone(),
+            bookmarks: inner.bookmarks.clone(),
+        }
     }
 
-    /// Jumps to a specific position in the media.
-    /// Passing `true` to the `accurate` parameter will result in more accurate seeking,
-    /// however, it is also slower. For most seeks (e.g., scrubbing) this is not needed.
-    pub fn seek(&mut self, position: impl Into<Position>, accurate: bool) -> Result<(), Error> {
-        self.get_mut().seek(position, accurate)
+    /// Replaces the trim, skip segments and bookmarks of the video with those of `edit_list`.
+    pub fn set_edit_list(&mut self, edit_list: EditList) -> Result<(), Error> {
+        self.set_skip_segments(&edit_list.skip_segments);
+        self.set_bookmarks(edit_list.bookmarks);
+        self.set_trim(edit_list.trim.0, edit_list.trim.1)
     }
 
-    /// Set the playback speed of the media.
-    /// The default speed is `1.0`.
-    pub fn set_speed(&mut self, speed: f64) -> Result<(), Error> {
-        self.get_mut().set_speed(speed)
+    /// Set timed lyrics in the LRC format, emitted line by line through
+    /// [`VideoPlayer::on_lyric_line`](crate::VideoPlayer::on_lyric_line).
+    pub fn set_lyrics(&mut self, lrc: &str) {
+        let mut inner = self.get_mut();
+        inner.lyrics = crate::lyrics::parse_lrc(lrc);
+        inner.lyric_line = None;
     }
 
-    /// Get the current playback speed.
-    pub fn speed(&self) -> f64 {
-        self.read().speed
+    /// Load timed lyrics from an LRC file, see [`Video::set_lyrics`].
+    pub fn load_lyrics(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        let lrc = std::fs::read_to_string(path)?;
+        self.set_lyrics(&lrc);
+        Ok(())
     }
 
-    /// Get the current playback position in time.
-    pub fn position(&self) -> Duration {
-        Duration::from_nanos(
-            self.read()
-                .source
-                .query_position::<gst::ClockTime>()
-                .map_or(0, |pos| pos.nseconds()),
-        )
+    /// Remove the lyrics.
+    pub fn clear_lyrics(&mut self) {
+        self.set_lyrics("");
     }
 
-    /// Get the media duration.
-    pub fn duration(&self) -> Duration {
-        self.read().duration
+    /// Set a track of time-synced data (e.g., telemetry recorded alongside the video),
+    /// emitted for every displayed frame through [`VideoPlayer::on_data_sample`](crate::VideoPlayer::on_data_sample).
+    ///
+    /// Samples don't need to line up with frames; `interpolation` decides how frames between samples are matched.
+    pub fn set_data_track(
+        &mut self,
+        samples: impl IntoIterator<Item = DataSample>,
+        interpolation: DataInterpolation,
+    ) {
+        let mut inner = self.get_mut();
+        inner.data_track = samples.into_iter().collect();
+        inner.data_track.sort_by_key(|sample| sample.position);
+        inner.data_interpolation = interpolation;
+        inner.data_pts = None;
     }
 
-    /// Restarts a stream; seeks to the first frame and unpauses, sets the `eos` flag to false.
-    pub fn restart_stream(&mut self) -> Result<(), Error> {
-        self.get_mut().restart_stream()
+    /// Remove the data track.
+    pub fn clear_data_track(&mut self) {
+        self.get_mut().data_track.clear();
     }
 
-    /// Set the subtitle URL to display.
-    pub fn set_subtitle_url(&mut self, url: &url::Url) -> Result<(), Error> {
+    /// Get the data sample for the frame at `position`, see [`Video::set_data_track`].
+    pub fn data_sample_at(&self, position: Duration) -> Option<DataSample> {
+        let inner = self.read();
+        crate::data_track::sample_at(&inner.data_track, position, inner.data_interpolation)
+    }
+
+    /// List the programs (e.g., TV channels) of an MPEG-TS multi-program stream, with their tracks.
+    ///
+    /// Only local files can be inspected; other media, or media that isn't MPEG-TS, have no programs.
+    pub fn programs(&self) -> Result<Vec<crate::Program>, Error> {
+        let uri = {
+            let inner = self.read();
+            if !inner.source.has_property("current-uri", None) {
+                return Ok(Vec::new());
+            }
+            inner.source.property::<Option<String>>("current-uri")
+        };
+        let Some(path) = uri
+            .and_then(|uri| url::Url::parse(&uri).ok())
+            .and_then(|uri| uri.to_file_path().ok())
+        else {
+            return Ok(Vec::new());
+        };
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(crate::mpegts::programs(file)?)
+    }
+
+    /// Select the program of an MPEG-TS multi-program stream to play, see [`Video::programs`].
+    ///
+    /// The playback position and paused state are kept; decoding restarts in the background,
+    /// and the position is restored once a [`VideoPlayer`](crate::VideoPlayer) sees it's ready.
+    pub fn select_program(&mut self, number: u16) -> Result<(), Error> {
         let paused = self.paused();
+        let position = self.position();
         let mut inner = self.get_mut();
+        inner.program.store(i32::from(number), Ordering::SeqCst);
+
+        // the demuxer only picks its program on startup, so restart decoding
         inner.source.set_state(gst::State::Ready)?;
-        inner.source.set_property("suburi", url.as_str());
         inner.set_paused(paused);
+        inner.restart_from(position);
         Ok(())
     }
 
-    /// Get the current subtitle URL.
-    pub fn subtitle_url(&self) -> Option<url::Url> {
-        url::Url::parse(&self.read().source.property::<String>("suburi")).ok()
+    /// Get the selected MPEG-TS program, or `None` if the first one is played.
+    pub fn program(&self) -> Option<u16> {
+        u16::try_from(self.read().program.load(Ordering::SeqCst)).ok()
+    }
+
+    /// Lists the audio tracks of the media.
+    pub fn audio_tracks(&self) -> Vec<AudioTrack> {
+        let inner = self.read();
+        if !inner.source.has_property("n-audio", None) {
+            return Vec::new();
+        }
+        let count = inner.source.property::<i32>("n-audio");
+        (0..count)
+            .map(|index| {
+                let tags = inner
+                    .source
+                    .emit_by_name::<Option<gst::TagList>>("get-audio-tags", &[&index]);
+                let tag = |get: fn(&gst::TagListRef) -> Option<String>| {
+                    tags.as_ref().and_then(|tags| get(tags))
+                };
+                AudioTrack {
+                    index,
+                    language: tag(|tags| {
+                        Some(tags.get::<gst::tags::LanguageCode>()?.get().to_string())
+                    }),
+                    title: tag(|tags| Some(tags.get::<gst::tags::Title>()?.get().to_string())),
+                    codec: tag(|tags| Some(tags.get::<gst::tags::AudioCodec>()?.get().to_string())),
+                    audio_description: tags.as_ref().is_some_and(|tags| is_audio_description(tags)),
+                }
+            })
+            .collect()
+    }
+
+    /// Set the audio track to play, by its [`AudioTrack::index`].
+    pub fn set_audio_track(&mut self, index: i32) {
+        let mut inner = self.get_mut();
+        inner.main_audio_track = None;
+        inner.source.set_property("current-audio", index);
+    }
+
+    /// Get the index of the audio track being played.
+    pub fn audio_track(&self) -> i32 {
+        self.read().source.property("current-audio")
+    }
+
+    /// Lists the video tracks of the media (e.g., camera angles, or resolutions muxed together).
+    pub fn video_tracks(&self) -> Vec<VideoTrack> {
+        let inner = self.read();
+        if !inner.source.has_property("n-video", None) {
+            return Vec::new();
+        }
+        let count = inner.source.property::<i32>("n-video");
+        (0..count)
+            .map(|index| {
+                let tags = inner
+                    .source
+                    .emit_by_name::<Option<gst::TagList>>("get-video-tags", &[&index]);
+                let tag = |get: fn(&gst::TagListRef) -> Option<String>| {
+                    tags.as_ref().and_then(|tags| get(tags))
+                };
+                VideoTrack {
+                    index,
+                    language: tag(|tags| {
+                        Some(tags.get::<gst::tags::LanguageCode>()?.get().to_string())
+                    }),
+                    title: tag(|tags| Some(tags.get::<gst::tags::Title>()?.get().to_string())),
+                    codec: tag(|tags| Some(tags.get::<gst::tags::VideoCodec>()?.get().to_string())),
+                }
+            })
+            .collect()
+    }
+
+    /// Set the video track to play, by its [`VideoTrack::index`].
+    ///
+    /// Tracks of another resolution are played at their own size: [`Video::decoded_size`] follows the new track
+    /// once its frames arrive, and a size set with [`Video::set_output_size`] has to be set again.
+    pub fn set_video_track(&mut self, index: i32) {
+        self.get_mut().source.set_property("current-video", index);
+    }
+
+    /// Get the index of the video track being played.
+    pub fn video_track(&self) -> i32 {
+        self.read().source.property("current-video")
+    }
+
+    /// Switch to the first audio description track, or back to the previously played track.
+    ///
+    /// Returns whether an audio description is now playing;
+    /// check [`AudioTrack::audio_description`] to tell whether one is available.
+    pub fn enable_audio_description(&mut self, enable: bool) -> bool {
+        let described = self
+            .audio_tracks()
+            .into_iter()
+            .find(|track| track.audio_description);
+        let current = self.audio_track();
+        let mut inner = self.get_mut();
+        match (enable, described) {
+            (true, Some(track)) => {
+                if track.index != current {
+                    inner.main_audio_track = Some(current);
+                    inner.source.set_property("current-audio", track.index);
+                }
+                true
+            }
+            (true, None) => false,
+            (false, _) => {
+                if let Some(main) = inner.main_audio_track.take() {
+                    inner.source.set_property("current-audio", main);
+                }
+                false
+            }
+        }
     }
 
     /// Get the underlying GStreamer pipeline.
@@ -526,6 +3585,119 @@ impl Video {
         self.read().source.clone()
     }
 
+    /// Get the latest decoded frame as an image, e.g., to draw the video in a custom scene
+    /// without a GPU (see [`VideoTexture`](crate::VideoTexture) for the `wgpu` path).
+    ///
+    /// Returns `None` until the first frame is decoded, and while the video is
+    /// [capture protected](Video::set_capture_protected).
+    ///
+    /// Frames come at the decoded resolution; for a small player, have GStreamer scale them down
+    /// with [`Video::set_output_size`] rather than scaling the image on the CPU every frame,
+    /// or draw them with a [`VideoImage`](crate::VideoImage), which does both.
+    pub fn current_frame(&self) -> Option<img::Handle> {
+        let (width, height, rgba) = self.current_frame_rgba(1).ok()?;
+        Some(img::Handle::from_rgba(width, height, rgba))
+    }
+
+    /// Has GStreamer scale frames down to fit within `size` (in physical pixels), keeping their aspect ratio,
+    /// so a full resolution frame isn't converted and scaled on the CPU every time it's drawn on iced's
+    /// software renderer. `None` goes back to the decoded size.
+    ///
+    /// [`VideoImage`](crate::VideoImage) does this by itself as it's laid out; otherwise, call it again
+    /// whenever the widget is resized. The pipeline is only renegotiated when the size changes by more
+    /// than a tenth, so it's fine to call on every layout. Frames are never scaled up.
+    ///
+    /// There is only one stream of frames, so everything reading them gets the scaled ones: a
+    /// [`VideoPlayer`](crate::VideoPlayer) of the same video is drawn from them too and loses sharpness,
+    /// as do [`Video::thumbnails`] and snapshots. Only use it for videos shown on the software renderer.
+    /// [`Video::decoded_size`] stays the decoded size. Pipelines passed to [`Video::from_gst_pipeline`] need a
+    /// `videoscale` in front of the sink for this.
+    pub fn set_output_size(&mut self, size: Option<(u32, u32)>) {
+        self.get_mut().set_output_size(size);
+    }
+
+    /// The size frames are scaled down to with [`Video::set_output_size`], `None` if they aren't.
+    pub fn output_size(&self) -> Option<(u32, u32)> {
+        let scaled = self.read().sink_caps.lock().ok()?.scaled?;
+        Some((scaled.0 as u32, scaled.1 as u32))
+    }
+
+    /// Get the latest decoded frame encoded as PNG, e.g., to save it or to copy it to the clipboard
+    /// like the screenshot feature of other players. iced's clipboard only holds text, so hand it to
+    /// a clipboard crate that supports images (e.g., `arboard`) for that.
+    pub fn current_frame_png(&self) -> Result<Vec<u8>, Error> {
+        let (width, height, rgba) = self.current_frame_rgba(1)?;
+        snapshot::encode_png(width, height, rgba)
+    }
+
+    /// Saves the latest decoded frame as a PNG in `dir`, returning its path.
+    ///
+    /// The file is named after `template`, in which `{title}` (the media's file name),
+    /// `{timecode}` (`HH-MM-SS-FF`) and `{frame}` (the frame number) are filled in,
+    /// e.g., `"{title}_{timecode}.png"`. The same details, and the media's URI, are embedded
+    /// as PNG text metadata so the snapshot can be traced back to its exact frame.
+    pub fn save_snapshot(
+        &self,
+        dir: impl AsRef<std::path::Path>,
+        template: &str,
+    ) -> Result<std::path::PathBuf, Error> {
+        let png = self.current_frame_png()?;
+        let snapshot = {
+            let inner = self.read();
+            let source = if inner.source.has_property("current-uri", None) {
+                inner.source.property::<Option<String>>("current-uri")
+            } else {
+                None
+            }
+            .unwrap_or_default();
+            let title = url::Url::parse(&source)
+                .ok()
+                .and_then(|uri| {
+                    // file paths come percent-decoded; for streams, the last URL segment does
+                    let path = uri.to_file_path().ok().unwrap_or_else(|| {
+                        uri.path_segments()
+                            .and_then(|mut segments| segments.next_back())
+                            .unwrap_or_default()
+                            .into()
+                    });
+                    Some(path.file_stem()?.to_string_lossy().into_owned())
+                })
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| "snapshot".into());
+            snapshot::Snapshot {
+                title,
+                source,
+                position: Duration::from_nanos(inner.frame_pts.load(Ordering::SeqCst)),
+                framerate: inner.framerate,
+            }
+        };
+
+        let path = dir.as_ref().join(snapshot.file_name(template));
+        std::fs::write(&path, snapshot::embed_text(&png, &snapshot.metadata()))?;
+        Ok(path)
+    }
+
+    /// The latest decoded frame as RGBA, downscaled by a given factor.
+    fn current_frame_rgba(&self, downscale: u32) -> Result<(u32, u32, Vec<u8>), Error> {
+        let inner = self.read();
+        if inner.content_gate.is_some() {
+            return Err(Error::ContentLocked);
+        }
+        if inner.capture_protected {
+            return Err(Error::CaptureProtected);
+        }
+        let frame = inner.frame.latest();
+        let (width, height) = frame.size();
+        if frame.len() < inner.format.frame_len(width, height) {
+            return Err(Error::NoFrame);
+        }
+        Ok((
+            width / downscale,
+            height / downscale,
+            yuv_to_rgba(&frame, inner.format, width, height, downscale),
+        ))
+    }
+
     /// Generates a list of thumbnails based on a set of positions in the media, downscaled by a given factor.
     ///
     /// Slow; only needs to be called once for each instance.
@@ -538,6 +3710,13 @@ impl Video {
     where
         I: IntoIterator<Item = Position>,
     {
+        if self.content_locked() {
+            return Err(Error::ContentLocked);
+        }
+        if self.capture_protected() {
+            return Err(Error::CaptureProtected);
+        }
+
         let downscale = u8::from(downscale) as u32;
 
         let paused = self.paused();
@@ -549,9 +3728,9 @@ impl Video {
 
         let out = {
             let inner = self.read();
-            let width = inner.width;
-            let height = inner.height;
-            positions
+            // a frame the same as the one before wouldn't be uploaded, and this would wait for it forever
+            inner.force_upload.store(true, Ordering::SeqCst);
+            let out = positions
                 .into_iter()
                 .map(|pos| {
                     inner.seek(pos, true)?;
@@ -559,18 +3738,17 @@ impl Video {
                     while !inner.upload_frame.load(Ordering::SeqCst) {
                         std::hint::spin_loop();
                     }
+                    let frame = inner.frame.latest();
+                    let (width, height) = frame.size();
                     Ok(img::Handle::from_rgba(
-                        inner.width as u32 / downscale,
-                        inner.height as u32 / downscale,
-                        yuv_to_rgba(
-                            &inner.frame.lock().map_err(|_| Error::Lock)?,
-                            width as _,
-                            height as _,
-                            downscale,
-                        ),
+                        width / downscale,
+                        height / downscale,
+                        yuv_to_rgba(&frame, inner.format, width, height, downscale),
                     ))
                 })
-                .collect()
+                .collect();
+            inner.force_upload.store(false, Ordering::SeqCst);
+            out
         };
 
         self.set_paused(paused);
@@ -579,10 +3757,47 @@ impl Video {
 
         out
     }
+
+    /// Generates `count` thumbnails evenly spaced across the media, downscaled by a given factor,
+    /// for display in a [`Filmstrip`](crate::Filmstrip).
+    ///
+    /// Slow; see [`Video::thumbnails`].
+    pub fn filmstrip_thumbnails(
+        &mut self,
+        count: usize,
+        downscale: NonZeroU8,
+    ) -> Result<Vec<img::Handle>, Error> {
+        let duration = self.duration();
+        self.thumbnails(
+            crate::filmstrip::positions(duration, count).map(Position::Time),
+            downscale,
+        )
+    }
+}
+
+/// Cheap hash of a frame's content, to tell repeated frames apart from new ones.
+///
+/// Stable across runs and platforms, so it can be compared against hashes recorded earlier.
+fn hash_frame(data: &[u8]) -> u64 {
+    let chunks = data.chunks_exact(8);
+    let rest = chunks.remainder();
+    chunks
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+        .chain(rest.iter().map(|&byte| u64::from(byte)))
+        .fold(0u64, |hash, word| {
+            (hash.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95)
+        })
 }
 
-fn yuv_to_rgba(yuv: &[u8], width: u32, height: u32, downscale: u32) -> Vec<u8> {
-    let uv_start = width * height;
+fn yuv_to_rgba(
+    yuv: &[u8],
+    format: PixelFormat,
+    width: u32,
+    height: u32,
+    downscale: u32,
+) -> Vec<u8> {
+    // deeper formats are dithered down to 8 bits, trading their banding for fine noise
+    let dither = format != PixelFormat::Nv12;
     let mut rgba = vec![];
 
     for y in 0..height / downscale {
@@ -590,22 +3805,44 @@ fn yuv_to_rgba(yuv: &[u8], width: u32, height: u32, downscale: u32) -> Vec<u8> {
             let x_src = x * downscale;
             let y_src = y * downscale;
 
-            let uv_i = uv_start + width * (y_src / 2) + x_src / 2 * 2;
-
-            let y = yuv[(y_src * width + x_src) as usize] as f32;
-            let u = yuv[uv_i as usize] as f32;
-            let v = yuv[(uv_i + 1) as usize] as f32;
-
-            let r = 1.164 * (y - 16.0) + 1.596 * (v - 128.0);
-            let g = 1.164 * (y - 16.0) - 0.813 * (v - 128.0) - 0.391 * (u - 128.0);
-            let b = 1.164 * (y - 16.0) + 2.018 * (u - 128.0);
+            let rgb = format
+                .yuv_at(yuv, width, height, x_src, y_src)
+                .map(yuv_to_rgb)
+                .unwrap_or_default();
+            let [r, g, b] = if dither {
+                dither_rgb(rgb, x, y)
+            } else {
+                rgb.map(|c| c as u8)
+            };
 
-            rgba.push(r as u8);
-            rgba.push(g as u8);
-            rgba.push(b as u8);
+            rgba.push(r);
+            rgba.push(g);
+            rgba.push(b);
             rgba.push(0xFF);
         }
     }
 
     rgba
 }
+
+/// Converts a limited range BT.601 YUV pixel to RGB, on the 8-bit scale but neither rounded nor clamped.
+pub(crate) fn yuv_to_rgb([y, u, v]: [f32; 3]) -> [f32; 3] {
+    let r = 1.164 * (y - 16.0) + 1.596 * (v - 128.0);
+    let g = 1.164 * (y - 16.0) - 0.813 * (v - 128.0) - 0.391 * (u - 128.0);
+    let b = 1.164 * (y - 16.0) + 2.018 * (u - 128.0);
+
+    [r, g, b]
+}
+
+/// Rounds `rgb` to 8 bits with a 4x4 ordered dither, so smooth gradients from deeper formats don't band.
+fn dither_rgb(rgb: [f32; 3], x: u32, y: u32) -> [u8; 3] {
+    const BAYER: [[f32; 4]; 4] = [
+        [0.0, 8.0, 2.0, 10.0],
+        [12.0, 4.0, 14.0, 6.0],
+        [3.0, 11.0, 1.0, 9.0],
+        [15.0, 7.0, 13.0, 5.0],
+    ];
+    let threshold = (BAYER[(y % 4) as usize][(x % 4) as usize] + 0.5) / 16.0;
+    // `as` saturates, clamping to 0..=255
+    rgb.map(|c| (c + threshold) as u8)
+}
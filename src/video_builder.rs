@@ -0,0 +1,727 @@
+use crate::closed_caption::Captions;
+use crate::sink_caps::SinkCaps;
+use crate::styled_subtitle::StyledSubtitles;
+use crate::thread_priority::Scheduling;
+use crate::video::{play_flag, play_flags, set_play_flags, Features, Video};
+use crate::{
+    CaptionChannel, ContentGate, EditList, Error, LowLatency, PixelFormat, ThreadPriority,
+};
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use gstreamer_app::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// Audio sinks tried in order by default; `fakesink` keeps video playing when no audio output works.
+const DEFAULT_AUDIO_SINKS: [&str; 2] = ["autoaudiosink", "fakesink"];
+
+/// Builder for a [`Video`] loaded from a URI, for when the defaults of [`Video::new`] aren't enough.
+#[derive(Debug, Clone)]
+pub struct VideoBuilder {
+    uri: url::Url,
+    audio_sinks: Vec<String>,
+    no_audio: bool,
+    max_size: Option<(u32, u32)>,
+    pixel_formats: Vec<PixelFormat>,
+    force_caps: Option<gst::Caps>,
+    decimate: Option<u32>,
+    styled_subtitles: bool,
+    closed_captions: Option<CaptionChannel>,
+    karaoke: bool,
+    meter_tracks: bool,
+    visualization: Option<String>,
+    visualization_output: Option<(String, (u32, u32))>,
+    preserve_pitch: bool,
+    resample_quality: Option<i32>,
+    low_latency: Option<LowLatency>,
+    sample_rate: Option<i32>,
+    native_audio: bool,
+    scheduling: Scheduling,
+    content_gate: Option<ContentGate>,
+    edit_list: Option<EditList>,
+    #[cfg(feature = "media-session")]
+    media_session: bool,
+    #[cfg(feature = "dmabuf")]
+    dmabuf: bool,
+}
+
+impl VideoBuilder {
+    /// Creates a new builder for a video which loads from `uri`.
+    pub fn new(uri: &url::Url) -> Self {
+        VideoBuilder {
+            uri: uri.clone(),
+            audio_sinks: DEFAULT_AUDIO_SINKS.iter().map(|s| s.to_string()).collect(),
+            no_audio: false,
+            max_size: None,
+            pixel_formats: vec![PixelFormat::Nv12, PixelFormat::P010],
+            force_caps: None,
+            decimate: None,
+            styled_subtitles: false,
+            closed_captions: None,
+            karaoke: false,
+            meter_tracks: false,
+            visualization: None,
+            visualization_output: None,
+            preserve_pitch: false,
+            resample_quality: None,
+            low_latency: None,
+            sample_rate: None,
+            native_audio: false,
+            scheduling: Scheduling::default(),
+            content_gate: None,
+            edit_list: None,
+            #[cfg(feature = "media-session")]
+            media_session: false,
+            #[cfg(feature = "dmabuf")]
+            dmabuf: false,
+        }
+    }
+
+    /// Sets the audio sinks to try, in order of preference (e.g., `["pipewiresink", "pulsesink", "alsasink", "fakesink"]`).
+    ///
+    /// The first sink that can be opened is used; if none can, the video plays without audio.
+    /// Use [`Video::audio_sink`] to find out which one is active.
+    pub fn audio_sinks<I, S>(self, audio_sinks: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        VideoBuilder {
+            audio_sinks: audio_sinks.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Sets if the audio stream should be skipped entirely.
+    ///
+    /// Unlike muting, audio isn't even decoded, which saves CPU when many silent previews play at once.
+    pub fn no_audio(self, no_audio: bool) -> Self {
+        VideoBuilder { no_audio, ..self }
+    }
+
+    /// Sets the maximum resolution frames are decoded at, downscaling (with the aspect ratio preserved) if needed.
+    ///
+    /// Useful for small previews, where full resolution frames would be wasted,
+    /// and for drawing [`Video::current_frame`] without a GPU, where they'd be scaled on the CPU.
+    pub fn max_size(self, width: u32, height: u32) -> Self {
+        VideoBuilder {
+            max_size: Some((width, height)),
+            ..self
+        }
+    }
+
+    /// Tunes RTSP streams (e.g., IP cameras) for [`LowLatency`], instead of buffering them for seconds.
+    ///
+    /// Such streams are live, so check [`Video::is_seekable`] before offering seeking.
+    pub fn low_latency(self, low_latency: LowLatency) -> Self {
+        VideoBuilder {
+            low_latency: Some(low_latency),
+            ..self
+        }
+    }
+
+    /// Sets the formats frames may be handed to the widget in, in order of preference, for tuning
+    /// to the hardware an app ships on. NV12 then P010 by default, so 10-bit video keeps its depth.
+    ///
+    /// Formats left out are never used: without P010, 10-bit video is converted down to NV12,
+    /// halving the memory bandwidth of its frames. An empty list keeps the default.
+    /// The format picked is told by [`Video::pixel_format`].
+    pub fn pixel_formats(self, formats: impl IntoIterator<Item = PixelFormat>) -> Self {
+        let mut pixel_formats: Vec<PixelFormat> = Vec::new();
+        for format in formats {
+            if !pixel_formats.contains(&format) {
+                pixel_formats.push(format);
+            }
+        }
+        if pixel_formats.is_empty() {
+            return self;
+        }
+        VideoBuilder {
+            pixel_formats,
+            ..self
+        }
+    }
+
+    /// Forces decoded frames into `caps` before they're converted for display
+    /// (e.g., `video/x-raw,format=I420` to get around a buggy hardware decoder, or `video/x-raw,framerate=30/1`
+    /// to cap the framerate of a preview). Caps with a framerate get a `videorate` to meet it.
+    ///
+    /// Frames are still converted to NV12 (or P010 for 10-bit video) for the widget afterwards.
+    pub fn force_caps(self, caps: gst::Caps) -> Self {
+        VideoBuilder {
+            force_caps: Some(caps),
+            ..self
+        }
+    }
+
+    /// Drops frames so at most `fps` frames per second are decoded and uploaded, whatever the framerate of the media,
+    /// which saves a lot of CPU when many previews play at once (e.g., in a grid or video wall).
+    /// Pass `0` to keep every frame.
+    pub fn decimate_to(self, fps: u32) -> Self {
+        VideoBuilder {
+            decimate: (fps > 0).then_some(fps),
+            ..self
+        }
+    }
+
+    /// Sets if ASS/SSA subtitles (e.g., of anime in Matroska files) are drawn over the video with their styling,
+    /// positioning and karaoke effects, instead of being emitted as plain text. Needs `assrender` (gst-plugins-bad).
+    ///
+    /// `assrender` renders them into a subtitle layer of [`VideoPlayer`](crate::VideoPlayer), at the size the video
+    /// is drawn at (up to 1080p), so they stay sharp whatever [`VideoBuilder::max_size`] the frames are scaled to.
+    /// Subtitles of other formats are still sent through
+    /// [`VideoPlayer::on_subtitle_text`](crate::VideoPlayer::on_subtitle_text).
+    pub fn styled_subtitles(self, styled_subtitles: bool) -> Self {
+        VideoBuilder {
+            styled_subtitles,
+            ..self
+        }
+    }
+
+    /// Decodes the CEA-608 closed captions embedded in the video (e.g., in H.264 SEI or MPEG-TS broadcast captures)
+    /// on the given `channel`, emitting them through [`VideoPlayer::on_closed_caption`](crate::VideoPlayer::on_closed_caption).
+    ///
+    /// Needs the `ccextractor` and `ccconverter` GStreamer elements (gst-plugins-bad) and `cea608tott` (gst-plugins-rs).
+    pub fn closed_captions(self, channel: CaptionChannel) -> Self {
+        VideoBuilder {
+            closed_captions: Some(channel),
+            ..self
+        }
+    }
+
+    /// Sets if the `audiokaraoke` vocal removal filter should be available.
+    ///
+    /// The filter starts disabled; toggle it at runtime with [`Video::set_karaoke`].
+    pub fn karaoke(self, karaoke: bool) -> Self {
+        VideoBuilder { karaoke, ..self }
+    }
+
+    /// Sets the visualization plugin (e.g., `"goom"` or `"wavescope"`) to render in place of video for
+    /// audio-only media, like [`Video::set_visualization`]. None by default, so audio-only media shows nothing.
+    ///
+    /// This is independent of [`VideoBuilder::visualization_output`]; with both set, each runs its own plugin.
+    pub fn visualization(self, name: &str) -> Self {
+        VideoBuilder {
+            visualization: Some(name.to_owned()),
+            ..self
+        }
+    }
+
+    /// Renders the audio with the visualization plugin `name` (e.g., `"goom"` or `"wavescope"`) into frames
+    /// `width` by `height` big, whether or not the media has video, to composite into custom scenes
+    /// through [`VideoTexture::visualization`](crate::VideoTexture::visualization).
+    ///
+    /// Unlike [`VideoBuilder::visualization`], this doesn't replace the video in the widget. With both set,
+    /// the widget shows the plugin set there for audio-only media, while these frames come from `name`.
+    pub fn visualization_output(self, name: &str, width: u32, height: u32) -> Self {
+        VideoBuilder {
+            visualization_output: Some((name.to_owned(), (width, height))),
+            ..self
+        }
+    }
+
+    /// Sets if the levels of every audio track should be measured, not just of the one playing,
+    /// e.g., to find the track holding dialogue before switching to it. Read them with [`Video::track_levels`].
+    ///
+    /// The tracks are decoded a second time next to playback, which costs some CPU.
+    pub fn meter_all_audio_tracks(self, meter_tracks: bool) -> Self {
+        VideoBuilder {
+            meter_tracks,
+            ..self
+        }
+    }
+
+    /// Sets if audio should keep its pitch when playing faster or slower, see [`Video::set_speed`].
+    ///
+    /// Uses `scaletempo`, which sounds best between 0.5x and 2x speed.
+    pub fn preserve_pitch(self, preserve_pitch: bool) -> Self {
+        VideoBuilder {
+            preserve_pitch,
+            ..self
+        }
+    }
+
+    /// Sets the quality of audio resampling, from 0 (fastest) to 10 (best). GStreamer defaults to 4.
+    pub fn audio_resample_quality(self, quality: u8) -> Self {
+        VideoBuilder {
+            resample_quality: Some(i32::from(quality.min(10))),
+            ..self
+        }
+    }
+
+    /// Pins the sample rate audio is output at (e.g., the native rate of an external DAC),
+    /// resampling the media's audio to it if needed.
+    pub fn audio_sample_rate(self, rate: u32) -> Self {
+        VideoBuilder {
+            sample_rate: i32::try_from(rate).ok(),
+            ..self
+        }
+    }
+
+    /// Sets if audio should reach the sink in the media's own format, without any conversion or resampling.
+    ///
+    /// Only use this when the audio device is known to support the media's sample rate and format,
+    /// otherwise audio fails to play. Overrides [`VideoBuilder::audio_sample_rate`].
+    pub fn native_audio(self, native_audio: bool) -> Self {
+        VideoBuilder {
+            native_audio,
+            ..self
+        }
+    }
+
+    /// Sets the scheduling priority of the threads decoding frames and handing them to the UI.
+    pub fn thread_priority(self, priority: ThreadPriority) -> Self {
+        VideoBuilder {
+            scheduling: Scheduling {
+                priority,
+                ..self.scheduling
+            },
+            ..self
+        }
+    }
+
+    /// Pins the threads decoding frames and handing them to the UI to the given CPUs (Linux only).
+    ///
+    /// CPUs past the last one the system supports (`CPU_SETSIZE`) are ignored, with a warning.
+    pub fn pin_threads(self, cpus: impl IntoIterator<Item = usize>) -> Self {
+        VideoBuilder {
+            scheduling: Scheduling {
+                cpus: cpus.into_iter().collect(),
+                ..self.scheduling
+            },
+            ..self
+        }
+    }
+
+    /// Locks the video behind a [`ContentGate`] from the start, so it can't play until unlocked
+    /// with [`Video::unlock_content`].
+    pub fn content_gate(self, content_gate: ContentGate) -> Self {
+        VideoBuilder {
+            content_gate: Some(content_gate),
+            ..self
+        }
+    }
+
+    /// Applies an [`EditList`] (e.g., one read back with [`EditList::parse_edl`]) once the video is open.
+    pub fn edit_list(self, edit_list: EditList) -> Self {
+        VideoBuilder {
+            edit_list: Some(edit_list),
+            ..self
+        }
+    }
+
+    /// Sets if playback should be reported to the desktop session (over MPRIS, as COSMIC's media controls expect),
+    /// so global media keys control the video and it pauses when the screen locks.
+    ///
+    /// Commands are followed while a [`VideoPlayer`](crate::VideoPlayer) shows the video.
+    #[cfg(feature = "media-session")]
+    pub fn media_session(self, media_session: bool) -> Self {
+        VideoBuilder {
+            media_session,
+            ..self
+        }
+    }
+
+    /// Sets if NV12 frames of hardware decoders (e.g., VAAPI) should be left in their DMA-BUFs and imported
+    /// into textures, instead of being copied through system memory.
+    ///
+    /// Importing needs a Vulkan device with the `VK_KHR_external_memory_fd`, `VK_EXT_external_memory_dma_buf`
+    /// and `VK_EXT_image_drm_format_modifier` extensions enabled. The device iced creates never enables them,
+    /// so a [`VideoPlayer`](crate::VideoPlayer) never imports: every frame is read back from its DMA-BUF
+    /// through system memory, which is slower than leaving this off. Only a [`VideoTexture`](crate::VideoTexture)
+    /// on a device the app opened with those extensions imports frames.
+    ///
+    /// Anything reading frames on the CPU (e.g., [`Video::current_frame`]) reads them back too, and frames
+    /// scaled down with [`Video::set_output_size`] are scaled in system memory, so they aren't imported.
+    #[cfg(feature = "dmabuf")]
+    pub fn dmabuf(self, dmabuf: bool) -> Self {
+        VideoBuilder { dmabuf, ..self }
+    }
+
+    /// Points the builder at another URI, keeping all other options.
+    pub(crate) fn with_uri(self, uri: url::Url) -> Self {
+        VideoBuilder { uri, ..self }
+    }
+
+    /// Builds the video.
+    /// Note that live sources will report the duration to be zero.
+    pub fn build(self) -> Result<Video, Error> {
+        gst::init()?;
+        // kept so the pipeline can be rebuilt with the same options
+        let builder = self.clone();
+
+        // by default 10-bit video negotiates P010, so it isn't converted down to 8 bits (I420_10LE is only repacked into it)
+        let formats: Vec<_> = self
+            .pixel_formats
+            .iter()
+            .map(|format| format.caps_name())
+            .collect();
+        let raw = format!("video/x-raw,format={{ {} }}", formats.join(", "));
+        // DMA-BUFs are preferred, so hardware decoders hand their frames over without copying them
+        #[cfg(feature = "dmabuf")]
+        let dmabuf = (self.dmabuf && self.pixel_formats.contains(&PixelFormat::Nv12))
+            .then_some(crate::dmabuf::CAPS);
+        #[cfg(not(feature = "dmabuf"))]
+        let dmabuf: Option<[&str; 0]> = None;
+        let max_size = self
+            .max_size
+            .map(|(width, height)| format!(",width=[1,{width}],height=[1,{height}]"))
+            .unwrap_or_default();
+        let video_caps = dmabuf
+            .into_iter()
+            .flatten()
+            .chain([raw.as_str()])
+            .map(|caps| format!("{caps}{max_size}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        // forced caps are set on the capsfilter after parsing, as they don't survive quoting, and so are the sink's
+        // captions are taken off the frames before anything else could drop their metadata
+        let closed_captions = match self.closed_captions {
+            Some(_) if gst::ElementFactory::find("ccextractor").is_some() => {
+                "ccextractor name=iced_cc ! "
+            }
+            Some(_) => {
+                log::warn!("closed captions need the ccextractor element, playing without them");
+                ""
+            }
+            None => "",
+        };
+        let decimate = self
+            .decimate
+            .map(|fps| format!("videorate drop-only=true max-rate={fps} ! "))
+            .unwrap_or_default();
+        let forced = match self.force_caps {
+            Some(ref caps)
+                if caps
+                    .iter()
+                    .any(|structure| structure.has_field("framerate")) =>
+            {
+                "videorate ! capsfilter name=iced_forced ! "
+            }
+            Some(_) => "capsfilter name=iced_forced ! ",
+            None => "",
+        };
+
+        let pipeline = format!("playbin uri=\"{}\" text-sink=\"appsink name=iced_text sync=true caps=text/x-raw\" video-sink=\"{}{}{}videoscale ! videoconvert name=iced_convert ! appsink name=iced_video drop=true\"", self.uri.as_str(), closed_captions, decimate, forced);
+        let pipeline = gst::parse::launch(pipeline.as_ref())?
+            .downcast::<gst::Pipeline>()
+            .map_err(|_| Error::Cast)?;
+
+        let audio_sink = if self.no_audio {
+            None
+        } else {
+            let audio_sink = open_audio_sink(&self.audio_sinks);
+            match audio_sink {
+                Some(ref audio_sink) => match self.sample_rate.filter(|_| !self.native_audio) {
+                    Some(rate) => pipeline.set_property("audio-sink", with_rate(audio_sink, rate)?),
+                    None => pipeline.set_property("audio-sink", audio_sink),
+                },
+                None => log::warn!("no usable audio sink, playing without audio"),
+            }
+            audio_sink
+        };
+
+        if let Some(low_latency) = self.low_latency {
+            pipeline.connect("source-setup", false, move |args| {
+                if let Some(source) = args
+                    .get(1)
+                    .and_then(|source| source.get::<gst::Element>().ok())
+                {
+                    low_latency.setup(&source);
+                }
+                None
+            });
+        }
+
+        if let Some(quality) = self.resample_quality {
+            pipeline.connect_deep_element_added(move |_, _, element| {
+                if element
+                    .factory()
+                    .is_some_and(|factory| factory.name() == "audioresample")
+                {
+                    element.set_property("quality", quality);
+                }
+            });
+        }
+
+        let mut audio_filters = Vec::new();
+        if self.preserve_pitch {
+            audio_filters.push(gst::ElementFactory::make("scaletempo").build()?);
+        }
+        if self.karaoke {
+            let karaoke = gst::ElementFactory::make("audiokaraoke")
+                .property("level", 0.0f32)
+                .property("mono-level", 0.0f32)
+                .build()?;
+            audio_filters.push(karaoke);
+        }
+        // last, so it shows what is heard
+        let mut visualizer = None;
+        if let Some((ref name, size)) = self.visualization_output {
+            match crate::visualization::visualizer(name, size) {
+                Ok((filter, frames)) => {
+                    audio_filters.push(filter);
+                    visualizer = Some(frames);
+                }
+                Err(err) => log::warn!("cannot render the {name} visualization: {err}"),
+            }
+        }
+        match audio_filters.len() {
+            0 => {}
+            1 => pipeline.set_property("audio-filter", &audio_filters[0]),
+            _ => pipeline.set_property("audio-filter", chain(&audio_filters)?),
+        }
+
+        // visualizations stay off unless asked for, see `VideoBuilder::visualization`
+        if let Some(ref name) = self.visualization {
+            let vis = gst::ElementFactory::make(name).build()?;
+            pipeline.set_property("vis-plugin", &vis);
+        }
+        set_play_flags(
+            &pipeline,
+            Features {
+                audio: audio_sink.is_some(),
+                vis: self.visualization.is_some(),
+                // network streams (including HLS and DASH) fill a buffer before playing, see `Video::buffering`
+                buffering: matches!(self.uri.scheme(), "http" | "https"),
+                ..Features::default()
+            }
+            .apply_bits(play_flags(&pipeline))
+                | if self.native_audio {
+                    play_flag("native-audio")
+                } else {
+                    0
+                },
+        );
+
+        let video_sink: gst::Element = pipeline.property("video-sink");
+        let pad = video_sink.pads().first().cloned().unwrap();
+        let pad = pad.dynamic_cast::<gst::GhostPad>().unwrap();
+        let bin = pad
+            .parent_element()
+            .unwrap()
+            .downcast::<gst::Bin>()
+            .unwrap();
+        if let Some(ref caps) = self.force_caps {
+            let capsfilter = bin
+                .by_name("iced_forced")
+                .ok_or_else(|| Error::Element("capsfilter".into()))?;
+            capsfilter.set_property("caps", caps);
+        }
+        let captions = Captions::default();
+        if let (Some(channel), Some(extractor)) = (self.closed_captions, bin.by_name("iced_cc")) {
+            crate::closed_caption::decode_captions(&bin, &extractor, channel, captions.clone());
+        }
+        let video_sink = bin.by_name("iced_video").unwrap();
+        let video_sink = video_sink.downcast::<gst_app::AppSink>().unwrap();
+        let video_caps = video_caps.parse::<gst::Caps>().map_err(|_| Error::Caps)?;
+        video_sink.set_caps(Some(&video_caps));
+        let sink_caps = Arc::new(Mutex::new(SinkCaps::new(Some(video_caps))));
+        #[cfg(feature = "dmabuf")]
+        if self.dmabuf {
+            crate::dmabuf::request_video_meta(&video_sink);
+        }
+        let convert = bin
+            .by_name("iced_convert")
+            .ok_or_else(|| Error::Element("videoconvert".into()))?;
+        pass_through_native(&convert, &video_sink, Arc::clone(&sink_caps), &formats);
+        crate::sink_caps::track_decoded_size(pad.upcast_ref(), &video_sink, Arc::clone(&sink_caps));
+
+        let text_sink: gst::Element = pipeline.property("text-sink");
+        //let pad = text_sink.pads().get(0).cloned().unwrap();
+        let text_sink = text_sink.downcast::<gst_app::AppSink>().unwrap();
+        let styled_subtitles = if self.styled_subtitles {
+            // playbin stops decoding the ASS/SSA stream once the sink takes it as it is
+            text_sink.set_caps(Some(
+                &"text/x-raw; application/x-ass; application/x-ssa".parse::<gst::Caps>()?,
+            ));
+            Some(StyledSubtitles::new()?)
+        } else {
+            None
+        };
+
+        let mut video = Video::from_gst_pipeline_scheduled(
+            pipeline,
+            video_sink,
+            Some(text_sink),
+            styled_subtitles,
+            self.scheduling,
+            self.content_gate,
+        )?;
+        #[cfg(feature = "media-session")]
+        if self.media_session {
+            let id = video.read().id;
+            match crate::media_session::MediaSession::new(id, &self.uri) {
+                Ok(session) => video.write().media_session = Some(session),
+                Err(err) => log::warn!("cannot join the media session: {err}"),
+            }
+        }
+
+        video.write().captions = captions;
+        video.write().sink_caps = sink_caps;
+        video.write().visualizer = visualizer;
+        if self.meter_tracks {
+            let clock = video.read().source.clock();
+            match crate::track_meter::TrackMeters::new(&self.uri, clock) {
+                Ok(meters) => {
+                    let mut inner = video.write();
+                    let paused = inner.paused();
+                    inner.track_meters = Some(meters);
+                    inner.set_paused(paused);
+                }
+                Err(err) => log::warn!("cannot meter audio tracks: {err}"),
+            }
+        }
+        if let Some(edit_list) = self.edit_list {
+            video.set_edit_list(edit_list)?;
+        }
+        video.write().builder = Some(builder);
+        Ok(video)
+    }
+}
+
+/// Keeps `convert` from touching frames decoded in one of the `formats` the shader samples (e.g., NV12 from
+/// hardware decoders), so only other formats (e.g., I420 from software decoders) cost a conversion every frame.
+///
+/// While such frames come in, `sink` only accepts their format and colorimetry instead of its usual caps,
+/// so `convert` negotiates the same caps on both sides and passes buffers through as they are.
+fn pass_through_native(
+    convert: &gst::Element,
+    sink: &gst_app::AppSink,
+    sink_caps: Arc<Mutex<SinkCaps>>,
+    formats: &[&'static str],
+) {
+    let (Some(sink_pad), Some(src_pad)) = (convert.static_pad("sink"), convert.static_pad("src"))
+    else {
+        return;
+    };
+    let formats = formats.to_vec();
+    let sink = sink.clone();
+    // the decoded caps reach the sink pad before `convert` negotiates its output from them
+    sink_pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_, info| {
+        let Some(gst::PadProbeData::Event(ref event)) = info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let gst::EventView::Caps(decoded) = event.view() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Some(decoded) = decoded.caps().structure(0) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Ok(mut sink_caps) = sink_caps.lock() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        sink_caps.native = match decoded.get::<&str>("format") {
+            Ok(format) if formats.contains(&format) => {
+                let mut native = gst::Structure::builder("video/x-raw")
+                    .field("format", format)
+                    .build();
+                for field in ["colorimetry", "chroma-site"] {
+                    if let Ok(value) = decoded.value(field) {
+                        native.set_value(field, value.clone());
+                    }
+                }
+                Some(native)
+            }
+            _ => None,
+        };
+        sink_caps.apply(&sink, false);
+        gst::PadProbeReturn::Ok
+    });
+
+    // checks what was negotiated, as `convert` decides for itself whether it can pass buffers through
+    let weak_sink_pad = sink_pad.downgrade();
+    src_pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_, info| {
+        let Some(gst::PadProbeData::Event(ref event)) = info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let gst::EventView::Caps(shown) = event.view() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Some(decoded) = weak_sink_pad.upgrade().and_then(|pad| pad.current_caps()) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let format = |caps: &gst::CapsRef| {
+            caps.structure(0)
+                .and_then(|structure| structure.get::<&str>("format").ok())
+                .map(str::to_string)
+        };
+        let (decoded_format, shown_format) = (format(&decoded), format(shown.caps()));
+        if decoded.as_ref() == shown.caps() {
+            log::debug!(
+                "frames are shown in their decoded format {decoded_format:?}, without conversion"
+            );
+        } else if decoded_format == shown_format {
+            log::warn!(
+                "frames are converted on the CPU despite their format: {decoded} to {}",
+                shown.caps()
+            );
+        } else {
+            log::debug!(
+                "frames are converted from {decoded_format:?} to {shown_format:?} on the CPU"
+            );
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+/// Wraps `sink` in a bin only accepting audio at `rate`, so playbin resamples to it.
+fn with_rate(sink: &gst::Element, rate: i32) -> Result<gst::Element, Error> {
+    let capsfilter = gst::ElementFactory::make("capsfilter")
+        .property(
+            "caps",
+            gst::Caps::builder("audio/x-raw")
+                .field("rate", rate)
+                .build(),
+        )
+        .build()?;
+    let bin = gst::Bin::new();
+    bin.add_many([&capsfilter, sink])?;
+    capsfilter.link(sink)?;
+    let pad = capsfilter.static_pad("sink").ok_or(Error::Caps)?;
+    bin.add_pad(&gst::GhostPad::with_target(&pad)?)?;
+    Ok(bin.upcast())
+}
+
+/// Links `elements` one after the other in a bin, so playbin can take them as a single filter.
+fn chain(elements: &[gst::Element]) -> Result<gst::Element, Error> {
+    let bin = gst::Bin::new();
+    bin.add_many(elements)?;
+    gst::Element::link_many(elements)?;
+    let sink = elements
+        .first()
+        .and_then(|element| element.static_pad("sink"))
+        .ok_or(Error::Caps)?;
+    let src = elements
+        .last()
+        .and_then(|element| element.static_pad("src"))
+        .ok_or(Error::Caps)?;
+    bin.add_pad(&gst::GhostPad::with_target(&sink)?)?;
+    bin.add_pad(&gst::GhostPad::with_target(&src)?)?;
+    Ok(bin.upcast())
+}
+
+/// Returns the first sink in `names` that exists and can open its device.
+fn open_audio_sink(names: &[String]) -> Option<gst::Element> {
+    names.iter().find_map(|name| {
+        let sink = match gst::ElementFactory::make(name).build() {
+            Ok(sink) => sink,
+            Err(err) => {
+                log::debug!("audio sink {name} is unavailable: {err}");
+                return None;
+            }
+        };
+        // going to READY opens the device (or connects to the sound server)
+        let opened = sink.set_state(gst::State::Ready).is_ok();
+        let _ = sink.set_state(gst::State::Null);
+        if !opened {
+            log::debug!("audio sink {name} failed to open");
+            return None;
+        }
+        if sink.has_property("sync", None) {
+            // keep `fakesink` and friends running in real time
+            sink.set_property("sync", true);
+        }
+        Some(sink)
+    })
+}
@@ -0,0 +1,164 @@
+use crate::Video;
+use iced::{
+    advanced::{self, graphics::core::event::Status, image as img, layout, widget, Widget},
+    widget::image::FilterMethod,
+    Element,
+};
+
+/// Draws a [`Video`] with [`Video::current_frame`], for iced's software renderer (`tiny-skia`),
+/// where [`VideoPlayer`](crate::VideoPlayer) can't be used.
+///
+/// As it's laid out, it has GStreamer scale frames down to its size with [`Video::set_output_size`],
+/// so they aren't converted and scaled on the CPU at full resolution every frame. That affects every
+/// other consumer of the video as well, so don't show the same video in a [`VideoPlayer`](crate::VideoPlayer).
+pub struct VideoImage<'a> {
+    video: &'a Video,
+    content_fit: iced::ContentFit,
+    width: iced::Length,
+    height: iced::Length,
+    scale_factor: f32,
+}
+
+impl<'a> VideoImage<'a> {
+    /// Creates a new widget drawing the frames of `video`.
+    pub fn new(video: &'a Video) -> Self {
+        VideoImage {
+            video,
+            content_fit: iced::ContentFit::default(),
+            width: iced::Length::Fill,
+            height: iced::Length::Fill,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// Sets the width of the widget.
+    pub fn width(self, width: impl Into<iced::Length>) -> Self {
+        VideoImage {
+            width: width.into(),
+            ..self
+        }
+    }
+
+    /// Sets the height of the widget.
+    pub fn height(self, height: impl Into<iced::Length>) -> Self {
+        VideoImage {
+            height: height.into(),
+            ..self
+        }
+    }
+
+    /// Sets the `ContentFit` of the widget.
+    pub fn content_fit(self, content_fit: iced::ContentFit) -> Self {
+        VideoImage {
+            content_fit,
+            ..self
+        }
+    }
+
+    /// Sets the scale factor of the window, so frames are scaled to the widget's size in physical pixels.
+    ///
+    /// Widgets don't get to see it, so pass the one from `iced::window::get_scale_factor`.
+    pub fn scale_factor(self, scale_factor: f32) -> Self {
+        VideoImage {
+            scale_factor,
+            ..self
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for VideoImage<'a>
+where
+    Renderer: advanced::image::Renderer<Handle = img::Handle>,
+{
+    fn size(&self) -> iced::Size<iced::Length> {
+        iced::Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut widget::Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        // based on `Image::layout`, but sized by the video rather than the (possibly scaled) frame
+        let (width, height) = self.video.size();
+        let image_size = iced::Size::new(width as f32, height as f32);
+        let raw_size = limits.resolve(self.width, self.height, image_size);
+        let full_size = self.content_fit.fit(image_size, raw_size);
+        let final_size = iced::Size {
+            width: match self.width {
+                iced::Length::Shrink => f32::min(raw_size.width, full_size.width),
+                _ => raw_size.width,
+            },
+            height: match self.height {
+                iced::Length::Shrink => f32::min(raw_size.height, full_size.height),
+                _ => raw_size.height,
+            },
+        };
+
+        let shown = self.content_fit.fit(image_size, final_size) * self.scale_factor;
+        self.video.read().set_output_size(Some((
+            shown.width.ceil().max(1.0) as u32,
+            shown.height.ceil().max(1.0) as u32,
+        )));
+
+        layout::Node::new(final_size)
+    }
+
+    fn draw(
+        &self,
+        _tree: &widget::Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &advanced::renderer::Style,
+        layout: advanced::Layout<'_>,
+        _cursor: advanced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+    ) {
+        let Some(handle) = self.video.current_frame() else {
+            return;
+        };
+        iced::widget::image::draw(
+            renderer,
+            layout,
+            &handle,
+            self.content_fit,
+            FilterMethod::Linear,
+            iced::Rotation::default(),
+            1.0,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        _tree: &mut widget::Tree,
+        event: iced::Event,
+        _layout: advanced::Layout<'_>,
+        _cursor: advanced::mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn advanced::Clipboard,
+        shell: &mut advanced::Shell<'_, Message>,
+        _viewport: &iced::Rectangle,
+    ) -> Status {
+        if let iced::Event::Window(iced::window::Event::RedrawRequested(_)) = event {
+            if !self.video.paused() {
+                shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+            }
+        }
+        Status::Ignored
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<VideoImage<'a>> for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: 'a + advanced::image::Renderer<Handle = img::Handle>,
+{
+    fn from(video_image: VideoImage<'a>) -> Self {
+        Self::new(video_image)
+    }
+}
@@ -1,13 +1,97 @@
-use crate::{pipeline::VideoPrimitive, video::Video};
+use crate::{
+    annotation,
+    audio_devices::AudioDevices,
+    pipeline::{DrawSettings, ToneMapping, VideoPrimitive},
+    pixel_probe,
+    video::{Internal, Video},
+    watermark::{Corner, Watermark},
+    Annotation, Cue, DataSample, FrameMetadata, Gap, Guides, MotionPreference, NetworkError,
+    NetworkErrorKind, PixelProbe, PowerStatus, QualityCap, QualityPolicy, SeekBar,
+    SystemMotionPreference, SystemPowerStatus, Transform,
+};
 use gstreamer as gst;
 use iced::{
-    advanced::{self, graphics::core::event::Status, layout, widget, Widget},
+    advanced::{self, graphics::core::event::Status, layout, text, widget, Widget},
     Element,
 };
 use iced_wgpu::primitive::Renderer as PrimitiveRenderer;
 use log::error;
-use std::{marker::PhantomData, sync::atomic::Ordering, time::Duration};
-use std::{sync::Arc, time::Instant};
+use std::{
+    marker::PhantomData,
+    sync::atomic::Ordering,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Frame interpolation applied by a [`VideoPlayer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation {
+    /// Show each frame as-is.
+    #[default]
+    None,
+    /// Motion smoothing: cross-fade from the previous frame to the current one over the frame's duration.
+    ///
+    /// Makes low framerate content (e.g., 24/25fps) appear smoother on high refresh rate displays,
+    /// at the cost of some ghosting, one frame of extra latency, and the GPU memory of a second frame.
+    Blend,
+}
+
+/// What a [`VideoPlayer`] does with playback while its window is unfocused, see [`VideoPlayer::focus_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FocusPolicy {
+    /// Keep playing as usual.
+    #[default]
+    Ignore,
+    /// Pause, resuming once the window is focused again.
+    Pause,
+    /// Duck the audio to this fraction (`0.0..=1.0`) of its volume (see [`Video::duck`](crate::Video::duck))
+    /// until the window is focused again.
+    Duck(f64),
+}
+
+/// Accessibility filter or display mode applied by a [`VideoPlayer`] when drawing frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Filter {
+    /// Show frames unaltered.
+    #[default]
+    None,
+    /// Increase contrast and saturation for low vision.
+    HighContrast,
+    /// Sharpen edges so shapes stand out.
+    EdgeEnhance,
+    /// Daltonize for red-blindness.
+    Protanopia,
+    /// Daltonize for green-blindness.
+    Deuteranopia,
+    /// Daltonize for blue-blindness.
+    Tritanopia,
+    /// Show only brightness, e.g., for monochrome scientific cameras.
+    Grayscale,
+    /// Map brightness to a thermal palette (black, purple, red, yellow, white), as expected of IR camera feeds.
+    Thermal,
+    /// Map brightness to a rainbow palette (blue to red), to make small differences in brightness stand out.
+    FalseColor,
+}
+
+/// Change to the available audio output devices, as reported by a [`VideoPlayer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AudioDeviceEvent {
+    /// An audio output with the given display name became available (e.g., headphones were plugged in).
+    Added(String),
+    /// An audio output with the given display name went away (e.g., headphones were unplugged).
+    Removed(String),
+}
+
+/// Space between the seek bar of [`VideoPlayer::controls`] and the edges of the player.
+const CONTROLS_PADDING: f32 = 8.0;
+
+#[derive(Debug)]
+struct State {
+    /// Tree of the seek bar drawn for [`VideoPlayer::controls`].
+    seek_bar: widget::Tree,
+}
+
+/// Produces a message from a value the player reports.
+type Callback<'a, T, Message> = Box<dyn Fn(T) -> Message + 'a>;
 
 /// Video player widget which displays the current frame of a [`Video`](crate::Video).
 pub struct VideoPlayer<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer>
@@ -16,12 +100,52 @@ where
 {
     video: &'a Video,
     content_fit: iced::ContentFit,
+    aspect_ratio: Option<f32>,
     width: iced::Length,
     height: iced::Length,
+    interpolation: Interpolation,
+    filter: Filter,
+    tone_mapping: ToneMapping,
+    transform: Transform,
+    crop: Option<iced::Rectangle>,
+    annotations: &'a [Annotation],
+    overlay: Option<Element<'a, Message, Theme, Renderer>>,
+    controls: bool,
+    cues: &'a [Cue],
+    guides: Guides,
+    watermark: Option<Watermark>,
+    respect_reduced_motion: bool,
+    motion_preference: &'a dyn MotionPreference,
+    focus_policy: FocusPolicy,
+    quality_policy: QualityPolicy,
+    power_status: &'a dyn PowerStatus,
+    on_quality_capped: Option<Callback<'a, QualityCap, Message>>,
+    on_variant_changed: Option<Callback<'a, usize, Message>>,
     on_end_of_stream: Option<Message>,
     on_new_frame: Option<Message>,
-    on_subtitle_text: Option<Box<dyn Fn(Option<String>) -> Message + 'a>>,
-    on_error: Option<Box<dyn Fn(&glib::Error) -> Message + 'a>>,
+    on_frame_hash: Option<Callback<'a, u64, Message>>,
+    on_frame_metadata: Option<Callback<'a, FrameMetadata, Message>>,
+    on_latency: Option<Callback<'a, Duration, Message>>,
+    on_subtitle_text: Option<Callback<'a, Option<String>, Message>>,
+    on_closed_caption: Option<Callback<'a, Option<String>, Message>>,
+    on_pixel_probe: Option<Callback<'a, PixelProbe, Message>>,
+    loupe: bool,
+    on_error: Option<Callback<'a, glib::Error, Message>>,
+    on_network_error: Option<Callback<'a, NetworkError, Message>>,
+    on_buffering: Option<Callback<'a, u8, Message>>,
+    on_audio_device_changed: Option<Callback<'a, AudioDeviceEvent, Message>>,
+    on_skip_segment: Option<Callback<'a, Option<(Duration, Duration)>, Message>>,
+    on_lyric_line: Option<Callback<'a, Option<String>, Message>>,
+    on_chapter_changed: Option<Callback<'a, Option<usize>, Message>>,
+    on_data_sample: Option<Callback<'a, DataSample, Message>>,
+    on_discontinuity: Option<Callback<'a, Gap, Message>>,
+    on_framerate: Option<Callback<'a, f64, Message>>,
+    on_frames_dropped: Option<Callback<'a, u32, Message>>,
+    on_outro: Option<(Duration, Callback<'a, Duration, Message>)>,
+    on_position: Option<(Duration, Callback<'a, Duration, Message>)>,
+    on_seek_settled: Option<Callback<'a, Duration, Message>>,
+    on_advance: Option<Message>,
+    on_renderer_reset: Option<Message>,
     _phantom: PhantomData<(Theme, Renderer)>,
 }
 
@@ -34,12 +158,52 @@ where
         VideoPlayer {
             video,
             content_fit: iced::ContentFit::default(),
+            aspect_ratio: None,
             width: iced::Length::Shrink,
             height: iced::Length::Shrink,
+            interpolation: Interpolation::default(),
+            filter: Filter::default(),
+            tone_mapping: ToneMapping::default(),
+            transform: Transform::default(),
+            crop: None,
+            annotations: &[],
+            overlay: None,
+            controls: false,
+            cues: &[],
+            guides: Guides::default(),
+            watermark: None,
+            respect_reduced_motion: false,
+            motion_preference: &SystemMotionPreference,
+            focus_policy: FocusPolicy::default(),
+            quality_policy: QualityPolicy::default(),
+            power_status: &SystemPowerStatus,
+            on_quality_capped: None,
+            on_variant_changed: None,
             on_end_of_stream: None,
             on_new_frame: None,
+            on_frame_hash: None,
+            on_frame_metadata: None,
+            on_latency: None,
             on_subtitle_text: None,
+            on_closed_caption: None,
+            on_pixel_probe: None,
+            loupe: false,
             on_error: None,
+            on_network_error: None,
+            on_buffering: None,
+            on_audio_device_changed: None,
+            on_skip_segment: None,
+            on_lyric_line: None,
+            on_chapter_changed: None,
+            on_data_sample: None,
+            on_discontinuity: None,
+            on_framerate: None,
+            on_frames_dropped: None,
+            on_outro: None,
+            on_position: None,
+            on_seek_settled: None,
+            on_advance: None,
+            on_renderer_reset: None,
             _phantom: Default::default(),
         }
     }
@@ -68,6 +232,224 @@ where
         }
     }
 
+    /// Forces the shown picture to an aspect ratio (width over height, e.g. `16.0 / 9.0`), in layout and drawing,
+    /// for media declaring the wrong one. `None` (the default) keeps the aspect ratio of the frame.
+    pub fn aspect_ratio(self, aspect_ratio: Option<f32>) -> Self {
+        VideoPlayer {
+            aspect_ratio: aspect_ratio.filter(|ratio| ratio.is_finite() && *ratio > 0.0),
+            ..self
+        }
+    }
+
+    /// Sets the frame [`Interpolation`] of the `VideoPlayer`. Disabled by default.
+    pub fn interpolation(self, interpolation: Interpolation) -> Self {
+        VideoPlayer {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Sets the accessibility [`Filter`] of the `VideoPlayer`. Disabled by default.
+    pub fn accessibility_filter(self, filter: Filter) -> Self {
+        VideoPlayer { filter, ..self }
+    }
+
+    /// Sets how HDR (HDR10 or HLG) videos are mapped down to SDR. [`ToneMapping::Hable`] by default.
+    ///
+    /// SDR videos are drawn as-is.
+    pub fn tone_mapping(self, tone_mapping: ToneMapping) -> Self {
+        VideoPlayer {
+            tone_mapping,
+            ..self
+        }
+    }
+
+    /// Sets the [`Transform`] rotating or mirroring the video, e.g. to correct a file with the wrong
+    /// orientation, or to mirror a webcam. Applied on top of the orientation the stream is tagged with;
+    /// the layout follows the transformed size.
+    pub fn transform(self, transform: Transform) -> Self {
+        VideoPlayer { transform, ..self }
+    }
+
+    /// Sets the region of the frame to show (and lay out), in normalized coordinates (`0.0..=1.0`) of the
+    /// frame as decoded, e.g. to cut off letterbox bars or broadcast overlays. The whole frame by default.
+    ///
+    /// Annotations and guides are relative to the shown region.
+    pub fn crop(self, crop: iced::Rectangle) -> Self {
+        let x = crop.x.clamp(0.0, 1.0);
+        let y = crop.y.clamp(0.0, 1.0);
+        let crop = iced::Rectangle {
+            x,
+            y,
+            width: crop.width.min(1.0 - x),
+            height: crop.height.min(1.0 - y),
+        };
+        VideoPlayer {
+            // nothing left to show, so show everything
+            crop: (crop.width > 0.0 && crop.height > 0.0).then_some(crop),
+            ..self
+        }
+    }
+
+    /// Sets the region of the frame to show, in pixels of the frame as decoded, see [`VideoPlayer::crop`].
+    pub fn crop_pixels(self, crop: iced::Rectangle) -> Self {
+        let (width, height) = self.video.decoded_size();
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+        self.crop(iced::Rectangle {
+            x: crop.x / width,
+            y: crop.y / height,
+            width: crop.width / width,
+            height: crop.height / height,
+        })
+    }
+
+    /// Sets the [`Annotation`]s drawn over the video, e.g. tracking boxes or review markup.
+    ///
+    /// They are positioned relative to the video frame, so they stay aligned whatever the [`iced::ContentFit`].
+    pub fn annotations(self, annotations: &'a [Annotation]) -> Self {
+        VideoPlayer {
+            annotations,
+            ..self
+        }
+    }
+
+    /// Sets widgets (e.g., controls, a picture-in-picture video, captions) laid out over the video.
+    ///
+    /// The overlay gets a layer of its own above the frame (so a picture-in-picture [`VideoPlayer`] in it
+    /// goes above this one) and gets events first. Widgets outside the player that should go above it
+    /// are wrapped in an [`AboveVideo`](crate::AboveVideo).
+    pub fn overlay(self, overlay: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        VideoPlayer {
+            overlay: Some(overlay.into()),
+            ..self
+        }
+    }
+
+    /// Sets if a seek bar is shown along the bottom of the video, marking the media's chapters
+    /// (see [`Video::chapters`](crate::Video::chapters)) and the [`VideoPlayer::cue_points`]. Hidden by default.
+    ///
+    /// Hovering a marker shows its label, and seeking near a marker snaps to it.
+    pub fn controls(self, controls: bool) -> Self {
+        VideoPlayer { controls, ..self }
+    }
+
+    /// Sets the cue points (e.g., bookmarks) marked on the seek bar of the [`VideoPlayer::controls`],
+    /// along with the chapters.
+    pub fn cue_points(self, cues: &'a [Cue]) -> Self {
+        VideoPlayer { cues, ..self }
+    }
+
+    /// Sets the framing [`Guides`] (safe areas, thirds, center cross) drawn over the video. None by default.
+    ///
+    /// Like [`Annotation`]s, they are aligned to the video frame rather than to the widget.
+    pub fn guides(self, guides: Guides) -> Self {
+        VideoPlayer { guides, ..self }
+    }
+
+    /// Sets a watermark (e.g., a logo) drawn over a `corner` of the video, `margin` logical pixels in from its edges.
+    ///
+    /// The image is drawn at its own size in logical pixels, but never wider than a quarter of the video.
+    pub fn watermark(
+        self,
+        image: impl Into<iced::widget::image::Handle>,
+        corner: Corner,
+        opacity: f32,
+        margin: f32,
+    ) -> Self {
+        VideoPlayer {
+            watermark: Some(Watermark {
+                image: image.into(),
+                corner,
+                opacity: opacity.clamp(0.0, 1.0),
+                margin,
+                drift: None,
+            }),
+            ..self
+        }
+    }
+
+    /// Sets the watermark to slowly drift around its corner within the margin, going round once every `period`,
+    /// so it doesn't burn into OLED and plasma screens. Has no effect without a [`VideoPlayer::watermark`].
+    pub fn watermark_drift(mut self, period: Duration) -> Self {
+        if let Some(ref mut watermark) = self.watermark {
+            watermark.drift = Some(period);
+        }
+        self
+    }
+
+    /// Sets whether to respect the user's "reduce motion" preference. Disabled by default.
+    ///
+    /// When motion should be reduced, the video doesn't autoplay; it shows its first frame as a poster
+    /// with a play button instead, until clicked or [`Video::set_paused`](crate::Video::set_paused)`(false)` is called.
+    pub fn respect_reduced_motion(self, respect_reduced_motion: bool) -> Self {
+        VideoPlayer {
+            respect_reduced_motion,
+            ..self
+        }
+    }
+
+    /// Sets what happens to playback while the window is unfocused (e.g., another app is in front),
+    /// [`FocusPolicy::Ignore`] by default.
+    ///
+    /// Playback is only taken up again if the app didn't pause (or duck) it meanwhile.
+    /// iced doesn't report windows being occluded, only unfocused.
+    pub fn focus_policy(self, focus_policy: FocusPolicy) -> Self {
+        VideoPlayer {
+            focus_policy,
+            ..self
+        }
+    }
+
+    /// Sets where the "reduce motion" preference is read from. Defaults to [`SystemMotionPreference`].
+    pub fn motion_preference(self, motion_preference: &'a dyn MotionPreference) -> Self {
+        VideoPlayer {
+            motion_preference,
+            ..self
+        }
+    }
+
+    /// Sets the [`QualityPolicy`] capping the variants adaptive streams (HLS, DASH) switch up to.
+    ///
+    /// Caps are re-evaluated while playing, at most every second.
+    pub fn quality_policy(self, quality_policy: QualityPolicy) -> Self {
+        VideoPlayer {
+            quality_policy,
+            ..self
+        }
+    }
+
+    /// Sets where [`QualityPolicy::battery_bitrate`] learns whether the device runs on battery.
+    /// Defaults to [`SystemPowerStatus`].
+    pub fn power_status(self, power_status: &'a dyn PowerStatus) -> Self {
+        VideoPlayer {
+            power_status,
+            ..self
+        }
+    }
+
+    /// Message to send when the [`QualityPolicy`] changes the cap on an adaptive stream's variants.
+    pub fn on_quality_capped<F>(self, on_quality_capped: F) -> Self
+    where
+        F: 'a + Fn(QualityCap) -> Message,
+    {
+        VideoPlayer {
+            on_quality_capped: Some(Box::new(on_quality_capped)),
+            ..self
+        }
+    }
+
+    /// Message to send with the index of the variant an adaptive stream switched to, in
+    /// [`Video::variants`](crate::Video::variants).
+    pub fn on_variant_changed<F>(self, on_variant_changed: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Message,
+    {
+        VideoPlayer {
+            on_variant_changed: Some(Box::new(on_variant_changed)),
+            ..self
+        }
+    }
+
     /// Message to send when the video reaches the end of stream (i.e., the video ends).
     pub fn on_end_of_stream(self, on_end_of_stream: Message) -> Self {
         VideoPlayer {
@@ -84,6 +466,48 @@ where
         }
     }
 
+    /// Message to send with the hash of each decoded frame, see [`Video::frame_hash`].
+    ///
+    /// Unlike [`VideoPlayer::on_new_frame`], repeated frames are reported too (with the same hash),
+    /// though frames decoded between two redraws are only reported once.
+    ///
+    /// Turns on [`Video::set_frame_hashing`] for the video.
+    pub fn on_frame_hash<F>(self, on_frame_hash: F) -> Self
+    where
+        F: 'a + Fn(u64) -> Message,
+    {
+        VideoPlayer {
+            on_frame_hash: Some(Box::new(on_frame_hash)),
+            ..self
+        }
+    }
+
+    /// Message to send with the metadata attached to a decoded frame, see [`Video::frame_metadata`].
+    ///
+    /// Of frames decoded between two redraws, only the latest one's metadata is reported.
+    pub fn on_frame_metadata<F>(self, on_frame_metadata: F) -> Self
+    where
+        F: 'a + Fn(FrameMetadata) -> Message,
+    {
+        VideoPlayer {
+            on_frame_metadata: Some(Box::new(on_frame_metadata)),
+            ..self
+        }
+    }
+
+    /// Message to send with the capture-to-display latency as frames of live sources are presented, see [`Video::latency`].
+    ///
+    /// Only sent while [`Video::set_measure_latency`] is on.
+    pub fn on_latency<F>(self, on_latency: F) -> Self
+    where
+        F: 'a + Fn(Duration) -> Message,
+    {
+        VideoPlayer {
+            on_latency: Some(Box::new(on_latency)),
+            ..self
+        }
+    }
+
     /// Message to send when the video receives a new frame.
     pub fn on_subtitle_text<F>(self, on_subtitle_text: F) -> Self
     where
@@ -95,23 +519,299 @@ where
         }
     }
 
+    /// Message to send with the pixel of the frame under the cursor whenever the cursor moves over the video,
+    /// e.g., for QA or camera calibration tools.
+    pub fn on_pixel_probe<F>(self, on_pixel_probe: F) -> Self
+    where
+        F: 'a + Fn(PixelProbe) -> Message,
+    {
+        VideoPlayer {
+            on_pixel_probe: Some(Box::new(on_pixel_probe)),
+            ..self
+        }
+    }
+
+    /// Sets if a loupe magnifying the pixels under the cursor is shown next to it.
+    pub fn loupe(self, loupe: bool) -> Self {
+        VideoPlayer { loupe, ..self }
+    }
+
+    /// Message to send when the closed caption on screen changes (`None` when it's cleared),
+    /// see [`VideoBuilder::closed_captions`](crate::VideoBuilder::closed_captions).
+    pub fn on_closed_caption<F>(self, on_closed_caption: F) -> Self
+    where
+        F: 'a + Fn(Option<String>) -> Message,
+    {
+        VideoPlayer {
+            on_closed_caption: Some(Box::new(on_closed_caption)),
+            ..self
+        }
+    }
+
     /// Message to send when the video playback encounters an error.
     pub fn on_error<F>(self, on_error: F) -> Self
     where
         F: 'a + Fn(&glib::Error) -> Message,
     {
         VideoPlayer {
-            on_error: Some(Box::new(on_error)),
+            on_error: Some(Box::new(move |err| on_error(&err))),
+            ..self
+        }
+    }
+
+    /// Message to send when streaming fails because of the network, classified into a [`NetworkError`].
+    ///
+    /// Sent in addition to [`VideoPlayer::on_error`].
+    pub fn on_network_error<F>(self, on_network_error: F) -> Self
+    where
+        F: 'a + Fn(NetworkError) -> Message,
+    {
+        VideoPlayer {
+            on_network_error: Some(Box::new(on_network_error)),
+            ..self
+        }
+    }
+
+    /// Message to send with how full the network buffer of a stream is (`0..=100`), as it fills,
+    /// e.g. to show a spinner below 100. Playback waits for the buffer, see [`Video::buffering`].
+    pub fn on_buffering<F>(self, on_buffering: F) -> Self
+    where
+        F: 'a + Fn(u8) -> Message,
+    {
+        VideoPlayer {
+            on_buffering: Some(Box::new(on_buffering)),
+            ..self
+        }
+    }
+
+    /// Message to send when an audio output device is added or removed.
+    ///
+    /// Audio may stop if the device it was playing on is removed;
+    /// this gives the app a chance to prompt the user or restart playback.
+    ///
+    /// Audio outputs are only watched once a player with this handler gets events, by a single
+    /// monitor shared between all videos; changes from before that aren't reported.
+    pub fn on_audio_device_changed<F>(self, on_audio_device_changed: F) -> Self
+    where
+        F: 'a + Fn(AudioDeviceEvent) -> Message,
+    {
+        VideoPlayer {
+            on_audio_device_changed: Some(Box::new(on_audio_device_changed)),
+            ..self
+        }
+    }
+
+    /// Message to send when playback enters (`Some((start, end))`) or leaves (`None`)
+    /// a [`SkipMode::Prompt`](crate::SkipMode::Prompt) segment, e.g., to show a "skip" button.
+    pub fn on_skip_segment<F>(self, on_skip_segment: F) -> Self
+    where
+        F: 'a + Fn(Option<(Duration, Duration)>) -> Message,
+    {
+        VideoPlayer {
+            on_skip_segment: Some(Box::new(on_skip_segment)),
+            ..self
+        }
+    }
+
+    /// Message to send when the current lyric line changes, see [`Video::set_lyrics`](crate::Video::set_lyrics).
+    pub fn on_lyric_line<F>(self, on_lyric_line: F) -> Self
+    where
+        F: 'a + Fn(Option<String>) -> Message,
+    {
+        VideoPlayer {
+            on_lyric_line: Some(Box::new(on_lyric_line)),
+            ..self
+        }
+    }
+
+    /// Message to send with the index of the new chapter (`None` between chapters) when playback
+    /// enters another chapter, see [`Video::chapters`](crate::Video::chapters).
+    pub fn on_chapter_changed<F>(self, on_chapter_changed: F) -> Self
+    where
+        F: 'a + Fn(Option<usize>) -> Message,
+    {
+        VideoPlayer {
+            on_chapter_changed: Some(Box::new(on_chapter_changed)),
+            ..self
+        }
+    }
+
+    /// Message to send with the data sample for every displayed frame, see [`Video::set_data_track`](crate::Video::set_data_track).
+    pub fn on_data_sample<F>(self, on_data_sample: F) -> Self
+    where
+        F: 'a + Fn(DataSample) -> Message,
+    {
+        VideoPlayer {
+            on_data_sample: Some(Box::new(on_data_sample)),
+            ..self
+        }
+    }
+
+    /// Message to send when playback skips over a [`Gap`] in the stream (e.g., a lost signal in a live stream).
+    pub fn on_discontinuity<F>(self, on_discontinuity: F) -> Self
+    where
+        F: 'a + Fn(Gap) -> Message,
+    {
+        VideoPlayer {
+            on_discontinuity: Some(Box::new(on_discontinuity)),
+            ..self
+        }
+    }
+
+    /// Message to send with the video's framerate once it is known, e.g., to match the display's refresh rate
+    /// to it (see [`Video::matching_refresh_rate`](crate::Video::matching_refresh_rate)) or enable variable refresh.
+    pub fn on_framerate<F>(self, on_framerate: F) -> Self
+    where
+        F: 'a + Fn(f64) -> Message,
+    {
+        VideoPlayer {
+            on_framerate: Some(Box::new(on_framerate)),
+            ..self
+        }
+    }
+
+    /// Message to send with the number of frames dropped since the last one,
+    /// because the UI couldn't draw them in time (e.g., during a window resize).
+    pub fn on_frames_dropped<F>(self, on_frames_dropped: F) -> Self
+    where
+        F: 'a + Fn(u32) -> Message,
+    {
+        VideoPlayer {
+            on_frames_dropped: Some(Box::new(on_frames_dropped)),
+            ..self
+        }
+    }
+
+    /// Message to send with the time left once playback is within `within` of the end,
+    /// every second until it ends (e.g., to count down to the next episode).
+    pub fn on_outro<F>(self, within: Duration, on_outro: F) -> Self
+    where
+        F: 'a + Fn(Duration) -> Message,
+    {
+        VideoPlayer {
+            on_outro: Some((within, Box::new(on_outro))),
+            ..self
+        }
+    }
+
+    /// Message to send with the playback position every `interval` during playback,
+    /// checked as frames are drawn, so it keeps in step with what is on screen
+    /// without polling [`Video::position`](crate::Video::position) on a timer.
+    pub fn on_position<F>(self, interval: Duration, on_position: F) -> Self
+    where
+        F: 'a + Fn(Duration) -> Message,
+    {
+        VideoPlayer {
+            on_position: Some((interval, Box::new(on_position))),
+            ..self
+        }
+    }
+
+    /// Message to send with the playback position once the last of a series of
+    /// [`Video::seek_smooth`](crate::Video::seek_smooth) calls finished.
+    pub fn on_seek_settled<F>(self, on_seek_settled: F) -> Self
+    where
+        F: 'a + Fn(Duration) -> Message,
+    {
+        VideoPlayer {
+            on_seek_settled: Some(Box::new(on_seek_settled)),
+            ..self
+        }
+    }
+
+    /// Message to send when playback continued with the next media, see [`Video::set_next_uri`](crate::Video::set_next_uri).
+    pub fn on_advance(self, on_advance: Message) -> Self {
+        VideoPlayer {
+            on_advance: Some(on_advance),
+            ..self
+        }
+    }
+
+    /// Message to send when the GPU device was lost (e.g., after a driver reset or suspend)
+    /// and the video was uploaded again to the new one.
+    pub fn on_renderer_reset(self, on_renderer_reset: Message) -> Self {
+        VideoPlayer {
+            on_renderer_reset: Some(on_renderer_reset),
             ..self
         }
     }
 }
 
+impl<'a, Message, Theme, Renderer> VideoPlayer<'a, Message, Theme, Renderer>
+where
+    Renderer: PrimitiveRenderer,
+{
+    fn framing(&self, inner: &Internal) -> Framing {
+        Framing {
+            size: inner.square_size(),
+            crop: self.crop.unwrap_or(iced::Rectangle::new(
+                iced::Point::ORIGIN,
+                iced::Size::new(1.0, 1.0),
+            )),
+            transform: inner.orientation().then(self.transform),
+            aspect_ratio: self.aspect_ratio,
+        }
+    }
+
+    /// The chapters of the media and the cue points, to mark on the seek bar of the controls.
+    fn cues(&self, inner: &Internal) -> Vec<Cue> {
+        let chapters = inner
+            .chapters
+            .lock()
+            .map(|chapters| chapters.clone())
+            .unwrap_or_default();
+        chapters
+            .into_iter()
+            .enumerate()
+            .map(|(i, chapter)| {
+                let label = chapter
+                    .title
+                    .unwrap_or_else(|| format!("Chapter {}", i + 1));
+                Cue::new(chapter.start, label)
+            })
+            .chain(self.cues.iter().cloned())
+            .collect()
+    }
+}
+
+/// How the frame is cut and turned before it is shown.
+#[derive(Debug, Clone, Copy)]
+struct Framing {
+    size: (i32, i32),
+    crop: iced::Rectangle,
+    transform: Transform,
+    aspect_ratio: Option<f32>,
+}
+
+impl Framing {
+    /// Size of the frame as shown.
+    fn size(&self) -> iced::Size {
+        let (width, height) = self.transform.display_size((
+            self.size.0 as f32 * self.crop.width,
+            self.size.1 as f32 * self.crop.height,
+        ));
+        match self.aspect_ratio {
+            // stretched horizontally, keeping the height
+            Some(aspect_ratio) => iced::Size::new(height * aspect_ratio, height),
+            None => iced::Size::new(width, height),
+        }
+    }
+
+    /// Where the point at `(x, y)` of the frame as shown is in the frame as decoded, both normalized.
+    fn source(&self, point: (f32, f32)) -> (f32, f32) {
+        let (x, y) = self.transform.source(point);
+        (
+            self.crop.x + x * self.crop.width,
+            self.crop.y + y * self.crop.height,
+        )
+    }
+}
+
 impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
     for VideoPlayer<'a, Message, Theme, Renderer>
 where
     Message: Clone,
-    Renderer: PrimitiveRenderer,
+    Renderer: PrimitiveRenderer + text::Renderer,
 {
     fn size(&self) -> iced::Size<iced::Length> {
         iced::Size {
@@ -120,16 +820,33 @@ where
         }
     }
 
+    fn tag(&self) -> widget::tree::Tag {
+        widget::tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> widget::tree::State {
+        let seek_bar: SeekBar<'_, Duration> = SeekBar::new(Duration::ZERO, Duration::ZERO, |at| at);
+        widget::tree::State::new(State {
+            seek_bar: widget::Tree::new(&seek_bar as &dyn Widget<Duration, Theme, Renderer>),
+        })
+    }
+
+    fn children(&self) -> Vec<widget::Tree> {
+        self.overlay.iter().map(widget::Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut widget::Tree) {
+        tree.diff_children(self.overlay.as_slice());
+    }
+
     fn layout(
         &self,
-        _tree: &mut widget::Tree,
-        _renderer: &Renderer,
+        tree: &mut widget::Tree,
+        renderer: &Renderer,
         limits: &layout::Limits,
     ) -> layout::Node {
-        let (video_width, video_height) = self.video.size();
-
         // based on `Image::layout`
-        let image_size = iced::Size::new(video_width as f32, video_height as f32);
+        let image_size = self.framing(&self.video.read()).size();
         let raw_size = limits.resolve(self.width, self.height, image_size);
         let full_size = self.content_fit.fit(image_size, raw_size);
         let final_size = iced::Size {
@@ -143,43 +860,49 @@ where
             },
         };
 
-        layout::Node::new(final_size)
+        // the overlay comes first, the controls last
+        let mut children = Vec::new();
+        if let Some(ref overlay) = self.overlay {
+            children.push(overlay.as_widget().layout(
+                &mut tree.children[0],
+                renderer,
+                &layout::Limits::new(iced::Size::ZERO, final_size),
+            ));
+        }
+        if self.controls {
+            let state = tree.state.downcast_mut::<State>();
+            let seek_bar: SeekBar<'_, Duration> =
+                SeekBar::new(Duration::ZERO, Duration::ZERO, |at| at);
+            let width = (final_size.width - CONTROLS_PADDING * 2.0).max(0.0);
+            let node = Widget::<Duration, Theme, Renderer>::layout(
+                &seek_bar,
+                &mut state.seek_bar,
+                renderer,
+                &layout::Limits::new(iced::Size::ZERO, iced::Size::new(width, final_size.height)),
+            );
+            let height = node.size().height;
+            children.push(node.move_to(iced::Point::new(
+                CONTROLS_PADDING,
+                (final_size.height - height - CONTROLS_PADDING).max(0.0),
+            )));
+        }
+        layout::Node::with_children(final_size, children)
     }
 
     fn draw(
         &self,
-        _tree: &widget::Tree,
+        tree: &widget::Tree,
         renderer: &mut Renderer,
-        _theme: &Theme,
-        _style: &advanced::renderer::Style,
+        theme: &Theme,
+        style: &advanced::renderer::Style,
         layout: advanced::Layout<'_>,
-        _cursor: advanced::mouse::Cursor,
-        _viewport: &iced::Rectangle,
+        cursor: advanced::mouse::Cursor,
+        viewport: &iced::Rectangle,
     ) {
         let mut inner = self.video.write();
 
-        // bounds based on `Image::draw`
-        let image_size = iced::Size::new(inner.width as f32, inner.height as f32);
         let bounds = layout.bounds();
-        let adjusted_fit = self.content_fit.fit(image_size, bounds.size());
-        let scale = iced::Vector::new(
-            adjusted_fit.width / image_size.width,
-            adjusted_fit.height / image_size.height,
-        );
-        let final_size = image_size * scale;
-
-        let position = match self.content_fit {
-            iced::ContentFit::None => iced::Point::new(
-                bounds.x + (image_size.width - adjusted_fit.width) / 2.0,
-                bounds.y + (image_size.height - adjusted_fit.height) / 2.0,
-            ),
-            _ => iced::Point::new(
-                bounds.center_x() - final_size.width / 2.0,
-                bounds.center_y() - final_size.height / 2.0,
-            ),
-        };
-
-        let drawing_bounds = iced::Rectangle::new(position, final_size);
+        let drawing_bounds = drawing_bounds(self.content_fit, self.framing(&inner).size(), bounds);
 
         let upload_frame = inner.upload_frame.swap(false, Ordering::SeqCst);
 
@@ -189,36 +912,330 @@ where
                 .lock()
                 .map(|time| *time)
                 .unwrap_or_else(|_| Instant::now());
-            inner.set_av_offset(Instant::now() - last_frame_time);
+            // the AV offset is measured once the frame is presented, see `on_event`
+            inner.presenting_frame_time = Some(last_frame_time);
+            inner.presenting_capture = inner.capture_time.lock().ok().and_then(|time| *time);
+            inner.upload_time = Instant::now();
+        }
+
+        let blend = match self.interpolation {
+            Interpolation::None => 1.0,
+            Interpolation::Blend => {
+                let frame_duration = 1.0 / inner.framerate as f32;
+                (inner.upload_time.elapsed().as_secs_f32() / frame_duration).min(1.0)
+            }
+        };
+
+        if let Some(ref styled_subtitles) = inner.styled_subtitles {
+            // rendered as big as they're drawn, so they stay sharp however the frames are scaled
+            let scale = f32::from_bits(inner.scale_factor.load(Ordering::SeqCst));
+            styled_subtitles.set_drawn_size((
+                (drawing_bounds.width * scale).round() as u32,
+                (drawing_bounds.height * scale).round() as u32,
+            ));
         }
 
         renderer.draw_primitive(
             drawing_bounds,
             VideoPrimitive::new(
-                inner.id,
-                Arc::clone(&inner.alive),
-                Arc::clone(&inner.frame),
-                (inner.width as _, inner.height as _),
+                &inner,
                 upload_frame,
+                DrawSettings {
+                    blend,
+                    interpolate: self.interpolation == Interpolation::Blend,
+                    // even paused, a locked video shouldn't show what it is
+                    obscure: inner.content_gate.is_some(),
+                    filter: self.filter,
+                    transfer: inner.transfer,
+                    tone_mapping: self.tone_mapping,
+                    color_balance: inner.color_balance,
+                    lut: inner.lut.clone(),
+                    transform: inner.orientation().then(self.transform),
+                    crop: self.crop,
+                    annotations: if self.guides.is_empty() {
+                        annotation::tessellate(self.annotations, drawing_bounds.size())
+                    } else {
+                        let mut annotations = self.guides.annotations(drawing_bounds.size());
+                        annotations.extend_from_slice(self.annotations);
+                        annotation::tessellate(&annotations, drawing_bounds.size())
+                    },
+                    watermark: self
+                        .watermark
+                        .as_ref()
+                        .map(|watermark| (watermark.clone(), watermark.offset())),
+                    subtitles: inner
+                        .styled_subtitles
+                        .as_ref()
+                        .and_then(|styled_subtitles| styled_subtitles.image.lock().ok()?.clone()),
+                },
             ),
         );
+
+        // text is drawn after primitives, so labels end up above the frame like the other annotations
+        let clip_bounds = bounds.intersection(&drawing_bounds).unwrap_or(bounds);
+        for annotation in self.annotations {
+            if let Annotation::Text {
+                position,
+                content,
+                size,
+                color,
+            } = annotation
+            {
+                renderer.fill_text(
+                    advanced::Text {
+                        content: content.clone(),
+                        bounds: drawing_bounds.size(),
+                        size: iced::Pixels(*size),
+                        line_height: text::LineHeight::default(),
+                        font: renderer.default_font(),
+                        horizontal_alignment: iced::alignment::Horizontal::Left,
+                        vertical_alignment: iced::alignment::Vertical::Top,
+                        shaping: text::Shaping::Advanced,
+                        wrapping: text::Wrapping::None,
+                    },
+                    iced::Point::new(
+                        drawing_bounds.x + position.x * drawing_bounds.width,
+                        drawing_bounds.y + position.y * drawing_bounds.height,
+                    ),
+                    *color,
+                    clip_bounds,
+                );
+            }
+        }
+
+        if inner.motion_poster && inner.paused() {
+            // a layer of its own, as quads would otherwise go below the frame
+            renderer.with_layer(bounds, |renderer| {
+                draw_play_button(renderer, drawing_bounds);
+            });
+        }
+
+        if self.loupe {
+            if let Some(position) = cursor.position_over(drawing_bounds) {
+                if let Some(probe) = probe(&inner, self.framing(&inner), drawing_bounds, position) {
+                    // a layer of its own, as quads would otherwise go below the frame
+                    renderer.with_layer(bounds, |renderer| {
+                        draw_loupe(renderer, &inner, probe, position, bounds);
+                    });
+                }
+            }
+        }
+
+        if let Some(controls_layout) = layout.children().last().filter(|_| self.controls) {
+            let cues = self.cues(&inner);
+            let seek_bar =
+                SeekBar::new(inner.position(), inner.duration, |at| at).cue_points(&cues);
+            let state = tree.state.downcast_ref::<State>();
+            // a layer of its own, as quads would otherwise go below the frame
+            renderer.with_layer(bounds, |renderer| {
+                Widget::<Duration, Theme, Renderer>::draw(
+                    &seek_bar,
+                    &state.seek_bar,
+                    renderer,
+                    theme,
+                    style,
+                    controls_layout,
+                    cursor,
+                    viewport,
+                );
+            });
+        }
+
+        // the overlay may look at the video too
+        drop(inner);
+        if let Some((overlay, overlay_layout)) = self.overlay.as_ref().zip(layout.children().next())
+        {
+            renderer.with_layer(bounds, |renderer| {
+                overlay.as_widget().draw(
+                    &tree.children[0],
+                    renderer,
+                    theme,
+                    style,
+                    overlay_layout,
+                    cursor,
+                    viewport,
+                );
+            });
+        }
     }
 
     fn on_event(
         &mut self,
-        _state: &mut widget::Tree,
+        tree: &mut widget::Tree,
         event: iced::Event,
-        _layout: advanced::Layout<'_>,
-        _cursor: advanced::mouse::Cursor,
-        _renderer: &Renderer,
-        _clipboard: &mut dyn advanced::Clipboard,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn advanced::Clipboard,
         shell: &mut advanced::Shell<'_, Message>,
-        _viewport: &iced::Rectangle,
+        viewport: &iced::Rectangle,
     ) -> Status {
+        if let Some((overlay, overlay_layout)) = self.overlay.as_mut().zip(layout.children().next())
+        {
+            let status = overlay.as_widget_mut().on_event(
+                &mut tree.children[0],
+                event.clone(),
+                overlay_layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+            // redraws keep playback going, whoever handles them
+            let redraw = matches!(
+                event,
+                iced::Event::Window(iced::window::Event::RedrawRequested(_))
+            );
+            if status == Status::Captured && !redraw {
+                return Status::Captured;
+            }
+        }
+
+        if let Some(controls_layout) = layout.children().last().filter(|_| self.controls) {
+            let inner = self.video.read();
+            let cues = self.cues(&inner);
+            let mut seek_bar =
+                SeekBar::new(inner.position(), inner.duration, |at| at).cue_points(&cues);
+            drop(inner);
+            let state = tree.state.downcast_mut::<State>();
+            let mut seeks = Vec::new();
+            let status = Widget::<Duration, Theme, Renderer>::on_event(
+                &mut seek_bar,
+                &mut state.seek_bar,
+                event.clone(),
+                controls_layout,
+                cursor,
+                renderer,
+                clipboard,
+                &mut advanced::Shell::new(&mut seeks),
+                viewport,
+            );
+            // only the latest position matters while dragging
+            if let Some(position) = seeks.pop() {
+                let mut inner = self.video.write();
+                inner.debounce_scrub();
+                if let Err(err) = inner.seek_smooth(position.into()) {
+                    error!("cannot seek: {err:#?}");
+                }
+                shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+            }
+            if status == Status::Captured {
+                return Status::Captured;
+            }
+        }
+
         let mut inner = self.video.write();
 
-        if let iced::Event::Window(iced::window::Event::RedrawRequested(_)) = event {
-            if inner.restart_stream || (!inner.is_eos && !inner.paused()) {
+        match event {
+            iced::Event::Window(iced::window::Event::Unfocused) => {
+                inner.unfocus(self.focus_policy);
+                return Status::Ignored;
+            }
+            iced::Event::Window(iced::window::Event::Focused) => {
+                inner.refocus();
+                return Status::Ignored;
+            }
+            _ => {}
+        }
+
+        if let iced::Event::Mouse(advanced::mouse::Event::CursorMoved { .. }) = event {
+            if let Some(ref on_pixel_probe) = self.on_pixel_probe {
+                let drawing_bounds = drawing_bounds(
+                    self.content_fit,
+                    self.framing(&inner).size(),
+                    layout.bounds(),
+                );
+                if let Some(position) = cursor.position_over(drawing_bounds) {
+                    if let Some(probe) =
+                        probe(&inner, self.framing(&inner), drawing_bounds, position)
+                    {
+                        shell.publish(on_pixel_probe(probe));
+                    }
+                }
+            }
+            if self.loupe {
+                shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+            }
+            return Status::Ignored;
+        }
+
+        if let iced::Event::Mouse(advanced::mouse::Event::ButtonPressed(
+            advanced::mouse::Button::Left,
+        )) = event
+        {
+            if inner.motion_poster && cursor.is_over(layout.bounds()) {
+                inner.motion_poster = false;
+                inner.motion_allowed = true;
+                inner.set_paused(false);
+                shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                return Status::Captured;
+            }
+            return Status::Ignored;
+        }
+
+        if let iced::Event::Window(iced::window::Event::RedrawRequested(now)) = event {
+            // iced doesn't expose presentation feedback, but the next redraw only happens after the
+            // last frame was presented (with vsync), so it bounds when the frame actually got on screen,
+            // up to one vsync interval late
+            if let Some(frame_time) = inner.presenting_frame_time.take() {
+                let presented = now.saturating_duration_since(frame_time);
+                inner.set_av_offset(presented);
+                let latency = inner
+                    .presenting_capture
+                    .take()
+                    .and_then(|captured| SystemTime::now().duration_since(captured).ok());
+                if let Some(latency) = latency {
+                    inner.record_latency(latency);
+                    if let (Some(on_latency), Some(latency)) = (&self.on_latency, inner.latency) {
+                        shell.publish(on_latency(latency));
+                    }
+                }
+            }
+
+            if self.respect_reduced_motion
+                && !inner.motion_allowed
+                && !inner.motion_poster
+                && !inner.paused()
+                && self.motion_preference.reduce_motion()
+            {
+                inner.set_paused(true);
+                inner.motion_poster = true;
+            }
+
+            inner.update_url_refresh();
+            inner.update_size();
+
+            if let Some(position) = inner.update_smooth_seek() {
+                if let Some(ref on_seek_settled) = self.on_seek_settled {
+                    shell.publish(on_seek_settled(position));
+                }
+            }
+
+            if inner.reported_framerate != Some(inner.framerate) {
+                if let Some(ref on_framerate) = self.on_framerate {
+                    inner.reported_framerate = Some(inner.framerate);
+                    shell.publish(on_framerate(inner.framerate));
+                }
+            }
+
+            // audio outputs are only watched for videos whose player wants to know
+            if let Some(ref on_audio_device_changed) = self.on_audio_device_changed {
+                let audio_devices = inner
+                    .audio_devices
+                    .get_or_insert_with(AudioDevices::subscribe);
+                while let Some(event) = audio_devices.pop() {
+                    shell.publish(on_audio_device_changed(event));
+                }
+            }
+
+            // while buffering, playback is paused but messages still have to come in to resume it,
+            // and a restarted pipeline has to report it prerolled to be sought back, even paused
+            if inner.restart_stream
+                || inner.buffering.is_some()
+                || inner.restart_position.is_some()
+                || (!inner.is_eos && !inner.paused())
+            {
                 let mut restart_stream = false;
                 if inner.restart_stream {
                     restart_stream = true;
@@ -227,16 +1244,37 @@ where
                 }
                 let mut eos_pause = false;
 
-                while let Some(msg) = inner
-                    .bus
-                    .pop_filtered(&[gst::MessageType::Error, gst::MessageType::Eos])
-                {
+                while let Some(msg) = inner.bus.pop_filtered(&[
+                    gst::MessageType::Error,
+                    gst::MessageType::Eos,
+                    gst::MessageType::SegmentDone,
+                    gst::MessageType::Buffering,
+                    gst::MessageType::Tag,
+                    gst::MessageType::Element,
+                    gst::MessageType::StreamStart,
+                    gst::MessageType::DurationChanged,
+                    gst::MessageType::AsyncDone,
+                ]) {
+                    inner.report_analytics(&msg);
+                    if let Some(index) = inner.update_variant(&msg) {
+                        if let Some(ref on_variant_changed) = self.on_variant_changed {
+                            shell.publish(on_variant_changed(index));
+                        }
+                    }
                     match msg.view() {
                         gst::MessageView::Error(err) => {
                             error!("bus returned an error: {err}");
                             if let Some(ref on_error) = self.on_error {
-                                shell.publish(on_error(&err.error()))
+                                shell.publish(on_error(err.error()))
                             };
+                            if let Some(network_error) = NetworkError::from_message(err) {
+                                if network_error.kind == NetworkErrorKind::Unauthorized {
+                                    inner.request_url_refresh();
+                                }
+                                if let Some(ref on_network_error) = self.on_network_error {
+                                    shell.publish(on_network_error(network_error));
+                                }
+                            }
                         }
                         gst::MessageView::Eos(_eos) => {
                             if let Some(on_end_of_stream) = self.on_end_of_stream.clone() {
@@ -248,6 +1286,26 @@ where
                                 eos_pause = true;
                             }
                         }
+                        gst::MessageView::SegmentDone(_) => inner.restart_loop_region(),
+                        gst::MessageView::Buffering(buffering) => {
+                            let percent = buffering.percent();
+                            inner.buffer(percent, buffering.buffering_stats().0);
+                            if let Some(ref on_buffering) = self.on_buffering {
+                                shell.publish(on_buffering(percent.clamp(0, 100) as u8));
+                            }
+                        }
+                        gst::MessageView::Element(element) => {
+                            if let Some(stats) = element.structure() {
+                                inner.quality.fragment(stats);
+                            }
+                        }
+                        gst::MessageView::StreamStart(_) if inner.update_advanced() => {
+                            if let Some(on_advance) = self.on_advance.clone() {
+                                shell.publish(on_advance);
+                            }
+                        }
+                        gst::MessageView::DurationChanged(_) => inner.update_duration(),
+                        gst::MessageView::AsyncDone(_) => inner.finish_restart(),
                         _ => {}
                     }
                 }
@@ -268,6 +1326,111 @@ where
                     }
                 }
 
+                if let Some(ref on_frame_hash) = self.on_frame_hash {
+                    inner.frame_hashing.store(true, Ordering::SeqCst);
+                    if inner.hash_updated.swap(false, Ordering::SeqCst) {
+                        if let Some(hash) = inner.frame_hash.lock().ok().and_then(|hash| *hash) {
+                            shell.publish(on_frame_hash(hash));
+                        }
+                    }
+                }
+
+                if let Some(ref on_frame_metadata) = self.on_frame_metadata {
+                    if inner.metadata_updated.swap(false, Ordering::SeqCst) {
+                        if let Some(metadata) = inner
+                            .frame_metadata
+                            .lock()
+                            .ok()
+                            .and_then(|latest| latest.clone())
+                        {
+                            shell.publish(on_frame_metadata(metadata));
+                        }
+                    }
+                }
+
+                if let Some(ref on_frames_dropped) = self.on_frames_dropped {
+                    let dropped = inner.frames_dropped.swap(0, Ordering::SeqCst);
+                    if dropped > 0 {
+                        shell.publish(on_frames_dropped(dropped));
+                    }
+                }
+
+                inner.update_speed_ramp();
+                inner.update_rate_map();
+                inner.update_scrub_fade();
+                inner.update_ducking();
+                inner.sync_secondary_audio();
+                inner.sync_track_meters();
+                // variants are measured in physical pixels
+                let height = drawing_bounds(
+                    self.content_fit,
+                    self.framing(&inner).size(),
+                    layout.bounds(),
+                )
+                .height
+                    * f32::from_bits(inner.scale_factor.load(Ordering::SeqCst));
+                if let Some(cap) =
+                    inner
+                        .quality
+                        .update(&self.quality_policy, height, self.power_status)
+                {
+                    if let Some(ref on_quality_capped) = self.on_quality_capped {
+                        shell.publish(on_quality_capped(cap));
+                    }
+                }
+                #[cfg(feature = "media-session")]
+                inner.update_media_session();
+
+                if inner.renderer_reset.swap(false, Ordering::SeqCst) {
+                    if let Some(on_renderer_reset) = self.on_renderer_reset.clone() {
+                        shell.publish(on_renderer_reset);
+                    }
+                }
+
+                if let Some((within, ref on_outro)) = self.on_outro {
+                    if let Some(remaining) = inner.update_outro(within) {
+                        shell.publish(on_outro(remaining));
+                    }
+                }
+
+                if let Some((interval, ref on_position)) = self.on_position {
+                    if let Some(position) = inner.update_position(interval) {
+                        shell.publish(on_position(position));
+                    }
+                }
+
+                if let Some(segment) = inner.update_skip_segment() {
+                    if let Some(ref on_skip_segment) = self.on_skip_segment {
+                        shell.publish(on_skip_segment(segment));
+                    }
+                }
+
+                if let Ok(mut gaps) = inner.gaps.try_lock() {
+                    for gap in gaps.drain(..) {
+                        if let Some(ref on_discontinuity) = self.on_discontinuity {
+                            shell.publish(on_discontinuity(gap));
+                        }
+                    }
+                }
+
+                if let Some(chapter) = inner.update_chapter() {
+                    if let Some(ref on_chapter_changed) = self.on_chapter_changed {
+                        shell.publish(on_chapter_changed(chapter));
+                    }
+                }
+
+                if let Some(line) = inner.update_lyric_line() {
+                    if let Some(ref on_lyric_line) = self.on_lyric_line {
+                        shell.publish(on_lyric_line(line));
+                    }
+                }
+
+                if let Some(ref on_data_sample) = self.on_data_sample {
+                    if let Some(sample) = inner.update_data_sample() {
+                        shell.publish(on_data_sample(sample));
+                    }
+                }
+
                 if let Some(on_subtitle_text) = &self.on_subtitle_text {
                     if inner.upload_text.swap(false, Ordering::SeqCst) {
                         if let Ok(text) = inner.subtitle_text.try_lock() {
@@ -276,7 +1439,24 @@ where
                     }
                 }
 
-                shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                if let Some(on_closed_caption) = &self.on_closed_caption {
+                    if inner.captions.updated.swap(false, Ordering::SeqCst) {
+                        if let Ok(text) = inner.captions.text.try_lock() {
+                            shell.publish(on_closed_caption(text.clone()));
+                        }
+                    }
+                }
+
+                if inner.static_content.load(Ordering::SeqCst)
+                    && self.interpolation == Interpolation::None
+                {
+                    // nothing changed on screen lately, so just check back by the next frame
+                    shell.request_redraw(iced::window::RedrawRequest::At(
+                        Instant::now() + Duration::from_secs_f64(1.0 / inner.framerate),
+                    ));
+                } else {
+                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                }
             } else {
                 shell.request_redraw(iced::window::RedrawRequest::At(
                     Instant::now() + Duration::from_millis(32),
@@ -287,6 +1467,249 @@ where
             Status::Ignored
         }
     }
+
+    fn mouse_interaction(
+        &self,
+        tree: &widget::Tree,
+        layout: advanced::Layout<'_>,
+        cursor: advanced::mouse::Cursor,
+        viewport: &iced::Rectangle,
+        renderer: &Renderer,
+    ) -> advanced::mouse::Interaction {
+        let overlay_interaction = self
+            .overlay
+            .as_ref()
+            .zip(layout.children().next())
+            .map(|(overlay, overlay_layout)| {
+                overlay.as_widget().mouse_interaction(
+                    &tree.children[0],
+                    overlay_layout,
+                    cursor,
+                    viewport,
+                    renderer,
+                )
+            })
+            .unwrap_or_default();
+        let over_controls = layout
+            .children()
+            .last()
+            .filter(|_| self.controls)
+            .is_some_and(|controls_layout| cursor.is_over(controls_layout.bounds()));
+        if overlay_interaction != advanced::mouse::Interaction::None {
+            overlay_interaction
+        } else if over_controls
+            || (self.video.read().motion_poster && cursor.is_over(layout.bounds()))
+        {
+            advanced::mouse::Interaction::Pointer
+        } else {
+            advanced::mouse::Interaction::None
+        }
+    }
+
+    fn operate(
+        &self,
+        tree: &mut widget::Tree,
+        layout: advanced::Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation,
+    ) {
+        if let Some((overlay, overlay_layout)) = self.overlay.as_ref().zip(layout.children().next())
+        {
+            overlay
+                .as_widget()
+                .operate(&mut tree.children[0], overlay_layout, renderer, operation);
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut widget::Tree,
+        layout: advanced::Layout<'_>,
+        renderer: &Renderer,
+        translation: iced::Vector,
+    ) -> Option<advanced::overlay::Element<'b, Message, Theme, Renderer>> {
+        let (overlay, overlay_layout) = self.overlay.as_mut().zip(layout.children().next())?;
+        overlay.as_widget_mut().overlay(
+            &mut tree.children[0],
+            overlay_layout,
+            renderer,
+            translation,
+        )
+    }
+}
+
+/// Where the frame, shown `image_size` big, is drawn within `bounds`, based on `Image::draw`.
+fn drawing_bounds(
+    content_fit: iced::ContentFit,
+    image_size: iced::Size,
+    bounds: iced::Rectangle,
+) -> iced::Rectangle {
+    let adjusted_fit = content_fit.fit(image_size, bounds.size());
+    let scale = iced::Vector::new(
+        adjusted_fit.width / image_size.width,
+        adjusted_fit.height / image_size.height,
+    );
+    let final_size = image_size * scale;
+
+    let position = match content_fit {
+        iced::ContentFit::None => iced::Point::new(
+            bounds.x + (image_size.width - adjusted_fit.width) / 2.0,
+            bounds.y + (image_size.height - adjusted_fit.height) / 2.0,
+        ),
+        _ => iced::Point::new(
+            bounds.center_x() - final_size.width / 2.0,
+            bounds.center_y() - final_size.height / 2.0,
+        ),
+    };
+
+    iced::Rectangle::new(position, final_size)
+}
+
+/// The pixel of the frame drawn at `position` within `drawing_bounds`.
+fn probe(
+    inner: &Internal,
+    framing: Framing,
+    drawing_bounds: iced::Rectangle,
+    position: iced::Point,
+) -> Option<PixelProbe> {
+    let frame = inner.frame.latest();
+    let (width, height) = frame.size();
+    let (x, y) = framing.source((
+        (position.x - drawing_bounds.x) / drawing_bounds.width,
+        (position.y - drawing_bounds.y) / drawing_bounds.height,
+    ));
+    let (x, y) = (x * width as f32, y * height as f32);
+    pixel_probe::pixel_at(&frame, inner.format, width, height, x as u32, y as u32)
+}
+
+/// Draws the pixels around `probe` magnified next to the cursor, the probed one outlined.
+fn draw_loupe<Renderer: advanced::Renderer>(
+    renderer: &mut Renderer,
+    inner: &Internal,
+    probe: PixelProbe,
+    cursor: iced::Point,
+    bounds: iced::Rectangle,
+) {
+    const RADIUS: i64 = 5;
+    const CELL: f32 = 8.0;
+
+    let side = (RADIUS * 2 + 1) as f32 * CELL;
+    // beside the cursor, flipped to the other side near the edges
+    let mut origin = cursor + iced::Vector::new(16.0, 16.0);
+    if origin.x + side > bounds.x + bounds.width {
+        origin.x = cursor.x - 16.0 - side;
+    }
+    if origin.y + side > bounds.y + bounds.height {
+        origin.y = cursor.y - 16.0 - side;
+    }
+
+    renderer.fill_quad(
+        advanced::renderer::Quad {
+            bounds: iced::Rectangle::new(origin, iced::Size::new(side, side)).expand(2.0),
+            ..Default::default()
+        },
+        iced::Color::WHITE,
+    );
+
+    let frame = inner.frame.latest();
+    let (width, height) = frame.size();
+    for dy in -RADIUS..=RADIUS {
+        for dx in -RADIUS..=RADIUS {
+            let (x, y) = (probe.x as i64 + dx, probe.y as i64 + dy);
+            let color = (x >= 0 && y >= 0)
+                .then(|| {
+                    pixel_probe::pixel_at(&frame, inner.format, width, height, x as u32, y as u32)
+                })
+                .flatten()
+                .map_or(iced::Color::BLACK, |pixel| {
+                    iced::Color::from_rgb8(pixel.rgb[0], pixel.rgb[1], pixel.rgb[2])
+                });
+            let cell = iced::Rectangle {
+                x: origin.x + (dx + RADIUS) as f32 * CELL,
+                y: origin.y + (dy + RADIUS) as f32 * CELL,
+                width: CELL,
+                height: CELL,
+            };
+            let border = if dx == 0 && dy == 0 {
+                iced::Border {
+                    color: iced::Color::WHITE,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                }
+            } else {
+                iced::Border::default()
+            };
+            renderer.fill_quad(
+                advanced::renderer::Quad {
+                    bounds: cell,
+                    border,
+                    ..Default::default()
+                },
+                color,
+            );
+        }
+    }
+}
+
+/// Draws a round play button in the middle of `bounds`, with the triangle made of thin slices.
+fn draw_play_button<Renderer: advanced::Renderer>(
+    renderer: &mut Renderer,
+    bounds: iced::Rectangle,
+) {
+    const SLICES: usize = 12;
+
+    let radius = (bounds.width.min(bounds.height) / 8.0).clamp(16.0, 48.0);
+    let center = bounds.center();
+
+    renderer.fill_quad(
+        advanced::renderer::Quad {
+            bounds: iced::Rectangle {
+                x: center.x - radius,
+                y: center.y - radius,
+                width: radius * 2.0,
+                height: radius * 2.0,
+            },
+            border: iced::border::rounded(radius),
+            ..Default::default()
+        },
+        iced::Color::BLACK.scale_alpha(0.6),
+    );
+
+    let side = radius * 0.9;
+    let left = center.x - side * 0.35;
+    let slice_width = side / SLICES as f32;
+    for i in 0..SLICES {
+        let height = side * (1.0 - i as f32 / SLICES as f32);
+        renderer.fill_quad(
+            advanced::renderer::Quad {
+                bounds: iced::Rectangle {
+                    x: left + i as f32 * slice_width,
+                    y: center.y - height / 2.0,
+                    width: slice_width,
+                    height,
+                },
+                ..Default::default()
+            },
+            iced::Color::WHITE,
+        );
+    }
+}
+
+impl<'a, Message, Theme, Renderer> VideoPlayer<'a, Message, Theme, Renderer>
+where
+    Message: 'a + Clone,
+    Theme: 'a,
+    Renderer: 'a + PrimitiveRenderer + text::Renderer,
+{
+    /// Tags every message the player emits with the [`VideoId`](crate::VideoId) of its video,
+    /// so apps playing many videos can route them, see [`VideoRegistry`](crate::VideoRegistry).
+    pub fn with_id<B: 'a>(
+        self,
+        f: impl Fn(crate::VideoId, Message) -> B + 'a,
+    ) -> Element<'a, B, Theme, Renderer> {
+        let id = self.video.id();
+        Element::from(self).map(move |message| f(id, message))
+    }
 }
 
 impl<'a, Message, Theme, Renderer> From<VideoPlayer<'a, Message, Theme, Renderer>>
@@ -294,7 +1717,7 @@ impl<'a, Message, Theme, Renderer> From<VideoPlayer<'a, Message, Theme, Renderer
 where
     Message: 'a + Clone,
     Theme: 'a,
-    Renderer: 'a + PrimitiveRenderer,
+    Renderer: 'a + PrimitiveRenderer + text::Renderer,
 {
     fn from(video_player: VideoPlayer<'a, Message, Theme, Renderer>) -> Self {
         Self::new(video_player)
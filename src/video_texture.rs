@@ -0,0 +1,247 @@
+use crate::frame::PixelFormat;
+use crate::pipeline::{ColorBalance, DrawSettings, OutputEncoding, Transfer, VideoPipeline};
+use crate::{Filter, Lut, ToneMapping, Video};
+use iced_wgpu::wgpu;
+
+/// The current frame of a [`Video`] as an RGBA `wgpu` texture, for drawing the video
+/// in custom shaders or scenes (e.g., on a surface in a 3D preview) instead of through a [`VideoPlayer`](crate::VideoPlayer).
+///
+/// Call [`VideoTexture::update`] before rendering each frame of the scene.
+/// Frames are drawn as decoded, not transformed by the stream's orientation tag (see [`Video::display_size`]).
+/// For the CPU path, see [`Video::current_frame`].
+///
+/// For audio-only media with [`Video::set_visualization`], the visualization takes the place of the video;
+/// see [`VideoTexture::visualization`] for a visualization next to the video.
+pub struct VideoTexture {
+    pipeline: VideoPipeline,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    size: (u32, u32),
+    format: wgpu::TextureFormat,
+    linear: bool,
+    filter: Filter,
+    tone_mapping: ToneMapping,
+    /// The color balance of the last frame drawn, which may change without a new frame.
+    color_balance: ColorBalance,
+    /// Id of the lookup table the last frame was drawn with, likewise.
+    lut: Option<u64>,
+    visualization: bool,
+    generation: Option<u64>,
+}
+
+impl std::fmt::Debug for VideoTexture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VideoTexture")
+            .field("texture", &self.texture)
+            .field("size", &self.size)
+            .field("filter", &self.filter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VideoTexture {
+    /// Default format of the texture; sampling it yields linear RGB.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// Creates a texture the size of `video`'s frames, in [`VideoTexture::FORMAT`].
+    pub fn new(device: &wgpu::Device, video: &Video) -> Self {
+        Self::with_format(device, video, Self::FORMAT, OutputEncoding::Auto)
+    }
+
+    /// Creates a texture the size of `video`'s frames in the given `format`, for render pipelines that expect
+    /// another format (e.g., a float format for HDR compositing), with colors written as `encoding` says.
+    ///
+    /// The format has to be usable as a render attachment.
+    pub fn with_format(
+        device: &wgpu::Device,
+        video: &Video,
+        format: wgpu::TextureFormat,
+        encoding: OutputEncoding,
+    ) -> Self {
+        let (width, height) = video.decoded_size();
+        Self::create(
+            device,
+            (width as u32, height as u32),
+            format,
+            encoding,
+            false,
+        )
+    }
+
+    /// Creates a texture for the frames of the visualization set up with
+    /// [`VideoBuilder::visualization_output`](crate::VideoBuilder::visualization_output),
+    /// in [`VideoTexture::FORMAT`]. Returns `None` if there is none.
+    ///
+    /// Filters, tone mapping and color balance don't apply to visualizations.
+    pub fn visualization(device: &wgpu::Device, video: &Video) -> Option<Self> {
+        let size = video.read().visualizer.as_ref()?.size;
+        Some(Self::create(
+            device,
+            size,
+            Self::FORMAT,
+            OutputEncoding::Auto,
+            true,
+        ))
+    }
+
+    fn create(
+        device: &wgpu::Device,
+        size: (u32, u32),
+        format: wgpu::TextureFormat,
+        encoding: OutputEncoding,
+        visualization: bool,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("iced_video_player render target"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        VideoTexture {
+            pipeline: VideoPipeline::new(device, format, encoding),
+            texture,
+            view,
+            size,
+            format,
+            linear: encoding.is_linear(format),
+            filter: Filter::None,
+            tone_mapping: ToneMapping::default(),
+            color_balance: ColorBalance::default(),
+            lut: None,
+            visualization,
+            generation: None,
+        }
+    }
+
+    /// Format of the texture.
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Whether sampling the texture yields linear RGB, rather than sRGB-encoded values.
+    pub fn is_linear(&self) -> bool {
+        self.linear
+    }
+
+    /// Sets the accessibility [`Filter`] applied to frames.
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.generation = None;
+    }
+
+    /// Sets the [`ToneMapping`] applied to HDR frames.
+    pub fn set_tone_mapping(&mut self, tone_mapping: ToneMapping) {
+        self.tone_mapping = tone_mapping;
+        self.generation = None;
+    }
+
+    /// Renders the latest frame of `video` into the texture, if it changed since the last update.
+    ///
+    /// Returns whether the texture changed. The texture isn't updated while the video is
+    /// [capture protected](Video::set_capture_protected).
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, video: &Video) -> bool {
+        let inner = video.read();
+        let (frames, format, transfer) = if self.visualization {
+            let Some(ref visualizer) = inner.visualizer else {
+                return false;
+            };
+            (&visualizer.frame, PixelFormat::Nv12, Transfer::Sdr)
+        } else {
+            (&inner.frame, inner.format, inner.transfer)
+        };
+        let generation = frames.generation();
+        let color_balance = if self.visualization {
+            ColorBalance::default()
+        } else {
+            inner.color_balance
+        };
+        let lut = inner.lut.clone().filter(|_| !self.visualization);
+        let lut_id = lut.as_ref().map(Lut::id);
+        if (self.generation == Some(generation)
+            && self.color_balance == color_balance
+            && self.lut == lut_id)
+            || inner.capture_protected
+        {
+            return false;
+        }
+
+        let frame = frames.latest();
+        if !self
+            .pipeline
+            .upload(device, queue, inner.id, &inner.alive, format, &frame, false)
+        {
+            return false;
+        }
+        drop(frame);
+
+        let bounds =
+            iced::Rectangle::with_size(iced::Size::new(self.size.0 as f32, self.size.1 as f32));
+        self.pipeline.prepare(
+            device,
+            queue,
+            inner.id,
+            &bounds,
+            &iced_wgpu::graphics::Viewport::with_physical_size(
+                iced::Size::new(self.size.0, self.size.1),
+                1.0,
+            ),
+            &DrawSettings {
+                blend: 1.0,
+                obscure: inner.content_gate.is_some(),
+                filter: if self.visualization {
+                    Filter::None
+                } else {
+                    self.filter
+                },
+                transfer,
+                tone_mapping: self.tone_mapping,
+                color_balance,
+                lut,
+                ..DrawSettings::default()
+            },
+        );
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("iced_video_player render target encoder"),
+        });
+        self.pipeline.draw(
+            &self.view,
+            &mut encoder,
+            &iced::Rectangle::with_size(iced::Size::new(self.size.0, self.size.1)),
+            inner.id,
+        );
+        queue.submit(Some(encoder.finish()));
+
+        self.generation = Some(generation);
+        self.color_balance = color_balance;
+        self.lut = lut_id;
+        true
+    }
+
+    /// The texture holding the current frame.
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    /// A view of the texture holding the current frame, for binding in a shader.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Size of the texture as `(width, height)`.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+}
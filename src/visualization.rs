@@ -0,0 +1,99 @@
+use crate::frame::FrameBuffer;
+use crate::Error;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use std::sync::Arc;
+
+/// Frames of a visualization rendered from the audio next to the video,
+/// see [`VideoBuilder::visualization_output`](crate::VideoBuilder::visualization_output).
+#[derive(Debug)]
+pub(crate) struct Visualizer {
+    pub(crate) frame: Arc<FrameBuffer>,
+    pub(crate) size: (u32, u32),
+}
+
+/// Builds an audio filter passing audio through untouched, while rendering it
+/// with the visualization plugin `name` into NV12 frames `width` by `height` big.
+pub(crate) fn visualizer(
+    name: &str,
+    (width, height): (u32, u32),
+) -> Result<(gst::Element, Visualizer), Error> {
+    // even, so the chroma plane lines up
+    let (width, height) = ((width.max(2) + 1) & !1, (height.max(2) + 1) & !1);
+
+    let tee = gst::ElementFactory::make("tee").build()?;
+    let passthrough = gst::ElementFactory::make("queue").build()?;
+    // never hold up the audio for the visuals
+    let queue = gst::ElementFactory::make("queue")
+        .property_from_str("leaky", "downstream")
+        .property("max-size-buffers", 4u32)
+        .build()?;
+    let audioconvert = gst::ElementFactory::make("audioconvert").build()?;
+    let vis = gst::ElementFactory::make(name).build()?;
+    let videoconvert = gst::ElementFactory::make("videoconvert").build()?;
+    let sink = gst_app::AppSink::builder()
+        .caps(
+            &gst::Caps::builder("video/x-raw")
+                .field("format", "NV12")
+                .field("width", width as i32)
+                .field("height", height as i32)
+                .build(),
+        )
+        .sync(true)
+        .drop(true)
+        .max_buffers(1)
+        .build();
+
+    let bin = gst::Bin::new();
+    bin.add_many([
+        &tee,
+        &passthrough,
+        &queue,
+        &audioconvert,
+        &vis,
+        &videoconvert,
+    ])?;
+    bin.add(&sink)?;
+    tee.link(&passthrough)?;
+    gst::Element::link_many([&tee, &queue, &audioconvert, &vis, &videoconvert])?;
+    videoconvert.link(&sink)?;
+
+    let sink_pad = tee.static_pad("sink").ok_or(Error::Caps)?;
+    let src_pad = passthrough.static_pad("src").ok_or(Error::Caps)?;
+    bin.add_pad(
+        &gst::GhostPad::builder_with_target(&sink_pad)?
+            .name("sink")
+            .build(),
+    )?;
+    bin.add_pad(
+        &gst::GhostPad::builder_with_target(&src_pad)?
+            .name("src")
+            .build(),
+    )?;
+
+    let (frame, mut frame_writer) =
+        FrameBuffer::new(crate::frame::PixelFormat::Nv12, (width, height));
+    sink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |sink| {
+                let sample = sink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                let map = sample
+                    .buffer_owned()
+                    .ok_or(gst::FlowError::Error)?
+                    .into_mapped_buffer_readable()
+                    .map_err(|_| gst::FlowError::Error)?;
+                frame_writer.write(map, (width, height));
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    Ok((
+        bin.upcast(),
+        Visualizer {
+            frame,
+            size: (width, height),
+        },
+    ))
+}
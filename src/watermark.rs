@@ -0,0 +1,68 @@
+use iced::widget::image as img;
+use iced::{Rectangle, Size, Vector};
+use std::time::{Duration, SystemTime};
+
+/// A corner of the video, see [`VideoPlayer::watermark`](crate::VideoPlayer::watermark).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Watermark {
+    pub(crate) image: img::Handle,
+    pub(crate) corner: Corner,
+    pub(crate) opacity: f32,
+    pub(crate) margin: f32,
+    pub(crate) drift: Option<Duration>,
+}
+
+impl Watermark {
+    /// How far the watermark has drifted from its corner right now, staying within the margin.
+    pub(crate) fn offset(&self) -> Vector {
+        let Some(period) = self.drift.filter(|period| !period.is_zero()) else {
+            return Vector::ZERO;
+        };
+        let elapsed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let phase = (elapsed.as_secs_f64() / period.as_secs_f64()).fract() * std::f64::consts::TAU;
+        // a figure eight, so no spot is covered more than the others
+        let reach = self.margin / 2.0;
+        Vector::new(
+            phase.sin() as f32 * reach,
+            (phase * 2.0).sin() as f32 * reach,
+        )
+    }
+
+    /// Where the watermark goes over a video `size` big, drawn at most a quarter of its width.
+    /// The image is `(width, height)` pixels big, shown as logical pixels.
+    pub(crate) fn bounds(
+        &self,
+        size: Size,
+        (width, height): (u32, u32),
+        offset: Vector,
+    ) -> Rectangle {
+        let scale = (size.width / 4.0 / width as f32).min(1.0);
+        let (width, height) = (width as f32 * scale, height as f32 * scale);
+        let (left, top) = (self.margin, self.margin);
+        let right = size.width - self.margin - width;
+        let bottom = size.height - self.margin - height;
+        let (x, y) = match self.corner {
+            Corner::TopLeft => (left, top),
+            Corner::TopRight => (right, top),
+            Corner::BottomLeft => (left, bottom),
+            Corner::BottomRight => (right, bottom),
+        };
+        Rectangle {
+            x: x + offset.x,
+            y: y + offset.y,
+            width,
+            height,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+struct Uniforms {
+    // left, top, right, bottom in clip space
+    rect: vec4<f32>,
+    opacity: f32,
+}
+
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s: sampler;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
+    // triangle strip: top-left, top-right, bottom-left, bottom-right
+    let uv = vec2<f32>(f32(in_vertex_index & 1u), f32(in_vertex_index >> 1u));
+
+    var out: VertexOutput;
+    out.uv = uv;
+    out.position = vec4<f32>(mix(uniforms.rect.xy, uniforms.rect.zw, uv), 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // the texture is sRGB, so this is linear
+    let color = textureSample(tex, s, in.uv);
+    let rgb = select(srgb_encode(color.rgb), color.rgb, LINEAR_OUTPUT);
+    return vec4<f32>(rgb, color.a * uniforms.opacity);
+}
+
+// sRGB encoding, for targets that don't encode on write
+fn srgb_encode(linear: vec3<f32>) -> vec3<f32> {
+    let lo = linear * 12.92;
+    let hi = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(hi, lo, linear <= vec3<f32>(0.0031308));
+}